@@ -13,13 +13,12 @@
 use distributed_task_queue::{
     Task, TaskClient, TaskScheduler, 
     scheduler::{ScheduleExpression, ScheduledJob},
-    TaskResult, TaskError
+    TaskError
 };
 use chrono::{Duration, Utc};
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 use tracing::{info, Level};
-use tracing_subscriber;
 
 /// A task that logs a message
 #[derive(Debug, Serialize, Deserialize)]
@@ -266,8 +265,8 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     info!("Starting scheduler... (will run for 60 seconds)");
     
     // Start scheduler in background
+    let scheduler = Arc::new(scheduler);
     let scheduler_handle = {
-        let scheduler = Arc::new(scheduler);
         let scheduler_clone = scheduler.clone();
         
         tokio::spawn(async move {
@@ -292,12 +291,14 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 }
 
 /// Example of creating a custom schedule expression
+#[allow(dead_code)]
 fn create_custom_schedule() -> ScheduleExpression {
     // Run every 5 minutes
     ScheduleExpression::EveryMinutes(5)
 }
 
 /// Example of a more complex scheduled job setup
+#[allow(dead_code)]
 async fn setup_maintenance_jobs(scheduler: &TaskScheduler) -> Result<(), Box<dyn std::error::Error>> {
     // Database backup every day at 3 AM
     let backup_task = LogTask {
@@ -11,12 +11,11 @@
 
 use distributed_task_queue::{
     Task, TaskClient, TaskQueue, TaskQueueConfig, Worker, WorkerConfig,
-    worker::TaskHandler, TaskResult, TaskError
+    worker::{TaskHandler, TypedTaskHandler}, TaskResult, TaskError
 };
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 use tracing::{info, Level};
-use tracing_subscriber;
 
 /// A simple math task that adds two numbers
 #[derive(Debug, Serialize, Deserialize)]
@@ -85,23 +84,7 @@ impl TaskHandler for AddTaskHandler {
 
     async fn handle(&self, task_data: &str) -> TaskResult<String> {
         let task: AddTask = serde_json::from_str(task_data)?;
-        let result = task.execute().await?;
-        Ok(serde_json::to_string(&result)?)
-    }
-}
-
-/// A task handler for MessageTask
-struct MessageTaskHandler;
-
-#[async_trait::async_trait]
-impl TaskHandler for MessageTaskHandler {
-    fn can_handle(&self, task_name: &str) -> bool {
-        task_name == "MessageTask"
-    }
-
-    async fn handle(&self, task_data: &str) -> TaskResult<String> {
-        let task: MessageTask = serde_json::from_str(task_data)?;
-        let result = task.execute().await?;
+        let result = task.execute_validated().await?;
         Ok(serde_json::to_string(&result)?)
     }
 }
@@ -138,7 +121,10 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     // Register task handlers
     worker.register_handler("AddTask".to_string(), AddTaskHandler).await;
-    worker.register_handler("MessageTask".to_string(), MessageTaskHandler).await;
+    // Or let the task's own `Task` impl do the work
+    worker
+        .register_handler("MessageTask".to_string(), TypedTaskHandler::<MessageTask>::new("MessageTask"))
+        .await;
 
     // Submit some test tasks first (you can also run the task_client example)
     let client = TaskClient::from_queue(queue);
@@ -170,8 +156,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     info!("Starting worker...");
     
-    // Start the worker (this will run until interrupted)
-    // In a real application, you might want to handle shutdown signals
+    // Start the worker (runs until Ctrl+C, then drains active tasks)
     if let Err(e) = worker.start().await {
         eprintln!("Worker error: {}", e);
     }
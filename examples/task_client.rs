@@ -13,11 +13,10 @@
 //! 3. Run: cargo run --example task_client
 
 use distributed_task_queue::{
-    Task, TaskClient, TaskPriority, TaskResult, TaskError
+    Task, TaskClient, TaskPriority, TaskError
 };
 use serde::{Deserialize, Serialize};
 use tracing::{info, Level};
-use tracing_subscriber;
 
 /// A simple computation task
 #[derive(Debug, Serialize, Deserialize)]
@@ -39,12 +38,13 @@ impl Task for ComputeTask {
             "multiply" => Ok(self.x * self.y),
             "divide" => {
                 if self.y == 0.0 {
-                    Err(TaskError::task_execution("Division by zero"))
+                    // Retrying won't help, so fail the task straight away
+                    Err(TaskError::permanent("Division by zero"))
                 } else {
                     Ok(self.x / self.y)
                 }
             }
-            _ => Err(TaskError::task_execution(format!("Unknown operation: {}", self.operation))),
+            _ => Err(TaskError::permanent(format!("Unknown operation: {}", self.operation))),
         }
     }
 
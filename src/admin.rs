@@ -0,0 +1,75 @@
+//! Queue inspection and maintenance for admin tools
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::error::TaskResult;
+use crate::queue::{QueueInfo, TaskQueue};
+use crate::task::{TaskDefinition, TaskId, TaskStatus};
+
+/// The inspection and maintenance calls a CLI or admin panel needs
+///
+/// A thin layer over `TaskQueue`; every method maps to one or a few queue
+/// calls.
+///
+/// ```no_run
+/// use std::sync::Arc;
+/// use distributed_task_queue::{QueueAdmin, TaskQueue};
+///
+/// # async fn run() -> distributed_task_queue::TaskResult<()> {
+/// let admin = QueueAdmin::new(Arc::new(TaskQueue::new_default().await?));
+/// for (queue, depth) in admin.queue_depths().await? {
+///     println!("{}: {} pending", queue, depth);
+/// }
+/// for task in admin.failed_tasks("emails", 20).await? {
+///     admin.retry_failed(task.id).await?;
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct QueueAdmin {
+    queue: Arc<TaskQueue>,
+}
+
+impl QueueAdmin {
+    /// Create an admin over a queue
+    pub fn new(queue: Arc<TaskQueue>) -> Self {
+        Self { queue }
+    }
+
+    /// The underlying queue
+    pub fn queue(&self) -> &Arc<TaskQueue> {
+        &self.queue
+    }
+
+    /// Every known queue and whether it is paused
+    pub async fn queues(&self) -> TaskResult<Vec<QueueInfo>> {
+        self.queue.list_queues().await
+    }
+
+    /// Number of pending tasks in every known queue
+    pub async fn queue_depths(&self) -> TaskResult<HashMap<String, u64>> {
+        let mut depths = HashMap::new();
+        for info in self.queue.list_queues().await? {
+            let stats = self.queue.get_stats(&info.name).await?;
+            depths.insert(info.name, stats.pending_tasks);
+        }
+        Ok(depths)
+    }
+
+    /// The most recently failed tasks of a queue, newest first
+    pub async fn failed_tasks(&self, queue_name: &str, limit: usize) -> TaskResult<Vec<TaskDefinition>> {
+        self.queue.list_tasks_by_status(queue_name, TaskStatus::Failed, limit).await
+    }
+
+    /// Re-submit a failed task; returns `false` if it has not failed
+    pub async fn retry_failed(&self, task_id: TaskId) -> TaskResult<bool> {
+        self.queue.retry_failed_task(task_id).await
+    }
+
+    /// Remove every pending task from a queue, returning how many were removed
+    pub async fn purge(&self, queue_name: &str) -> TaskResult<u64> {
+        self.queue.purge_queue(queue_name).await
+    }
+}
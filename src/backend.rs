@@ -0,0 +1,392 @@
+//! Pluggable storage for pending, running and scheduled tasks
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use std::cmp::Ordering;
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::sync::{Mutex, MutexGuard};
+use std::time::Duration;
+use tokio::sync::Notify;
+
+use crate::error::TaskResult;
+use crate::queue::QueueStats;
+use crate::result_backend::ResultBackend;
+use crate::task::{TaskDefinition, TaskId, TaskStatus};
+
+/// Where a `TaskQueue` created with `TaskQueue::from_backend` keeps its tasks
+///
+/// Covers what workers, clients and schedulers need: submitting, claiming,
+/// finishing and cancelling tasks, delayed tasks, progress, statistics and
+/// scheduled job state. `TaskQueue` takes care of defaults, scoring, events
+/// and result compression, so implementations only store. Redis-specific
+/// features (pausing queues, groups, status indexes, idempotency keys,
+/// resources, heartbeats and reclaiming) are not available on a backend.
+#[async_trait]
+pub trait QueueBackend: Send + Sync + std::fmt::Debug {
+    /// Add a task to its queue, also storing its record
+    ///
+    /// Tasks with higher scores are dequeued first, and tasks with equal
+    /// scores in the order they were enqueued. With `only_new`, nothing is
+    /// stored and `false` returned if a task with the same id exists.
+    async fn enqueue(&self, task_def: &TaskDefinition, score: f64, only_new: bool) -> TaskResult<bool>;
+
+    /// Hold a task, also storing its record, until `take_due_tasks` hands it
+    /// back at `due_at`
+    async fn schedule(&self, task_def: &TaskDefinition, due_at: DateTime<Utc>) -> TaskResult<()>;
+
+    /// Remove and return up to `max` held tasks due at `now`, longest due first
+    async fn take_due_tasks(&self, now: DateTime<Utc>, max: usize) -> TaskResult<Vec<TaskDefinition>>;
+
+    /// Claim the first task of a queue, moving it to the processing set
+    async fn dequeue(&self, queue_name: &str) -> TaskResult<Option<TaskDefinition>>;
+
+    /// Wait until a task may have been enqueued, or at most `timeout`
+    ///
+    /// Returning early without a new task is fine; callers dequeue again.
+    async fn wait_for_task(&self, timeout: Duration) {
+        tokio::time::sleep(timeout).await;
+    }
+
+    /// Store the current state of a task's record
+    async fn save_task(&self, task_def: &TaskDefinition) -> TaskResult<()>;
+
+    /// Get a task's record
+    async fn get_task(&self, task_id: TaskId) -> TaskResult<Option<TaskDefinition>>;
+
+    /// Record a task that finished with `Success` or `Failed`, or was
+    /// `Cancelled` on dequeue because it expired
+    ///
+    /// Removes it from the processing set, stores its record and counts it
+    /// in its queue's completed, failed or expired total.
+    async fn finish_task(&self, task_def: &TaskDefinition) -> TaskResult<()>;
+
+    /// Remove a task from the processing set
+    async fn remove_from_processing(&self, task_id: TaskId) -> TaskResult<()>;
+
+    /// Remove a pending or held task from its queue, returning whether it
+    /// was waiting
+    async fn remove_waiting(&self, task_def: &TaskDefinition) -> TaskResult<bool>;
+
+    /// Remove every pending task of a queue with its record, returning how
+    /// many were removed
+    async fn purge_queue(&self, queue_name: &str) -> TaskResult<u64>;
+
+    /// Names of the queues with pending tasks, sorted
+    async fn queue_names(&self) -> TaskResult<Vec<String>>;
+
+    /// Statistics of a queue (`processing_tasks` counts every queue)
+    async fn stats(&self, queue_name: &str) -> TaskResult<QueueStats>;
+
+    /// Store a running task's progress
+    async fn set_progress(&self, task_id: TaskId, percent: u8, message: Option<&str>) -> TaskResult<()>;
+
+    /// Get the last progress a task reported
+    async fn get_progress(&self, task_id: TaskId) -> TaskResult<Option<(u8, Option<String>)>>;
+
+    /// Persist a scheduled job's serialized state
+    async fn store_job(&self, job_id: &str, job_json: &str) -> TaskResult<()>;
+
+    /// Delete a persisted scheduled job
+    async fn remove_job(&self, job_id: &str) -> TaskResult<()>;
+
+    /// Every persisted scheduled job
+    async fn load_jobs(&self) -> TaskResult<Vec<String>>;
+}
+
+/// Keeps every task in process memory, for tests and examples
+///
+/// Also stores results (it is a `ResultBackend`). Nothing expires: finished
+/// tasks and results are kept for the life of the backend, and nothing
+/// survives a restart.
+///
+/// ```
+/// use distributed_task_queue::{TaskQueue, TaskQueueConfig};
+///
+/// let queue = TaskQueue::new_in_memory(TaskQueueConfig::default())?;
+/// # Ok::<(), distributed_task_queue::TaskError>(())
+/// ```
+#[derive(Debug, Default)]
+pub struct MemoryBackend {
+    state: Mutex<MemoryState>,
+    task_enqueued: Notify,
+}
+
+#[derive(Debug, Default)]
+struct MemoryState {
+    /// Pending tasks of each queue, in dequeue order
+    queues: HashMap<String, BTreeSet<QueueEntry>>,
+    /// Queue entry of each pending task
+    pending: HashMap<TaskId, QueueEntry>,
+    /// Held tasks by due time, then hold order
+    scheduled: BTreeSet<(DateTime<Utc>, u64, TaskId)>,
+    processing: HashSet<TaskId>,
+    tasks: HashMap<TaskId, TaskDefinition>,
+    results: HashMap<TaskId, TaskDefinition>,
+    /// Completed and failed totals of each queue
+    finished: HashMap<String, (u64, u64, u64)>,
+    progress: HashMap<TaskId, (u8, Option<String>)>,
+    jobs: HashMap<String, String>,
+    /// Insertion counter keeping equal scores and due times in order
+    sequence: u64,
+}
+
+/// A pending task's place in its queue: highest score first, then FIFO
+#[derive(Debug, Clone, Copy)]
+struct QueueEntry {
+    score: f64,
+    sequence: u64,
+    task_id: TaskId,
+}
+
+impl PartialEq for QueueEntry {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for QueueEntry {}
+
+impl PartialOrd for QueueEntry {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for QueueEntry {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other
+            .score
+            .total_cmp(&self.score)
+            .then(self.sequence.cmp(&other.sequence))
+    }
+}
+
+impl MemoryState {
+    fn next_sequence(&mut self) -> u64 {
+        self.sequence += 1;
+        self.sequence
+    }
+
+    /// Take a task out of its queue or the held set, if it is waiting
+    fn unlink_waiting(&mut self, task_def: &TaskDefinition) -> bool {
+        if let Some(entry) = self.pending.remove(&task_def.id) {
+            if let Some(queue) = self.queues.get_mut(&task_def.queue) {
+                queue.remove(&entry);
+            }
+            return true;
+        }
+        let held = self
+            .scheduled
+            .iter()
+            .find(|(_, _, task_id)| *task_id == task_def.id)
+            .copied();
+        match held {
+            Some(held) => self.scheduled.remove(&held),
+            None => false,
+        }
+    }
+}
+
+impl MemoryBackend {
+    /// Create an empty backend
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn state(&self) -> MutexGuard<'_, MemoryState> {
+        self.state.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+#[async_trait]
+impl QueueBackend for MemoryBackend {
+    async fn enqueue(&self, task_def: &TaskDefinition, score: f64, only_new: bool) -> TaskResult<bool> {
+        {
+            let mut state = self.state();
+            if only_new && (state.tasks.contains_key(&task_def.id) || state.results.contains_key(&task_def.id)) {
+                return Ok(false);
+            }
+
+            state.unlink_waiting(task_def);
+            let entry = QueueEntry {
+                score,
+                sequence: state.next_sequence(),
+                task_id: task_def.id,
+            };
+            state.queues.entry(task_def.queue.clone()).or_default().insert(entry);
+            state.pending.insert(task_def.id, entry);
+            state.tasks.insert(task_def.id, task_def.clone());
+        }
+        self.task_enqueued.notify_waiters();
+        Ok(true)
+    }
+
+    async fn schedule(&self, task_def: &TaskDefinition, due_at: DateTime<Utc>) -> TaskResult<()> {
+        let mut state = self.state();
+        state.unlink_waiting(task_def);
+        let sequence = state.next_sequence();
+        state.scheduled.insert((due_at, sequence, task_def.id));
+        state.tasks.insert(task_def.id, task_def.clone());
+        Ok(())
+    }
+
+    async fn take_due_tasks(&self, now: DateTime<Utc>, max: usize) -> TaskResult<Vec<TaskDefinition>> {
+        let mut state = self.state();
+        let due: Vec<_> = state
+            .scheduled
+            .iter()
+            .take_while(|(due_at, _, _)| *due_at <= now)
+            .take(max)
+            .copied()
+            .collect();
+
+        let mut tasks = Vec::with_capacity(due.len());
+        for held in due {
+            state.scheduled.remove(&held);
+            if let Some(task_def) = state.tasks.get(&held.2) {
+                tasks.push(task_def.clone());
+            }
+        }
+        Ok(tasks)
+    }
+
+    async fn dequeue(&self, queue_name: &str) -> TaskResult<Option<TaskDefinition>> {
+        let mut state = self.state();
+        let Some(entry) = state.queues.get_mut(queue_name).and_then(|queue| queue.pop_first()) else {
+            return Ok(None);
+        };
+        state.pending.remove(&entry.task_id);
+        state.processing.insert(entry.task_id);
+        Ok(state.tasks.get(&entry.task_id).cloned())
+    }
+
+    async fn wait_for_task(&self, timeout: Duration) {
+        let _ = tokio::time::timeout(timeout, self.task_enqueued.notified()).await;
+    }
+
+    async fn save_task(&self, task_def: &TaskDefinition) -> TaskResult<()> {
+        self.state().tasks.insert(task_def.id, task_def.clone());
+        Ok(())
+    }
+
+    async fn get_task(&self, task_id: TaskId) -> TaskResult<Option<TaskDefinition>> {
+        Ok(self.state().tasks.get(&task_id).cloned())
+    }
+
+    async fn finish_task(&self, task_def: &TaskDefinition) -> TaskResult<()> {
+        let mut state = self.state();
+        state.processing.remove(&task_def.id);
+        state.tasks.insert(task_def.id, task_def.clone());
+        let (completed, failed, expired) = state.finished.entry(task_def.queue.clone()).or_default();
+        match task_def.status {
+            TaskStatus::Success => *completed += 1,
+            TaskStatus::Failed => *failed += 1,
+            TaskStatus::Cancelled => *expired += 1,
+            _ => {}
+        }
+        Ok(())
+    }
+
+    async fn remove_from_processing(&self, task_id: TaskId) -> TaskResult<()> {
+        self.state().processing.remove(&task_id);
+        Ok(())
+    }
+
+    async fn remove_waiting(&self, task_def: &TaskDefinition) -> TaskResult<bool> {
+        Ok(self.state().unlink_waiting(task_def))
+    }
+
+    async fn purge_queue(&self, queue_name: &str) -> TaskResult<u64> {
+        let mut state = self.state();
+        let purged = state.queues.remove(queue_name).unwrap_or_default();
+        for entry in &purged {
+            state.pending.remove(&entry.task_id);
+            state.tasks.remove(&entry.task_id);
+        }
+        Ok(purged.len() as u64)
+    }
+
+    async fn queue_names(&self) -> TaskResult<Vec<String>> {
+        let mut names: Vec<String> = self
+            .state()
+            .queues
+            .iter()
+            .filter(|(_, queue)| !queue.is_empty())
+            .map(|(queue_name, _)| queue_name.clone())
+            .collect();
+        names.sort();
+        Ok(names)
+    }
+
+    async fn stats(&self, queue_name: &str) -> TaskResult<QueueStats> {
+        let state = self.state();
+        let (completed_tasks, failed_tasks, expired_tasks) = state.finished.get(queue_name).copied().unwrap_or_default();
+        let in_queue = |task_id: &TaskId| {
+            state
+                .tasks
+                .get(task_id)
+                .is_some_and(|task_def| task_def.queue == queue_name)
+        };
+
+        Ok(QueueStats {
+            pending_tasks: state.queues.get(queue_name).map_or(0, |queue| queue.len()) as u64,
+            processing_tasks: state.processing.len() as u64,
+            completed_tasks,
+            failed_tasks,
+            expired_tasks,
+            scheduled_tasks: state.scheduled.iter().filter(|(_, _, task_id)| in_queue(task_id)).count() as u64,
+            stored_results: state
+                .results
+                .values()
+                .filter(|task_def| task_def.queue == queue_name && task_def.status == TaskStatus::Success)
+                .count() as u64,
+        })
+    }
+
+    async fn set_progress(&self, task_id: TaskId, percent: u8, message: Option<&str>) -> TaskResult<()> {
+        self.state()
+            .progress
+            .insert(task_id, (percent, message.map(str::to_string)));
+        Ok(())
+    }
+
+    async fn get_progress(&self, task_id: TaskId) -> TaskResult<Option<(u8, Option<String>)>> {
+        Ok(self.state().progress.get(&task_id).cloned())
+    }
+
+    async fn store_job(&self, job_id: &str, job_json: &str) -> TaskResult<()> {
+        self.state().jobs.insert(job_id.to_string(), job_json.to_string());
+        Ok(())
+    }
+
+    async fn remove_job(&self, job_id: &str) -> TaskResult<()> {
+        self.state().jobs.remove(job_id);
+        Ok(())
+    }
+
+    async fn load_jobs(&self) -> TaskResult<Vec<String>> {
+        Ok(self.state().jobs.values().cloned().collect())
+    }
+}
+
+#[async_trait]
+impl ResultBackend for MemoryBackend {
+    async fn store_result(&self, task_def: &TaskDefinition) -> TaskResult<()> {
+        self.state().results.insert(task_def.id, task_def.clone());
+        Ok(())
+    }
+
+    async fn store_failure(&self, task_def: &TaskDefinition) -> TaskResult<()> {
+        self.state().results.insert(task_def.id, task_def.clone());
+        Ok(())
+    }
+
+    async fn get_result(&self, task_id: TaskId) -> TaskResult<Option<TaskDefinition>> {
+        Ok(self.state().results.get(&task_id).cloned())
+    }
+
+    async fn remove_result(&self, task_id: TaskId) -> TaskResult<()> {
+        self.state().results.remove(&task_id);
+        Ok(())
+    }
+}
@@ -1,54 +1,302 @@
 //! Client interface for submitting tasks to the queue
 
 use chrono::{DateTime, Utc};
-use serde::Serialize;
-use std::sync::Arc;
+use futures_util::future::join_all;
+use futures_util::{Stream, StreamExt};
+use redis::aio::PubSub;
+use std::collections::{HashMap, HashSet};
+use std::marker::PhantomData;
+use std::sync::{Arc, RwLock as StdRwLock};
+use std::time::{Duration, Instant};
+use tracing::warn;
 
 use crate::error::{TaskError, TaskResult};
-use crate::queue::{TaskQueue, TaskQueueConfig};
-use crate::task::{Task, TaskDefinition, TaskId, TaskPriority, TaskStatus};
+use crate::events::EventListener;
+use crate::group::{GroupId, TaskGroup};
+use crate::queue::{QueueInfo, SubmitOutcome, TaskQueue, TaskQueueConfig};
+use crate::routing::{DefaultRoutingPolicy, RoutingPolicy};
+use crate::task::{RetryConfig, SerializableTask, TaskDefinition, TaskId, TaskPriority, TaskStatus};
+
+/// How often to poll task status when pub/sub is unavailable
+const RESULT_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Longest wait for a completion event before re-checking the stored status
+const RESULT_EVENT_RECHECK: Duration = Duration::from_secs(5);
+
+/// How often `submit_when_room` retries a submission to a full queue
+const QUEUE_FULL_RETRY_INTERVAL: Duration = Duration::from_millis(100);
+
+/// State of a `TaskClient::submit_and_watch` stream
+struct StatusWatch {
+    task_id: TaskId,
+    pubsub: Option<PubSub>,
+    deadline: Option<Instant>,
+    /// Status most recently yielded
+    last: Option<TaskStatus>,
+    finished: bool,
+}
+
+/// Task names that `TaskClient::submit_checked` accepts
+///
+/// Clones share the same set of names, so names registered after the registry
+/// was handed to a client are still seen by it. Fill it from the names
+/// workers handle with `Worker::task_names`.
+///
+/// ```
+/// use distributed_task_queue::client::KnownTasks;
+///
+/// let known: KnownTasks = ["EmailTask", "ResizeImage"].into_iter().collect();
+/// known.register("GenerateReport");
+/// assert!(known.contains("ResizeImage"));
+/// assert!(!known.contains("EmialTask"));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct KnownTasks {
+    names: Arc<StdRwLock<HashSet<String>>>,
+}
+
+impl KnownTasks {
+    /// Create an empty registry
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Accept tasks named `name`
+    pub fn register<S: Into<String>>(&self, name: S) {
+        self.names
+            .write()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .insert(name.into());
+    }
+
+    /// Whether tasks named `name` are accepted
+    pub fn contains(&self, name: &str) -> bool {
+        self.names
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .contains(name)
+    }
+
+    /// Registered names, sorted
+    pub fn names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self
+            .names
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .iter()
+            .cloned()
+            .collect();
+        names.sort();
+        names
+    }
+}
+
+impl<S: Into<String>> FromIterator<S> for KnownTasks {
+    fn from_iter<I: IntoIterator<Item = S>>(iter: I) -> Self {
+        let known = Self::new();
+        for name in iter {
+            known.register(name);
+        }
+        known
+    }
+}
 
 /// Client for submitting tasks to the distributed task queue
 #[derive(Debug)]
 pub struct TaskClient {
     queue: Arc<TaskQueue>,
+    routing: Arc<dyn RoutingPolicy>,
+    known_tasks: Option<KnownTasks>,
 }
 
 impl TaskClient {
     /// Create a new task client with the given queue configuration
     pub async fn new(config: TaskQueueConfig) -> TaskResult<Self> {
         let queue = Arc::new(TaskQueue::new(config).await?);
-        Ok(Self { queue })
+        Ok(Self::from_queue(queue))
     }
 
     /// Create a new task client with default configuration
     pub async fn new_default() -> TaskResult<Self> {
         let queue = Arc::new(TaskQueue::new_default().await?);
-        Ok(Self { queue })
+        Ok(Self::from_queue(queue))
     }
 
     /// Create a task client from an existing queue
     pub fn from_queue(queue: Arc<TaskQueue>) -> Self {
-        Self { queue }
+        Self {
+            queue,
+            routing: Arc::new(DefaultRoutingPolicy),
+            known_tasks: None,
+        }
+    }
+
+    /// Pick destination queues with `policy` instead of using the queue
+    /// names passed to the submit methods as-is
+    ///
+    /// Applies to every submission except `submit_built`, whose definition
+    /// is submitted unchanged.
+    pub fn with_routing_policy<P>(mut self, policy: P) -> Self
+    where
+        P: RoutingPolicy + 'static,
+    {
+        self.routing = Arc::new(policy);
+        self
+    }
+
+    /// Set the task's destination queue according to the routing policy
+    fn route(&self, mut task_def: TaskDefinition) -> TaskDefinition {
+        task_def.queue = self.routing.route(&task_def);
+        task_def
     }
 
     /// Submit a task to the default queue
     pub async fn submit<T>(&self, task: &T) -> TaskResult<TaskId>
     where
-        T: Task + Serialize,
+        T: SerializableTask,
     {
         self.submit_to_queue(task, "default").await
     }
 
     /// Submit a task to a specific queue
+    ///
+    /// If the task has an idempotency key and a task with the same key is
+    /// still pending or running, the existing task's id is returned.
     pub async fn submit_to_queue<T>(&self, task: &T, queue_name: &str) -> TaskResult<TaskId>
     where
-        T: Task + Serialize,
+        T: SerializableTask,
     {
-        let task_def = TaskDefinition::new(task, queue_name.to_string())?;
+        let task_def = self.route(TaskDefinition::new(task, queue_name.to_string())?);
         self.queue.submit_task(task_def).await
     }
 
+    /// Submit a task, waiting up to `timeout` for room in a full queue
+    ///
+    /// Like `submit_to_queue`, but a queue at `TaskQueueConfig::max_queue_depth`
+    /// is retried until it has room instead of failing straight away. Fails
+    /// with `TaskError::QueueFull` if it is still full after `timeout`.
+    ///
+    /// ```
+    /// use std::sync::Arc;
+    /// use std::time::Duration;
+    /// use distributed_task_queue::{Task, TaskClient, TaskError, TaskQueue, TaskQueueConfig};
+    ///
+    /// #[derive(Debug, serde::Serialize, serde::Deserialize)]
+    /// struct Job;
+    ///
+    /// #[async_trait::async_trait]
+    /// impl Task for Job {
+    ///     type Output = ();
+    ///     type Error = TaskError;
+    ///     async fn execute(&self) -> Result<(), TaskError> { Ok(()) }
+    /// }
+    ///
+    /// # tokio_test::block_on(async {
+    /// let queue = Arc::new(TaskQueue::new_in_memory(TaskQueueConfig { max_queue_depth: Some(1), ..Default::default() })?);
+    /// let client = TaskClient::from_queue(queue.clone());
+    /// client.submit_to_queue(&Job, "jobs").await?;
+    /// assert!(matches!(client.submit_to_queue(&Job, "jobs").await, Err(TaskError::QueueFull { depth: 1, .. })));
+    ///
+    /// // A worker makes room shortly
+    /// tokio::spawn(async move {
+    ///     tokio::time::sleep(Duration::from_millis(200)).await;
+    ///     queue.get_next_task("jobs").await
+    /// });
+    /// client.submit_when_room(&Job, "jobs", Duration::from_secs(5)).await?;
+    /// # Ok::<(), TaskError>(())
+    /// # })?;
+    /// # Ok::<(), TaskError>(())
+    /// ```
+    pub async fn submit_when_room<T>(&self, task: &T, queue_name: &str, timeout: Duration) -> TaskResult<TaskId>
+    where
+        T: SerializableTask,
+    {
+        let task_def = self.route(TaskDefinition::new(task, queue_name.to_string())?);
+        let deadline = Instant::now() + timeout;
+        loop {
+            match self.queue.submit_task(task_def.clone()).await {
+                Err(TaskError::QueueFull { .. }) if Instant::now() < deadline => {
+                    let remaining = deadline.saturating_duration_since(Instant::now());
+                    tokio::time::sleep(remaining.min(QUEUE_FULL_RETRY_INTERVAL)).await;
+                }
+                outcome => return outcome,
+            }
+        }
+    }
+
+    /// Check task names against `known` in `submit_checked`
+    pub fn with_known_tasks(mut self, known: KnownTasks) -> Self {
+        self.known_tasks = Some(known);
+        self
+    }
+
+    /// Submit a task to a specific queue if its name is a known task
+    ///
+    /// Fails with `TaskError::Config` when the client has no `KnownTasks`
+    /// registry or the task's `name()` is not in it, so a task no worker
+    /// handles never reaches the queue.
+    pub async fn submit_checked<T>(&self, task: &T, queue_name: &str) -> TaskResult<TaskId>
+    where
+        T: SerializableTask,
+    {
+        let known = self
+            .known_tasks
+            .as_ref()
+            .ok_or_else(|| TaskError::config("submit_checked needs a KnownTasks registry"))?;
+        if !known.contains(task.name()) {
+            return Err(TaskError::config(format!("no handler is known for task {}", task.name())));
+        }
+        self.submit_to_queue(task, queue_name).await
+    }
+
+    /// Submit a task under an application-supplied id
+    ///
+    /// Fails with `TaskError::TaskExists` if a task with that id is pending,
+    /// processing or still has a stored result.
+    pub async fn submit_with_id<T>(&self, task: &T, queue_name: &str, task_id: TaskId) -> TaskResult<TaskId>
+    where
+        T: SerializableTask,
+    {
+        let task_def = self.route(TaskDefinition::with_id(task, queue_name.to_string(), task_id)?);
+        Ok(self.queue.submit_new_task(task_def).await?.task_id())
+    }
+
+    /// Submit a task to a specific queue, reporting whether it was deduplicated
+    pub async fn submit_with_outcome<T>(&self, task: &T, queue_name: &str) -> TaskResult<SubmitOutcome>
+    where
+        T: SerializableTask,
+    {
+        let task_def = self.route(TaskDefinition::new(task, queue_name.to_string())?);
+        self.queue.submit_task_with_outcome(task_def).await
+    }
+
+    /// Submit a task to the default queue and get a handle to its result
+    pub async fn submit_handle<T>(&self, task: &T) -> TaskResult<TaskHandle<T::Output>>
+    where
+        T: SerializableTask,
+    {
+        self.submit_handle_to_queue(task, "default").await
+    }
+
+    /// Submit a task to a specific queue and get a handle to its result
+    ///
+    /// Deduplicated like `submit_to_queue`; the handle then refers to the
+    /// existing task.
+    pub async fn submit_handle_to_queue<T>(&self, task: &T, queue_name: &str) -> TaskResult<TaskHandle<T::Output>>
+    where
+        T: SerializableTask,
+    {
+        let task_def = self.route(TaskDefinition::new(task, queue_name.to_string())?);
+        let queue = task_def.queue.clone();
+        let task_id = self.queue.submit_task(task_def).await?;
+        Ok(TaskHandle {
+            task_id,
+            queue,
+            client: TaskClient::from_queue(self.queue.clone()),
+            _output: PhantomData,
+        })
+    }
+
     /// Submit a task with custom priority
     pub async fn submit_with_priority<T>(
         &self,
@@ -57,11 +305,11 @@ impl TaskClient {
         priority: TaskPriority,
     ) -> TaskResult<TaskId>
     where
-        T: Task + Serialize,
+        T: SerializableTask,
     {
         let mut task_def = TaskDefinition::new(task, queue_name.to_string())?;
-        task_def.priority = priority;
-        self.queue.submit_task(task_def).await
+        task_def.set_priority(priority);
+        self.queue.submit_task(self.route(task_def)).await
     }
 
     /// Submit a task to be executed at a specific time
@@ -72,9 +320,9 @@ impl TaskClient {
         scheduled_at: DateTime<Utc>,
     ) -> TaskResult<TaskId>
     where
-        T: Task + Serialize,
+        T: SerializableTask,
     {
-        let task_def = TaskDefinition::new_scheduled(task, queue_name.to_string(), scheduled_at)?;
+        let task_def = self.route(TaskDefinition::new_scheduled(task, queue_name.to_string(), scheduled_at)?);
         self.queue.submit_scheduled_task(task_def).await
     }
 
@@ -86,22 +334,73 @@ impl TaskClient {
         delay_seconds: u64,
     ) -> TaskResult<TaskId>
     where
-        T: Task + Serialize,
+        T: SerializableTask,
     {
         let scheduled_at = Utc::now() + chrono::Duration::seconds(delay_seconds as i64);
         self.submit_at(task, queue_name, scheduled_at).await
     }
 
+    /// Submit a task with a raw byte payload (see `TaskDefinition::from_bytes`)
+    ///
+    /// Requires the `binary` feature. Read the output back from
+    /// `TaskDefinition::result_bytes` of the finished task.
+    pub async fn submit_bytes(&self, task_name: &str, data: &[u8], queue_name: &str) -> TaskResult<TaskId> {
+        let task_def = self.route(TaskDefinition::from_bytes(task_name, data, queue_name.to_string())?);
+        self.queue.submit_task(task_def).await
+    }
+
+    /// Submit a task by name with its payload already serialized as JSON
+    ///
+    /// For gateways forwarding tasks from other services without linking
+    /// the task types (see `TaskDefinition::from_raw`). Fails with
+    /// `TaskError::Config` if `data` is empty.
+    pub async fn submit_raw(
+        &self,
+        task_name: &str,
+        data: String,
+        queue_name: &str,
+        priority: TaskPriority,
+    ) -> TaskResult<TaskId> {
+        if data.trim().is_empty() {
+            return Err(TaskError::config(format!("raw task {} has no data", task_name)));
+        }
+        let mut task_def = TaskDefinition::from_raw(task_name, data, queue_name.to_string());
+        task_def.set_priority(priority);
+        self.queue.submit_task(self.route(task_def)).await
+    }
+
+    /// Submit a fully built task definition (see `TaskDefinition::builder`)
+    ///
+    /// Definitions with `scheduled_at` set are scheduled, others enqueued.
+    pub async fn submit_built(&self, task_def: TaskDefinition) -> TaskResult<TaskId> {
+        if task_def.scheduled_at.is_some() {
+            self.queue.submit_scheduled_task(task_def).await
+        } else {
+            self.queue.submit_task(task_def).await
+        }
+    }
+
     /// Submit a task with custom configuration
     pub async fn submit_with_config<T>(&self, task_config: TaskSubmissionConfig<'_, T>) -> TaskResult<TaskId>
     where
-        T: Task + Serialize,
+        T: SerializableTask,
     {
         let mut task_def = TaskDefinition::new(task_config.task, task_config.queue.to_string())?;
         
         if let Some(priority) = task_config.priority {
-            task_def.priority = priority;
+            task_def.set_priority(priority);
+        }
+
+        task_def.callback_url = task_config.callback_url;
+        task_def.expires_at = task_config.expires_at;
+        task_def.store_result = task_config.store_result;
+        task_def.result_ttl = task_config.result_ttl;
+
+        if let Some(retry_config) = task_config.retry_config {
+            task_def.set_retry_config(retry_config);
         }
+
+        let mut task_def = self.route(task_def);
         
         if let Some(scheduled_at) = task_config.scheduled_at {
             task_def.scheduled_at = Some(scheduled_at);
@@ -112,56 +411,286 @@ impl TaskClient {
         }
     }
 
+    /// Submit a group of tasks to a queue, returning the group's id
+    ///
+    /// The group's finalizer, if any, goes to the same queue once every
+    /// member has finished. Members are routed like other submissions; the
+    /// finalizer is not.
+    pub async fn submit_group(&self, mut group: TaskGroup, queue_name: &str) -> TaskResult<GroupId> {
+        group.set_queue(queue_name);
+        group.members = group
+            .members
+            .into_iter()
+            .map(|task_def| self.route(task_def))
+            .collect();
+        self.queue.submit_group(group).await
+    }
+
+    /// Register a listener for task lifecycle events
+    ///
+    /// Listeners belong to the underlying queue, so they also see the events
+    /// of workers sharing it.
+    pub async fn add_event_listener<L>(&self, listener: L)
+    where
+        L: EventListener + 'static,
+    {
+        self.queue.add_event_listener(listener).await;
+    }
+
+    /// Parse a task id received as a string, such as an HTTP path parameter
+    ///
+    /// Fails with `TaskError::Config` if `task_id` is not a UUID.
+    ///
+    /// ```
+    /// use distributed_task_queue::{TaskClient, TaskError};
+    ///
+    /// let task_id = TaskClient::parse_task_id("67e55044-10b1-426f-9247-bb680e5fe0c8")?;
+    /// assert_eq!(task_id.to_string(), "67e55044-10b1-426f-9247-bb680e5fe0c8");
+    /// assert!(matches!(TaskClient::parse_task_id("42"), Err(TaskError::Config { .. })));
+    /// # Ok::<(), TaskError>(())
+    /// ```
+    pub fn parse_task_id(task_id: &str) -> TaskResult<TaskId> {
+        TaskId::parse_str(task_id.trim())
+            .map_err(|e| TaskError::config(format!("invalid task id {:?}: {}", task_id, e)))
+    }
+
+    /// Cancel a task that has not started executing yet
+    ///
+    /// Returns `false` if the task is already running or finished.
+    pub async fn cancel_task(&self, task_id: TaskId) -> TaskResult<bool> {
+        self.queue.cancel_task(task_id).await
+    }
+
+    /// `cancel_task` with the id given as a string (see `parse_task_id`)
+    pub async fn cancel_task_str(&self, task_id: &str) -> TaskResult<bool> {
+        self.cancel_task(Self::parse_task_id(task_id)?).await
+    }
+
+    /// Get the last progress a task reported as `(percent, message)`
+    pub async fn get_task_progress(&self, task_id: TaskId) -> TaskResult<Option<(u8, Option<String>)>> {
+        self.queue.get_task_progress(task_id).await
+    }
+
     /// Get task status by ID
+    ///
+    /// The returned task includes its failed attempts in `attempts`.
     pub async fn get_task_status(&self, task_id: TaskId) -> TaskResult<Option<TaskDefinition>> {
         self.queue.get_task(task_id).await
     }
 
+    /// `get_task_status` with the id given as a string (see `parse_task_id`)
+    pub async fn get_task_status_str(&self, task_id: &str) -> TaskResult<Option<TaskDefinition>> {
+        self.get_task_status(Self::parse_task_id(task_id)?).await
+    }
+
+    /// Get the status of many tasks in one round trip
+    ///
+    /// Tasks that do not exist map to `None`.
+    pub async fn get_task_statuses(&self, task_ids: &[TaskId]) -> TaskResult<HashMap<TaskId, Option<TaskDefinition>>> {
+        self.queue.get_tasks(task_ids).await
+    }
+
+    /// Get a task's result if it has finished, without waiting
+    ///
+    /// Returns `Ok(None)` while the task is still pending or running, and an
+    /// error if it failed, was cancelled or does not exist. Suits polling
+    /// UIs; `wait_for_result` blocks until the result is ready instead.
+    pub async fn try_get_result<T>(&self, task_id: TaskId) -> TaskResult<Option<T>>
+    where
+        T: serde::de::DeserializeOwned,
+    {
+        let task_def = match self.queue.get_result(task_id).await? {
+            Some(task_def) => task_def,
+            None => self.queue.get_task(task_id).await?.ok_or_else(|| TaskError::TaskNotFound {
+                task_id: task_id.to_string(),
+            })?,
+        };
+
+        match task_def.status {
+            TaskStatus::Success => {
+                if let Some(output) = task_def.result {
+                    let result: T = task_def.format.deserialize(&output)?;
+                    Ok(Some(result))
+                } else if !task_def.store_result {
+                    Err(TaskError::task_execution("Task completed but was submitted without storing its result"))
+                } else {
+                    Err(TaskError::task_execution("Task completed but no result found"))
+                }
+            }
+            TaskStatus::Failed => {
+                let error_msg = task_def.error.unwrap_or_else(|| "Unknown error".to_string());
+                Err(TaskError::task_execution(error_msg))
+            }
+            TaskStatus::Cancelled => Err(TaskError::task_execution("Task was cancelled")),
+            _ => Ok(None),
+        }
+    }
+
+    /// `try_get_result` with the id given as a string (see `parse_task_id`)
+    pub async fn try_get_result_str<T>(&self, task_id: &str) -> TaskResult<Option<T>>
+    where
+        T: serde::de::DeserializeOwned,
+    {
+        self.try_get_result(Self::parse_task_id(task_id)?).await
+    }
+
     /// Wait for a task to complete and return its result
+    ///
+    /// Waits for the task's completion event over Redis pub/sub and falls back
+    /// to polling the stored status if subscribing fails.
     pub async fn wait_for_result<T>(&self, task_id: TaskId, timeout_seconds: Option<u64>) -> TaskResult<T>
     where
         T: serde::de::DeserializeOwned,
     {
-        let start_time = std::time::Instant::now();
-        let timeout = timeout_seconds.map(std::time::Duration::from_secs);
+        let deadline = timeout_seconds.map(|secs| Instant::now() + Duration::from_secs(secs));
+
+        // Subscribe before the first status check so no event is missed
+        let mut pubsub = match self.queue.subscribe_task_events(task_id).await {
+            Ok(pubsub) => pubsub,
+            Err(e) => {
+                warn!("Falling back to polling for task {}: {}", task_id, e);
+                return self.poll_for_result(task_id, deadline).await;
+            }
+        };
 
+        loop {
+            if let Some(result) = self.try_get_result(task_id).await? {
+                return Ok(result);
+            }
+
+            let wait = match deadline {
+                Some(deadline) => {
+                    let remaining = deadline.saturating_duration_since(Instant::now());
+                    if remaining.is_zero() {
+                        return Err(TaskError::timeout("wait_for_result"));
+                    }
+                    remaining.min(RESULT_EVENT_RECHECK)
+                }
+                None => RESULT_EVENT_RECHECK,
+            };
+
+            // Either an event arrived or it's time to re-check; both re-read the status
+            let mut messages = pubsub.on_message();
+            let _ = tokio::time::timeout(wait, messages.next()).await;
+        }
+    }
+
+    /// Poll the stored task status until it reaches a terminal state
+    async fn poll_for_result<T>(&self, task_id: TaskId, deadline: Option<Instant>) -> TaskResult<T>
+    where
+        T: serde::de::DeserializeOwned,
+    {
         loop {
             // Check timeout
-            if let Some(timeout) = timeout {
-                if start_time.elapsed() > timeout {
+            if let Some(deadline) = deadline {
+                if Instant::now() > deadline {
                     return Err(TaskError::timeout("wait_for_result"));
                 }
             }
 
-            // Check task status
-            if let Some(task_def) = self.queue.get_task(task_id).await? {
-                match task_def.status {
-                    TaskStatus::Success => {
-                        if let Some(result_json) = task_def.result {
-                            let result: T = serde_json::from_str(&result_json)?;
-                            return Ok(result);
-                        } else {
-                            return Err(TaskError::task_execution("Task completed but no result found"));
-                        }
-                    }
-                    TaskStatus::Failed => {
-                        let error_msg = task_def.error.unwrap_or_else(|| "Unknown error".to_string());
-                        return Err(TaskError::task_execution(error_msg));
-                    }
-                    TaskStatus::Cancelled => {
-                        return Err(TaskError::task_execution("Task was cancelled"));
-                    }
-                    _ => {
-                        // Task is still pending/running, wait and check again
-                        tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+            match self.try_get_result(task_id).await? {
+                Some(result) => return Ok(result),
+                // Task is still pending/running, wait and check again
+                None => tokio::time::sleep(RESULT_POLL_INTERVAL).await,
+            }
+        }
+    }
+
+    /// Wait for many tasks to finish and return their results in order
+    ///
+    /// The tasks are awaited together over one pub/sub subscription (falling
+    /// back to polling), so the wait lasts as long as the slowest task rather
+    /// than the sum of all. Every task gets its own outcome: a failed,
+    /// cancelled or unknown task yields an error in its slot, as does one
+    /// still unfinished when the overall timeout passes. Only errors reaching
+    /// Redis fail the whole call.
+    pub async fn wait_for_all<R>(
+        &self,
+        task_ids: &[TaskId],
+        timeout_seconds: Option<u64>,
+    ) -> TaskResult<Vec<TaskResult<R>>>
+    where
+        R: serde::de::DeserializeOwned,
+    {
+        if task_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+        let deadline = timeout_seconds.map(|secs| Instant::now() + Duration::from_secs(secs));
+        let mut outcomes: Vec<Option<TaskResult<R>>> = task_ids.iter().map(|_| None).collect();
+
+        // Subscribe before the first status check so no event is missed
+        let mut pubsub = match self.queue.subscribe_many_task_events(task_ids).await {
+            Ok(pubsub) => Some(pubsub),
+            Err(e) => {
+                warn!("Falling back to polling for {} tasks: {}", task_ids.len(), e);
+                None
+            }
+        };
+
+        let mut to_check: Vec<usize> = (0..task_ids.len()).collect();
+        loop {
+            let fetched = join_all(to_check.iter().map(|&i| self.try_get_result::<R>(task_ids[i]))).await;
+            for (i, fetched) in to_check.drain(..).zip(fetched) {
+                match fetched {
+                    Ok(Some(result)) => outcomes[i] = Some(Ok(result)),
+                    Ok(None) => {}
+                    Err(e @ (TaskError::TaskExecution { .. } | TaskError::TaskNotFound { .. } | TaskError::Serialization(_))) => {
+                        outcomes[i] = Some(Err(e));
                     }
+                    Err(e) => return Err(e),
                 }
-            } else {
-                return Err(TaskError::TaskNotFound {
-                    task_id: task_id.to_string(),
-                });
             }
+
+            let pending: Vec<usize> = (0..task_ids.len()).filter(|&i| outcomes[i].is_none()).collect();
+            if pending.is_empty() {
+                break;
+            }
+
+            let recheck = match pubsub {
+                Some(_) => RESULT_EVENT_RECHECK,
+                None => RESULT_POLL_INTERVAL,
+            };
+            let wait = match deadline {
+                Some(deadline) => {
+                    let remaining = deadline.saturating_duration_since(Instant::now());
+                    if remaining.is_zero() {
+                        for i in pending {
+                            outcomes[i] = Some(Err(TaskError::timeout("wait_for_all")));
+                        }
+                        break;
+                    }
+                    remaining.min(recheck)
+                }
+                None => recheck,
+            };
+
+            let notified = match pubsub.as_mut() {
+                Some(pubsub) => {
+                    let mut messages = pubsub.on_message();
+                    tokio::time::timeout(wait, messages.next())
+                        .await
+                        .ok()
+                        .flatten()
+                        .and_then(|message| message.get_payload::<String>().ok())
+                        .and_then(|payload| payload.parse::<TaskId>().ok())
+                }
+                None => {
+                    tokio::time::sleep(wait).await;
+                    None
+                }
+            };
+
+            // An event re-checks just its task; a timed-out wait re-checks them all
+            to_check = match notified {
+                Some(task_id) => pending.into_iter().filter(|&i| task_ids[i] == task_id).collect(),
+                None => pending,
+            };
         }
+
+        Ok(outcomes
+            .into_iter()
+            .map(|outcome| outcome.unwrap_or_else(|| Err(TaskError::timeout("wait_for_all"))))
+            .collect())
     }
 
     /// Get queue statistics
@@ -169,17 +698,87 @@ impl TaskClient {
         self.queue.get_stats(queue_name).await
     }
 
+    /// Get statistics summed over every queue
+    pub async fn get_global_stats(&self) -> TaskResult<crate::queue::QueueStats> {
+        self.queue.get_global_stats().await
+    }
+
+    /// Remove every pending task from a queue, returning how many were removed
+    pub async fn purge_queue(&self, queue_name: &str) -> TaskResult<u64> {
+        self.queue.purge_queue(queue_name).await
+    }
+
     /// List all available queues
-    pub async fn list_queues(&self) -> TaskResult<Vec<String>> {
+    pub async fn list_queues(&self) -> TaskResult<Vec<QueueInfo>> {
         self.queue.list_queues().await
     }
 
+    /// Check whether workers are skipping a queue (see `TaskQueue::pause_queue`)
+    pub async fn is_queue_paused(&self, queue_name: &str) -> TaskResult<bool> {
+        self.queue.is_queue_paused(queue_name).await
+    }
+
     /// Get access to the underlying queue for advanced operations
     pub fn queue(&self) -> &Arc<TaskQueue> {
         &self.queue
     }
 }
 
+/// Handle to a submitted task, typed to the task's output
+pub struct TaskHandle<R> {
+    task_id: TaskId,
+    queue: String,
+    client: TaskClient,
+    _output: PhantomData<fn() -> R>,
+}
+
+impl<R> TaskHandle<R>
+where
+    R: serde::de::DeserializeOwned,
+{
+    /// Id of the submitted task
+    pub fn id(&self) -> TaskId {
+        self.task_id
+    }
+
+    /// Queue the task was submitted to
+    pub fn queue(&self) -> &str {
+        &self.queue
+    }
+
+    /// Wait for the task to complete and return its result
+    ///
+    /// See `TaskClient::wait_for_result`.
+    pub async fn await_result(&self, timeout_seconds: Option<u64>) -> TaskResult<R> {
+        self.client.wait_for_result(self.task_id, timeout_seconds).await
+    }
+
+    /// The task's result if it has finished, without waiting
+    ///
+    /// See `TaskClient::try_get_result`.
+    pub async fn try_result(&self) -> TaskResult<Option<R>> {
+        self.client.try_get_result(self.task_id).await
+    }
+
+    /// Current status of the task, or `None` if its record has expired
+    pub async fn status(&self) -> TaskResult<Option<TaskStatus>> {
+        Ok(self
+            .client
+            .get_task_status(self.task_id)
+            .await?
+            .map(|task_def| task_def.status))
+    }
+}
+
+impl<R> std::fmt::Debug for TaskHandle<R> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TaskHandle")
+            .field("task_id", &self.task_id)
+            .field("queue", &self.queue)
+            .finish()
+    }
+}
+
 /// Configuration for task submission
 #[derive(Debug)]
 pub struct TaskSubmissionConfig<'a, T> {
@@ -191,6 +790,16 @@ pub struct TaskSubmissionConfig<'a, T> {
     pub priority: Option<TaskPriority>,
     /// Scheduled execution time
     pub scheduled_at: Option<DateTime<Utc>>,
+    /// URL to POST the task's outcome to when it finishes
+    pub callback_url: Option<String>,
+    /// Retry configuration overriding `Task::retry_config`
+    pub retry_config: Option<RetryConfig>,
+    /// Cancel the task if it has not started by then
+    pub expires_at: Option<DateTime<Utc>>,
+    /// Store the task's output when it succeeds
+    pub store_result: bool,
+    /// Seconds to keep the result, overriding the queue's `result_ttl`
+    pub result_ttl: Option<u64>,
 }
 
 impl<'a, T> TaskSubmissionConfig<'a, T> {
@@ -201,6 +810,11 @@ impl<'a, T> TaskSubmissionConfig<'a, T> {
             queue,
             priority: None,
             scheduled_at: None,
+            callback_url: None,
+            retry_config: None,
+            expires_at: None,
+            store_result: true,
+            result_ttl: None,
         }
     }
 
@@ -221,6 +835,47 @@ impl<'a, T> TaskSubmissionConfig<'a, T> {
         self.scheduled_at = Some(Utc::now() + chrono::Duration::seconds(delay_seconds as i64));
         self
     }
+
+    /// Cancel the task instead of running it if it has not started by `expires_at`
+    pub fn expires_at(mut self, expires_at: DateTime<Utc>) -> Self {
+        self.expires_at = Some(expires_at);
+        self
+    }
+
+    /// Cancel the task instead of running it if it has not started within
+    /// `seconds` of submission
+    pub fn expires_after(mut self, seconds: u64) -> Self {
+        self.expires_at = Some(Utc::now() + chrono::Duration::seconds(seconds as i64));
+        self
+    }
+
+    /// Don't store the task's output, for tasks whose result no one reads
+    ///
+    /// Waiting for such a task's result fails once it has succeeded.
+    pub fn no_result(mut self) -> Self {
+        self.store_result = false;
+        self
+    }
+
+    /// Keep the task's result for `seconds` instead of the queue's
+    /// `result_ttl` (0 to keep it until evicted)
+    pub fn result_ttl(mut self, seconds: u64) -> Self {
+        self.result_ttl = Some(seconds);
+        self
+    }
+
+    /// Override the task type's retry configuration for this submission
+    pub fn with_retry_config(mut self, retry_config: RetryConfig) -> Self {
+        self.retry_config = Some(retry_config);
+        self
+    }
+
+    /// Have the worker POST the task's outcome to `url` when it finishes
+    /// (requires the `webhooks` feature on the worker)
+    pub fn with_callback_url<S: Into<String>>(mut self, url: S) -> Self {
+        self.callback_url = Some(url.into());
+        self
+    }
 }
 
 /// Convenience methods for common task submission patterns
@@ -228,7 +883,7 @@ impl TaskClient {
     /// Submit a high-priority task
     pub async fn submit_high_priority<T>(&self, task: &T, queue_name: &str) -> TaskResult<TaskId>
     where
-        T: Task + Serialize,
+        T: SerializableTask,
     {
         self.submit_with_priority(task, queue_name, TaskPriority::High)
             .await
@@ -237,7 +892,7 @@ impl TaskClient {
     /// Submit a critical priority task
     pub async fn submit_critical<T>(&self, task: &T, queue_name: &str) -> TaskResult<TaskId>
     where
-        T: Task + Serialize,
+        T: SerializableTask,
     {
         self.submit_with_priority(task, queue_name, TaskPriority::Critical)
             .await
@@ -246,7 +901,7 @@ impl TaskClient {
     /// Submit a low-priority task
     pub async fn submit_low_priority<T>(&self, task: &T, queue_name: &str) -> TaskResult<TaskId>
     where
-        T: Task + Serialize,
+        T: SerializableTask,
     {
         self.submit_with_priority(task, queue_name, TaskPriority::Low)
             .await
@@ -260,26 +915,131 @@ impl TaskClient {
         timeout_seconds: Option<u64>,
     ) -> TaskResult<R>
     where
-        T: Task + Serialize,
+        T: SerializableTask,
         R: serde::de::DeserializeOwned,
     {
         let task_id = self.submit_to_queue(task, queue_name).await?;
         self.wait_for_result(task_id, timeout_seconds).await
     }
 
+    /// Submit a task and follow its status until it finishes
+    ///
+    /// The stream yields the task's initial status, then each status it is
+    /// seen to change to, and ends after a terminal one (`Success`, `Failed`
+    /// or `Cancelled`). Changes are picked up from the task's event channel,
+    /// falling back to polling if subscribing fails; a status that is
+    /// superseded before it is read is not reported. If `timeout_seconds`
+    /// elapses first, the stream ends with a `TaskError::Timeout`.
+    pub async fn submit_and_watch<T>(
+        &self,
+        task: &T,
+        queue_name: &str,
+        timeout_seconds: Option<u64>,
+    ) -> TaskResult<impl Stream<Item = TaskResult<TaskStatus>> + Unpin + '_>
+    where
+        T: SerializableTask,
+    {
+        let deadline = timeout_seconds.map(|secs| Instant::now() + Duration::from_secs(secs));
+        let task_def = self.route(TaskDefinition::new(task, queue_name.to_string())?);
+        let submitted_id = task_def.id;
+
+        // Subscribe before submitting so no transition is missed
+        let mut pubsub = self.watch_subscription(submitted_id).await;
+        let task_id = self.queue.submit_task(task_def).await?;
+        if task_id != submitted_id {
+            // Deduplicated: follow the task that was already pending instead
+            pubsub = self.watch_subscription(task_id).await;
+        }
+
+        let watch = StatusWatch {
+            task_id,
+            pubsub,
+            deadline,
+            last: None,
+            finished: false,
+        };
+        Ok(Box::pin(futures_util::stream::unfold(watch, move |mut watch| async move {
+            if watch.finished {
+                return None;
+            }
+            let item = self.next_status(&mut watch).await;
+            watch.finished = match &item {
+                Ok(status) => status.is_terminal(),
+                Err(_) => true,
+            };
+            Some((item, watch))
+        })))
+    }
+
+    /// Subscribe to a task's events for `submit_and_watch`, or `None` to poll
+    async fn watch_subscription(&self, task_id: TaskId) -> Option<PubSub> {
+        match self.queue.subscribe_task_events(task_id).await {
+            Ok(pubsub) => Some(pubsub),
+            Err(e) => {
+                warn!("Falling back to polling for task {}: {}", task_id, e);
+                None
+            }
+        }
+    }
+
+    /// Wait until the watched task's status differs from the last one reported
+    async fn next_status(&self, watch: &mut StatusWatch) -> TaskResult<TaskStatus> {
+        loop {
+            let status = match self.queue.get_result(watch.task_id).await? {
+                Some(task_def) => task_def.status,
+                None => {
+                    self.queue
+                        .get_task(watch.task_id)
+                        .await?
+                        .ok_or_else(|| TaskError::TaskNotFound {
+                            task_id: watch.task_id.to_string(),
+                        })?
+                        .status
+                }
+            };
+            if watch.last.as_ref() != Some(&status) {
+                watch.last = Some(status.clone());
+                return Ok(status);
+            }
+
+            let recheck = match watch.pubsub {
+                Some(_) => RESULT_EVENT_RECHECK,
+                None => RESULT_POLL_INTERVAL,
+            };
+            let wait = match watch.deadline {
+                Some(deadline) => {
+                    let remaining = deadline.saturating_duration_since(Instant::now());
+                    if remaining.is_zero() {
+                        return Err(TaskError::timeout("submit_and_watch"));
+                    }
+                    remaining.min(recheck)
+                }
+                None => recheck,
+            };
+
+            match watch.pubsub.as_mut() {
+                Some(pubsub) => {
+                    let mut messages = pubsub.on_message();
+                    let _ = tokio::time::timeout(wait, messages.next()).await;
+                }
+                None => tokio::time::sleep(wait).await,
+            }
+        }
+    }
+
     /// Submit multiple tasks at once
+    ///
+    /// All tasks are written in a single Redis round trip.
     pub async fn submit_batch<T>(&self, tasks: &[T], queue_name: &str) -> TaskResult<Vec<TaskId>>
     where
-        T: Task + Serialize,
+        T: SerializableTask,
     {
-        let mut task_ids = Vec::new();
-        
-        for task in tasks {
-            let task_id = self.submit_to_queue(task, queue_name).await?;
-            task_ids.push(task_id);
-        }
-        
-        Ok(task_ids)
+        let task_defs = tasks
+            .iter()
+            .map(|task| Ok(self.route(TaskDefinition::new(task, queue_name.to_string())?)))
+            .collect::<TaskResult<Vec<_>>>()?;
+
+        self.queue.submit_tasks(task_defs).await
     }
 
     /// Submit multiple tasks with different priorities
@@ -289,15 +1049,17 @@ impl TaskClient {
         queue_name: &str,
     ) -> TaskResult<Vec<TaskId>>
     where
-        T: Task + Serialize,
+        T: SerializableTask,
     {
-        let mut task_ids = Vec::new();
-        
-        for (task, priority) in tasks {
-            let task_id = self.submit_with_priority(task, queue_name, priority.clone()).await?;
-            task_ids.push(task_id);
-        }
-        
-        Ok(task_ids)
+        let task_defs = tasks
+            .iter()
+            .map(|(task, priority)| {
+                let mut task_def = TaskDefinition::new(task, queue_name.to_string())?;
+                task_def.set_priority(priority.clone());
+                Ok(self.route(task_def))
+            })
+            .collect::<TaskResult<Vec<_>>>()?;
+
+        self.queue.submit_tasks(task_defs).await
     }
 } 
\ No newline at end of file
@@ -0,0 +1,102 @@
+//! Compression of stored task results
+
+use crate::error::{TaskError, TaskResult};
+
+/// Prefix of a result stored gzip-compressed and base64-encoded
+///
+/// Starts with a control character, which neither JSON nor base64 output
+/// can begin with.
+const COMPRESSED_PREFIX: &str = "\u{1}gz:";
+
+/// Whether a stored result was compressed by `compress_result`
+pub fn is_compressed(stored: &str) -> bool {
+    stored.starts_with(COMPRESSED_PREFIX)
+}
+
+/// Compress a result for storage if it is at least `threshold` bytes long
+///
+/// Shorter results are returned unchanged, unless they would be mistaken for
+/// a compressed result. Requires the `compression` feature.
+///
+/// ```
+/// # #[cfg(feature = "compression")]
+/// # {
+/// use distributed_task_queue::compression::{compress_result, decompress_result};
+///
+/// let result = r#"{"greeting":"héllo wörld ✓ 你好 🦀"}"#.repeat(100);
+/// let stored = compress_result(&result, 1024)?;
+/// assert!(stored.len() < result.len());
+/// assert_eq!(decompress_result(&stored)?, result);
+///
+/// // Results below the threshold are stored as they are
+/// assert_eq!(compress_result("42", 1024)?, "42");
+///
+/// // ... unless they look like a compressed result
+/// let tricky = "\u{1}gz:not really";
+/// assert_ne!(compress_result(tricky, 1024)?, tricky);
+/// assert_eq!(decompress_result(&compress_result(tricky, 1024)?)?, tricky);
+/// # }
+/// # Ok::<(), distributed_task_queue::TaskError>(())
+/// ```
+pub fn compress_result(result: &str, threshold: usize) -> TaskResult<String> {
+    if result.len() < threshold && !is_compressed(result) {
+        return Ok(result.to_string());
+    }
+    compress(result)
+}
+
+/// Restore a result stored by `compress_result`
+///
+/// Uncompressed results are returned unchanged.
+pub fn decompress_result(stored: &str) -> TaskResult<String> {
+    match stored.strip_prefix(COMPRESSED_PREFIX) {
+        Some(encoded) => decompress(encoded),
+        None => Ok(stored.to_string()),
+    }
+}
+
+#[cfg(feature = "compression")]
+fn compress(result: &str) -> TaskResult<String> {
+    use base64::Engine;
+    use flate2::write::GzEncoder;
+    use flate2::Compression;
+    use std::io::Write;
+
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(result.as_bytes())?;
+    let bytes = encoder.finish()?;
+    Ok(format!(
+        "{}{}",
+        COMPRESSED_PREFIX,
+        base64::engine::general_purpose::STANDARD.encode(bytes)
+    ))
+}
+
+#[cfg(feature = "compression")]
+fn decompress(encoded: &str) -> TaskResult<String> {
+    use base64::Engine;
+    use flate2::read::GzDecoder;
+    use std::io::Read;
+
+    let bytes = base64::engine::general_purpose::STANDARD
+        .decode(encoded)
+        .map_err(|e| TaskError::Internal(anyhow::anyhow!("Compressed result is not base64: {}", e)))?;
+    let mut result = String::new();
+    GzDecoder::new(bytes.as_slice()).read_to_string(&mut result)?;
+    Ok(result)
+}
+
+#[cfg(not(feature = "compression"))]
+fn compress(_result: &str) -> TaskResult<String> {
+    Err(compression_disabled())
+}
+
+#[cfg(not(feature = "compression"))]
+fn decompress(_encoded: &str) -> TaskResult<String> {
+    Err(compression_disabled())
+}
+
+#[cfg(not(feature = "compression"))]
+fn compression_disabled() -> TaskError {
+    TaskError::config("Compressed results require the `compression` feature")
+}
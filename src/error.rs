@@ -24,6 +24,14 @@ pub enum TaskError {
     #[error("Task not found: {task_id}")]
     TaskNotFound { task_id: String },
 
+    /// A task with the same id already exists
+    #[error("Task already exists: {task_id}")]
+    TaskExists { task_id: String },
+
+    /// The queue holds `TaskQueueConfig::max_queue_depth` pending tasks
+    #[error("Queue is full: {queue} ({depth} pending tasks)")]
+    QueueFull { queue: String, depth: u64 },
+
     /// Queue operation errors
     #[error("Queue operation failed: {operation}: {reason}")]
     QueueOperation { operation: String, reason: String },
@@ -44,6 +52,10 @@ pub enum TaskError {
     #[error("Operation timed out: {operation}")]
     Timeout { operation: String },
 
+    /// Task failures that retrying cannot fix
+    #[error("Task failed permanently: {message}")]
+    Permanent { message: String },
+
     /// Task retry limit exceeded
     #[error("Task retry limit exceeded: {task_id} (max retries: {max_retries})")]
     RetryLimitExceeded { task_id: String, max_retries: u32 },
@@ -65,8 +77,15 @@ impl TaskError {
         }
     }
 
+    /// Create an error for a failure that should not be retried
+    pub fn permanent<S: Into<String>>(message: S) -> Self {
+        Self::Permanent {
+            message: message.into(),
+        }
+    }
+
     /// Create a queue operation error
-    pub fn queue_operation<S: Into<String>>(operation: S, reason: S) -> Self {
+    pub fn queue_operation<O: Into<String>, R: Into<String>>(operation: O, reason: R) -> Self {
         Self::QueueOperation {
             operation: operation.into(),
             reason: reason.into(),
@@ -101,6 +120,18 @@ impl TaskError {
         }
     }
 
+    /// Check if the error means Redis could not be reached
+    ///
+    /// Such errors say nothing about the task that ran into them.
+    pub fn is_connection_error(&self) -> bool {
+        match self {
+            TaskError::Redis(e) => {
+                e.is_io_error() || e.is_connection_refusal() || e.is_connection_dropped() || e.is_timeout()
+            }
+            _ => false,
+        }
+    }
+
     /// Check if the error is recoverable (can be retried)
     pub fn is_recoverable(&self) -> bool {
         match self {
@@ -109,8 +140,11 @@ impl TaskError {
             TaskError::QueueOperation { .. } => true,
             TaskError::Worker { .. } => true,
             TaskError::TaskExecution { .. } => true,
+            TaskError::Permanent { .. } => false,
             TaskError::Serialization(_) => false,
             TaskError::TaskNotFound { .. } => false,
+            TaskError::TaskExists { .. } => false,
+            TaskError::QueueFull { .. } => true,
             TaskError::Config { .. } => false,
             TaskError::RetryLimitExceeded { .. } => false,
             TaskError::Scheduler { .. } => false,
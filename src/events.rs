@@ -0,0 +1,99 @@
+//! Task lifecycle events and listeners
+
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+use crate::task::{TaskId, TaskStatus};
+
+/// A task state transition
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TaskEvent {
+    /// The task was submitted (to its queue, or to the scheduled set)
+    Queued { task_id: TaskId, queue: String },
+    /// A worker started executing the task
+    Started { task_id: TaskId, queue: String, worker_id: String },
+    /// The task finished successfully
+    Succeeded { task_id: TaskId, queue: String },
+    /// The task failed for good
+    Failed { task_id: TaskId, queue: String, error: String },
+    /// The task failed and was requeued for another attempt
+    Retried { task_id: TaskId, queue: String, attempt: u32 },
+    /// The task is still running after the worker's soft timeout
+    SoftTimeout { task_id: TaskId, queue: String, elapsed_secs: u64 },
+}
+
+impl TaskEvent {
+    /// Id of the task the event is about
+    pub fn task_id(&self) -> TaskId {
+        match self {
+            TaskEvent::Queued { task_id, .. }
+            | TaskEvent::Started { task_id, .. }
+            | TaskEvent::Succeeded { task_id, .. }
+            | TaskEvent::Failed { task_id, .. }
+            | TaskEvent::Retried { task_id, .. }
+            | TaskEvent::SoftTimeout { task_id, .. } => *task_id,
+        }
+    }
+
+    /// Queue of the task
+    pub fn queue(&self) -> &str {
+        match self {
+            TaskEvent::Queued { queue, .. }
+            | TaskEvent::Started { queue, .. }
+            | TaskEvent::Succeeded { queue, .. }
+            | TaskEvent::Failed { queue, .. }
+            | TaskEvent::Retried { queue, .. }
+            | TaskEvent::SoftTimeout { queue, .. } => queue,
+        }
+    }
+
+    /// Status the task moved to
+    ///
+    /// `Pending` for `Queued`, even when the task waits in the scheduled set.
+    pub fn status(&self) -> TaskStatus {
+        match self {
+            TaskEvent::Queued { .. } => TaskStatus::Pending,
+            TaskEvent::Started { .. } | TaskEvent::SoftTimeout { .. } => TaskStatus::Running,
+            TaskEvent::Succeeded { .. } => TaskStatus::Success,
+            TaskEvent::Failed { .. } => TaskStatus::Failed,
+            TaskEvent::Retried { .. } => TaskStatus::Retrying,
+        }
+    }
+}
+
+/// Receives task lifecycle events, e.g. to write an audit log or call webhooks
+///
+/// Listeners are awaited in line with the operation that emitted the event,
+/// so slow work should be handed off to a background task.
+#[async_trait::async_trait]
+pub trait EventListener: Send + Sync {
+    /// Handle an event
+    async fn on_event(&self, event: TaskEvent);
+}
+
+/// Listeners registered on a task queue
+#[derive(Default)]
+pub(crate) struct EventListeners {
+    listeners: RwLock<Vec<Arc<dyn EventListener>>>,
+}
+
+impl EventListeners {
+    /// Add a listener
+    pub(crate) async fn add(&self, listener: Arc<dyn EventListener>) {
+        self.listeners.write().await.push(listener);
+    }
+
+    /// Deliver an event to every listener, in registration order
+    pub(crate) async fn emit(&self, event: TaskEvent) {
+        let listeners = self.listeners.read().await.clone();
+        for listener in listeners {
+            listener.on_event(event.clone()).await;
+        }
+    }
+}
+
+impl std::fmt::Debug for EventListeners {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EventListeners").finish_non_exhaustive()
+    }
+}
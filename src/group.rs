@@ -0,0 +1,187 @@
+//! Task groups with an aggregate completion callback
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::error::{TaskError, TaskResult};
+use crate::serialization::SerializationFormat;
+use crate::task::{SerializableTask, TaskDefinition, TaskId, TaskStatus};
+
+/// Unique identifier for task groups
+pub type GroupId = Uuid;
+
+/// Field of the finalizer's payload that receives the members' outcomes
+pub const GROUP_RESULTS_FIELD: &str = "group_results";
+
+/// When a group's finalizer is enqueued
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum FinalizerPolicy {
+    /// Only if every member succeeded
+    #[default]
+    AllSucceeded,
+    /// Once every member has finished, whether or not some failed
+    Always,
+}
+
+/// Outcome of one group member, as passed to the finalizer
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GroupMemberResult {
+    pub task_id: TaskId,
+    /// `Success`, `Failed` or `Cancelled`
+    pub status: TaskStatus,
+    /// The member's output, in its serialization format
+    pub result: Option<String>,
+    pub error: Option<String>,
+}
+
+impl GroupMemberResult {
+    pub(crate) fn from_task(task_def: &TaskDefinition) -> Self {
+        Self {
+            task_id: task_def.id,
+            status: task_def.status.clone(),
+            result: task_def.result.clone(),
+            error: task_def.error.clone(),
+        }
+    }
+}
+
+/// Tasks run in parallel, with an optional finalizer task enqueued once all
+/// of them have finished
+///
+/// The finalizer's payload must serialize to a JSON object. When it is
+/// enqueued, the members' outcomes are added to it as a `group_results`
+/// array of `GroupMemberResult`s in member order, so the finalizer task
+/// declares a matching field:
+///
+/// ```
+/// use distributed_task_queue::group::{FinalizerPolicy, GroupMemberResult, TaskGroup};
+/// use distributed_task_queue::{Task, TaskError};
+/// use serde::{Deserialize, Serialize};
+///
+/// #[derive(Debug, Serialize, Deserialize)]
+/// struct Resize { image: String }
+///
+/// #[derive(Debug, Serialize, Deserialize)]
+/// struct Publish {
+///     album: String,
+///     #[serde(default)]
+///     group_results: Vec<GroupMemberResult>,
+/// }
+/// # #[async_trait::async_trait]
+/// # impl Task for Resize {
+/// #     type Output = ();
+/// #     type Error = TaskError;
+/// #     async fn execute(&self) -> Result<(), TaskError> { Ok(()) }
+/// # }
+/// # #[async_trait::async_trait]
+/// # impl Task for Publish {
+/// #     type Output = ();
+/// #     type Error = TaskError;
+/// #     async fn execute(&self) -> Result<(), TaskError> { Ok(()) }
+/// # }
+///
+/// let resizes: Vec<Resize> = (1..=3).map(|i| Resize { image: format!("{}.png", i) }).collect();
+/// let group = TaskGroup::new(&resizes)?
+///     .with_finalizer(&Publish { album: "holiday".into(), group_results: Vec::new() })?
+///     .finalizer_policy(FinalizerPolicy::Always);
+/// assert_eq!(group.len(), 3);
+/// # Ok::<(), TaskError>(())
+/// ```
+#[derive(Debug, Clone)]
+pub struct TaskGroup {
+    pub(crate) id: GroupId,
+    pub(crate) members: Vec<TaskDefinition>,
+    pub(crate) finalizer: Option<TaskDefinition>,
+    pub(crate) policy: FinalizerPolicy,
+}
+
+impl TaskGroup {
+    /// Create a group of the given tasks
+    ///
+    /// Members are never deduplicated, whatever their idempotency keys.
+    pub fn new<T>(tasks: &[T]) -> TaskResult<Self>
+    where
+        T: SerializableTask,
+    {
+        if tasks.is_empty() {
+            return Err(TaskError::config("a task group needs at least one task"));
+        }
+
+        let id = GroupId::new_v4();
+        let members = tasks
+            .iter()
+            .map(|task| {
+                let mut task_def = TaskDefinition::new(task, String::new())?;
+                task_def.group_id = Some(id);
+                // A deduplicated member would never report back to the group
+                task_def.idempotency_key = None;
+                Ok(task_def)
+            })
+            .collect::<TaskResult<Vec<_>>>()?;
+
+        Ok(Self {
+            id,
+            members,
+            finalizer: None,
+            policy: FinalizerPolicy::default(),
+        })
+    }
+
+    /// Enqueue `finalizer` once the group's tasks have finished
+    pub fn with_finalizer<F>(mut self, finalizer: &F) -> TaskResult<Self>
+    where
+        F: SerializableTask,
+    {
+        let task_def = TaskDefinition::new(finalizer, String::new())?;
+        if task_def.format != SerializationFormat::Json
+            || !serde_json::from_str::<serde_json::Value>(&task_def.data)?.is_object()
+        {
+            return Err(TaskError::config(
+                "a group finalizer must serialize to a JSON object",
+            ));
+        }
+        self.finalizer = Some(task_def);
+        Ok(self)
+    }
+
+    /// Whether a failed member prevents the finalizer from running
+    pub fn finalizer_policy(mut self, policy: FinalizerPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// Id of the group
+    pub fn id(&self) -> GroupId {
+        self.id
+    }
+
+    /// Number of tasks in the group
+    pub fn len(&self) -> usize {
+        self.members.len()
+    }
+
+    /// Whether the group has no tasks (never true for a constructed group)
+    pub fn is_empty(&self) -> bool {
+        self.members.is_empty()
+    }
+
+    /// Set the queue of every member, and of the finalizer
+    pub(crate) fn set_queue(&mut self, queue: &str) {
+        for task_def in self.members.iter_mut().chain(self.finalizer.as_mut()) {
+            task_def.queue = queue.to_string();
+        }
+    }
+}
+
+/// Build the finalizer task to enqueue, with the members' outcomes added
+pub(crate) fn finalizer_with_results(
+    mut finalizer: TaskDefinition,
+    results: Vec<GroupMemberResult>,
+) -> TaskResult<TaskDefinition> {
+    let mut data: serde_json::Value = serde_json::from_str(&finalizer.data)?;
+    if let Some(fields) = data.as_object_mut() {
+        fields.insert(GROUP_RESULTS_FIELD.to_string(), serde_json::to_value(results)?);
+    }
+    finalizer.data = serde_json::to_string(&data)?;
+    Ok(finalizer)
+}
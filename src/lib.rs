@@ -14,10 +14,10 @@
 //! ## Quick Start
 //!
 //! ```rust,no_run
-//! use distributed_task_queue::{TaskQueue, Worker, Task};
+//! use distributed_task_queue::{Task, TaskError};
 //! use serde::{Deserialize, Serialize};
 //!
-//! #[derive(Serialize, Deserialize)]
+//! #[derive(Debug, Serialize, Deserialize)]
 //! struct MyTask {
 //!     message: String,
 //! }
@@ -25,7 +25,7 @@
 //! #[async_trait::async_trait]
 //! impl Task for MyTask {
 //!     type Output = String;
-//!     type Error = anyhow::Error;
+//!     type Error = TaskError;
 //!
 //!     async fn execute(&self) -> Result<Self::Output, Self::Error> {
 //!         Ok(format!("Processed: {}", self.message))
@@ -33,20 +33,42 @@
 //! }
 //! ```
 
+#[cfg(feature = "admin")]
+pub mod admin;
+pub mod backend;
 pub mod client;
+pub mod compression;
 pub mod error;
+pub mod events;
+pub mod group;
 pub mod queue;
+pub mod result_backend;
+pub mod routing;
 pub mod scheduler;
+pub mod serialization;
 pub mod task;
+#[cfg(feature = "webhooks")]
+mod webhooks;
 pub mod worker;
 
 // Re-export commonly used types
-pub use client::TaskClient;
+#[cfg(feature = "admin")]
+pub use admin::QueueAdmin;
+pub use backend::{MemoryBackend, QueueBackend};
+pub use client::{KnownTasks, TaskClient, TaskHandle};
 pub use error::{TaskError, TaskResult};
-pub use queue::TaskQueue;
+pub use events::{EventListener, TaskEvent};
+pub use group::{FinalizerPolicy, GroupId, GroupMemberResult, TaskGroup};
+pub use queue::{
+    AgingConfig, HealthStatus, QueueDefaults, QueueInfo, QueuedTask, ReconnectBackoff, SubmitOutcome, TaskQueue,
+    TaskQueueConfig,
+};
+pub use result_backend::{RedisResultBackend, ResultBackend};
+pub use routing::{DefaultRoutingPolicy, PriorityRoutingPolicy, RoutingPolicy};
 pub use scheduler::TaskScheduler;
-pub use task::{Task, TaskDefinition, TaskId, TaskStatus};
-pub use worker::{Worker, WorkerConfig};
+pub use serialization::{SerializationFormat, Serializer};
+pub use task::{SerializableTask, Task, TaskDefinition, TaskDefinitionBuilder, TaskId, TaskPriority, TaskStatus};
+pub use worker::{AckHandle, Middleware, QueueStrategy, Worker, WorkerConfig};
 
 /// Version of the distributed task queue library
 pub const VERSION: &str = env!("CARGO_PKG_VERSION"); 
\ No newline at end of file
@@ -1,373 +1,2749 @@
 //! Task queue implementation with Redis backend
 
-use redis::aio::Connection;
-use redis::{Client, RedisError};
-use std::collections::HashMap;
-use std::sync::Arc;
-use tokio::sync::RwLock;
-use tracing::{debug, error, info, warn};
+use async_trait::async_trait;
+use bb8::{ManageConnection, Pool, PooledConnection};
+use chrono::{DateTime, Utc};
+use rand::Rng;
+use redis::aio::{Connection, PubSub};
+use redis::{Client, ErrorKind, IntoConnectionInfo, RedisError, Script};
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::fmt;
+use std::sync::{Arc, Mutex as StdMutex};
+use std::time::{Duration, Instant};
+use tracing::{debug, info, warn};
 
+use crate::backend::{MemoryBackend, QueueBackend};
+use crate::compression;
 use crate::error::{TaskError, TaskResult};
-use crate::task::{TaskDefinition, TaskId, TaskPriority, TaskStatus};
+use crate::events::{EventListener, EventListeners, TaskEvent};
+use crate::group::{self, FinalizerPolicy, GroupId, GroupMemberResult, TaskGroup};
+use crate::result_backend::{RedisResultBackend, ResultBackend};
+use crate::task::{RetryConfig, TaskDefinition, TaskId, TaskPriority, TaskStatus};
 
-/// Redis keys for different queue operations
-const QUEUE_KEY: &str = "dtq:queue";
-const SCHEDULED_KEY: &str = "dtq:scheduled";
-const PROCESSING_KEY: &str = "dtq:processing";
-const RESULTS_KEY: &str = "dtq:results";
-const FAILED_KEY: &str = "dtq:failed";
-const STATS_KEY: &str = "dtq:stats";
+/// Redis keys for different queue operations, relative to the key prefix
+const QUEUE_KEY: &str = "queue";
+const SCHEDULED_KEY: &str = "scheduled";
+const SCHEDULED_QUEUES_KEY: &str = "scheduled_queues";
+const PROCESSING_KEY: &str = "processing";
+const RESULTS_KEY: &str = "results";
+const FAILED_KEY: &str = "failed";
+const STATS_KEY: &str = "stats";
+const RESOURCES_KEY: &str = "resources";
+const SCHEDULED_JOBS_KEY: &str = "scheduled_jobs";
+const EVENTS_KEY: &str = "events";
+const WORKERS_KEY: &str = "workers";
+const IDEMPOTENCY_KEY: &str = "idempotency";
+const STATUS_KEY: &str = "status";
+const PAUSED_KEY: &str = "paused";
+const GROUP_KEY: &str = "group";
+const SEQUENCE_KEY: &str = "sequence";
+const CORRUPT_KEY: &str = "corrupt";
+
+/// Submission sequence numbers that fit in one priority point of a queue
+/// score before the fraction wraps (2^32)
+const SEQUENCE_SPAN: u64 = 1 << 32;
+
+/// Claim an idempotency key for a task.
+///
+/// KEYS[1]: idempotency key. ARGV[1]: task id, ARGV[2]: TTL in seconds.
+/// Returns nil if the key was claimed, otherwise the id of the task holding it.
+const CLAIM_IDEMPOTENCY_KEY_SCRIPT: &str = r#"
+if redis.call('SET', KEYS[1], ARGV[1], 'NX', 'EX', ARGV[2]) then
+    return false
+end
+return redis.call('GET', KEYS[1])
+"#;
+
+/// Release an idempotency key if it is still held by the given task.
+///
+/// KEYS[1]: idempotency key. ARGV[1]: task id.
+const RELEASE_IDEMPOTENCY_KEY_SCRIPT: &str = r#"
+if redis.call('GET', KEYS[1]) == ARGV[1] then
+    return redis.call('DEL', KEYS[1])
+end
+return 0
+"#;
+
+/// Lua helper shared by the scripts that change a task's status.
+///
+/// `index_status` moves a task into the status index set for its new status,
+/// removing it from the sets of every other status, and drops entries of that
+/// set older than the trim cutoff. Its six arguments are the ones
+/// `TaskQueue::status_index_args` produces: status key prefix of the task's
+/// queue, task id, new status, timestamp, trim cutoff ('' to keep every
+/// entry) and a JSON array of all status names.
+macro_rules! index_status_lua {
+    () => {
+        r#"
+local function index_status(prefix, task_id, status, now, cutoff, statuses)
+    for _, other in ipairs(cjson.decode(statuses)) do
+        if other ~= status then
+            redis.call('ZREM', prefix .. other, task_id)
+        end
+    end
+    redis.call('ZADD', prefix .. status, now, task_id)
+    if cutoff ~= '' then
+        redis.call('ZREMRANGEBYSCORE', prefix .. status, '-inf', '(' .. cutoff)
+    end
+end
+"#
+    };
+}
+
+/// Enqueue a scheduled job's task unless it already exists, and persist the
+/// job's updated state in the same step.
+///
+/// KEYS[1]: task hash, KEYS[2]: queue sorted set, KEYS[3]: scheduled jobs hash.
+/// ARGV[1]: task JSON, ARGV[2]: priority score, ARGV[3]: job id, ARGV[4]: job JSON,
+/// ARGV[5..10]: status index arguments. Returns 1 if the task was enqueued, 0
+/// if it had already been submitted.
+const FIRE_SCHEDULED_JOB_SCRIPT: &str = concat!(
+    index_status_lua!(),
+    r#"
+local created = redis.call('HSETNX', KEYS[1], 'data', ARGV[1])
+if created == 1 then
+    redis.call('ZADD', KEYS[2], ARGV[2], ARGV[1])
+    index_status(unpack(ARGV, 5, 10))
+end
+redis.call('HSET', KEYS[3], ARGV[3], ARGV[4])
+return created
+"#
+);
+
+/// Cancel a waiting task, unless a worker claimed it since it was read.
+///
+/// KEYS[1]: task hash, KEYS[2]: queue sorted set, KEYS[3]: the queue's
+/// scheduled set, KEYS[4]: global scheduled set. ARGV[1]: task JSON as read,
+/// ARGV[2]: cancelled task JSON, ARGV[3]: event channel, ARGV[4]: task id,
+/// ARGV[5..10]: status index arguments. Returns the number of entries removed;
+/// nothing is written if it is 0.
+const CANCEL_TASK_SCRIPT: &str = concat!(
+    index_status_lua!(),
+    r#"
+local removed = redis.call('ZREM', KEYS[2], ARGV[1])
+    + redis.call('ZREM', KEYS[3], ARGV[1])
+    + redis.call('ZREM', KEYS[4], ARGV[1])
+if removed > 0 then
+    redis.call('HSET', KEYS[1], 'data', ARGV[2])
+    index_status(unpack(ARGV, 5, 10))
+    redis.call('PUBLISH', ARGV[3], ARGV[4])
+end
+return removed
+"#
+);
+
+/// Enqueue a task unless a task with its id is pending, processing or has a
+/// stored result or failure.
+///
+/// KEYS[1]: task hash, KEYS[2]: queue sorted set, KEYS[3]: processing set,
+/// KEYS[4]: result hash, KEYS[5]: failure hash. ARGV[1]: task JSON, ARGV[2]:
+/// score, ARGV[3]: task id, ARGV[4..9]: status index arguments. Returns 1 if
+/// the task was enqueued, 0 if the id is taken.
+const SUBMIT_NEW_TASK_SCRIPT: &str = concat!(
+    index_status_lua!(),
+    r#"
+if redis.call('EXISTS', KEYS[1]) == 1
+    or redis.call('ZSCORE', KEYS[3], ARGV[3])
+    or redis.call('EXISTS', KEYS[4]) == 1
+    or redis.call('EXISTS', KEYS[5]) == 1 then
+    return 0
+end
+redis.call('HSET', KEYS[1], 'data', ARGV[1])
+redis.call('ZADD', KEYS[2], ARGV[2], ARGV[1])
+index_status(unpack(ARGV, 4, 9))
+return 1
+"#
+);
+
+/// Record a task's new status in its queue's status index.
+///
+/// ARGV[1..6]: status index arguments (see `index_status_lua`).
+const SET_TASK_STATUS_SCRIPT: &str = concat!(index_status_lua!(), "index_status(unpack(ARGV, 1, 6))\n");
+
+/// Record that a group member finished, once per member.
+///
+/// KEYS[1]: group hash, KEYS[2]: group results hash. ARGV[1]: task id,
+/// ARGV[2]: member result JSON, ARGV[3]: 1 if the member did not succeed,
+/// ARGV[4]: group TTL in seconds, renewed on both keys. Returns the number of
+/// members still running, or -1 if the group is gone or the member was
+/// already recorded.
+const GROUP_MEMBER_DONE_SCRIPT: &str = r#"
+if redis.call('EXISTS', KEYS[1]) == 0 then
+    return -1
+end
+if redis.call('HSETNX', KEYS[2], ARGV[1], ARGV[2]) == 0 then
+    return -1
+end
+if ARGV[3] == '1' then
+    redis.call('HINCRBY', KEYS[1], 'failed', 1)
+end
+redis.call('EXPIRE', KEYS[1], ARGV[4])
+redis.call('EXPIRE', KEYS[2], ARGV[4])
+return redis.call('HINCRBY', KEYS[1], 'remaining', -1)
+"#;
+
+/// Delete a queue's pending tasks along with their task records.
+///
+/// KEYS[1]: queue sorted set, KEYS[2]: the queue's `Pending` status index.
+/// ARGV[1]: task key prefix, ARGV[2]: idempotency key prefix.
+/// Returns the number of removed tasks.
+const PURGE_QUEUE_SCRIPT: &str = r#"
+local tasks = redis.call('ZRANGE', KEYS[1], 0, -1)
+for _, task_json in ipairs(tasks) do
+    local task = cjson.decode(task_json)
+    redis.call('DEL', ARGV[1] .. task.id)
+    redis.call('ZREM', KEYS[2], task.id)
+    if type(task.idempotency_key) == 'string' then
+        local key = ARGV[2] .. task.idempotency_key
+        if redis.call('GET', key) == task.id then
+            redis.call('DEL', key)
+        end
+    end
+end
+redis.call('DEL', KEYS[1])
+return #tasks
+"#;
+
+/// Atomically claim the highest-priority runnable task of a queue.
+///
+/// A task is only claimed if a slot on each of its required resources is
+/// free; the slots are taken in the same step. Tasks whose resources are busy
+/// are skipped, so they don't hold back independent tasks queued behind them;
+/// at most ARGV[4] entries are examined per call. Entries that are not a JSON
+/// object with an id are moved to the corrupt list instead. KEYS[1]: queue
+/// sorted set, KEYS[2]: processing set, KEYS[3]: corrupt list. ARGV[1]:
+/// timestamp, ARGV[2]: resource key prefix, ARGV[3]: JSON object of resource
+/// capacities (missing entries default to 1), ARGV[4]: scan window. Returns the
+/// task JSON, or nil if no task in the window can run.
+const DEQUEUE_TASK_SCRIPT: &str = r#"
+local capacities = cjson.decode(ARGV[3])
+local window = tonumber(ARGV[4])
+local entries = redis.call('ZREVRANGE', KEYS[1], 0, window - 1)
+for _, entry in ipairs(entries) do
+    local ok, task = pcall(cjson.decode, entry)
+    if not (ok and type(task) == 'table' and task.id ~= nil) then
+        redis.log(redis.LOG_WARNING, 'Quarantining undecodable task in ' .. KEYS[1])
+        redis.call('ZREM', KEYS[1], entry)
+        redis.call('RPUSH', KEYS[3], entry)
+    else
+        local resources = task.required_resources
+        local runnable = true
+        if type(resources) == 'table' then
+            for _, resource in ipairs(resources) do
+                local key = ARGV[2] .. resource
+                if not redis.call('ZSCORE', key, task.id) then
+                    if redis.call('ZCARD', key) >= (tonumber(capacities[resource]) or 1) then
+                        runnable = false
+                        break
+                    end
+                end
+            end
+        end
+        if runnable then
+            if type(resources) == 'table' then
+                for _, resource in ipairs(resources) do
+                    redis.call('ZADD', ARGV[2] .. resource, ARGV[1], task.id)
+                end
+            end
+            redis.call('ZREM', KEYS[1], entry)
+            redis.call('ZADD', KEYS[2], ARGV[1], task.id)
+            return entry
+        end
+    end
+end
+return false
+"#;
+
+/// Number of keys moved per round trip when migrating a namespace
+const MIGRATION_BATCH_SIZE: usize = 100;
+
+/// `COUNT` hint passed to each `SCAN` call
+const SCAN_BATCH_SIZE: usize = 100;
+
+/// Queue entries `DEQUEUE_TASK_SCRIPT` looks at before giving up on a queue
+/// whose leading tasks are all waiting on busy resources
+const DEQUEUE_SCAN_WINDOW: usize = 50;
+
+/// Longest single wait between dequeue attempts in `get_next_task_blocking`
+const BLOCKING_WAIT_SLICE: Duration = Duration::from_millis(100);
+
+/// Seconds a task must have been running before `reclaim_dead_worker_tasks`
+/// treats it as orphaned
+const DEAD_WORKER_GRACE_SECONDS: i64 = 60;
+
+/// Atomically acquire a slot on every resource key, or none of them.
+///
+/// KEYS: resource holder sets. ARGV[1]: holder id, ARGV[2]: timestamp,
+/// ARGV[3..]: capacity for each key in order. Returns 1 if acquired.
+const ACQUIRE_RESOURCES_SCRIPT: &str = r#"
+for i, key in ipairs(KEYS) do
+    if not redis.call('ZSCORE', key, ARGV[1]) then
+        if redis.call('ZCARD', key) >= tonumber(ARGV[i + 2]) then
+            return 0
+        end
+    end
+end
+for _, key in ipairs(KEYS) do
+    redis.call('ZADD', key, ARGV[2], ARGV[1])
+end
+return 1
+"#;
 
 /// Configuration for the task queue
 #[derive(Debug, Clone)]
 pub struct TaskQueueConfig {
-    /// Redis connection URL
+    /// Redis connection URL (`rediss://` requires the `tls` feature)
     pub redis_url: String,
+    /// Prefix of every Redis key this queue uses
+    ///
+    /// Deployments sharing a Redis instance must use different prefixes.
+    pub key_prefix: String,
+    /// Redis ACL username, overriding any username in `redis_url`
+    pub username: Option<String>,
+    /// Redis password, overriding any password in `redis_url`
+    pub password: Option<String>,
+    /// Logical Redis database to use, overriding any database in `redis_url`
+    ///
+    /// Deployments on different databases are fully isolated, whatever their
+    /// key prefixes.
+    pub db: Option<u8>,
+    /// Seconds to wait when establishing a connection (including the TLS handshake)
+    pub connection_timeout: u64,
     /// Default queue name
     pub default_queue: String,
     /// Maximum number of connections in the pool
     pub max_connections: u32,
-    /// Task result TTL in seconds
+    /// Task result TTL in seconds (0 to keep results until evicted)
+    ///
+    /// Tasks can override it with `TaskDefinition::result_ttl`.
     pub result_ttl: u64,
     /// Failed task TTL in seconds
     pub failed_ttl: u64,
     /// Cleanup interval in seconds
     pub cleanup_interval: u64,
+    /// Upper bound in seconds on how long an idempotency key is held
+    ///
+    /// Keys are released as soon as their task finishes; the TTL only
+    /// matters if that never happens.
+    pub idempotency_ttl: u64,
+    /// Seconds a task may sit in the processing set before it is considered
+    /// abandoned and re-queued
+    ///
+    /// Should exceed the longest task timeout, otherwise tasks that are still
+    /// running get executed a second time.
+    pub visibility_timeout: u64,
+    /// Seconds after which a task still in the processing set is given up on
+    ///
+    /// Such tasks are removed and marked failed by `cleanup_expired_tasks`
+    /// instead of being redelivered. Must exceed `visibility_timeout`.
+    pub processing_max_age: u64,
+    /// Times a task is redelivered (after its worker died or it went unacked)
+    /// before it is marked failed instead (`None` for no limit)
+    ///
+    /// Redeliveries are counted in `TaskDefinition::reclaim_count`, separately
+    /// from retries.
+    pub max_redeliveries: Option<u32>,
+    /// Seconds a task group is tracked after it was submitted or a member
+    /// last finished
+    ///
+    /// A group whose remaining members take longer than this is forgotten,
+    /// and its finalizer never runs.
+    pub group_ttl: u64,
+    /// Maximum number of stored results per queue (`None` for unlimited)
+    ///
+    /// When exceeded, the oldest results and their task records are evicted
+    /// regardless of `result_ttl`.
+    pub max_results_per_queue: Option<u64>,
+    /// Number of concurrent holders allowed per external resource
+    ///
+    /// Resources a task requires that are not listed here have a capacity of 1.
+    pub resource_capacities: HashMap<String, u32>,
+    /// Raise the effective priority of tasks the longer they wait
+    /// (`None` to always take the highest priority first)
+    pub priority_aging: Option<AgingConfig>,
+    /// Gzip-compress stored results (requires the `compression` feature)
+    ///
+    /// Compressed results are decompressed transparently when read back.
+    pub compress_results: bool,
+    /// Results shorter than this many bytes are stored uncompressed
+    pub compression_threshold: usize,
+    /// Largest serialized task payload (`data`) accepted on submit, in bytes
+    /// (`None` for unlimited)
+    ///
+    /// Larger tasks are rejected with a config error before anything is
+    /// written.
+    pub max_payload_bytes: Option<usize>,
+    /// Largest `TaskDefinition::chain_depth` a task may reach (`None` for
+    /// unlimited)
+    ///
+    /// Guards against tasks that keep re-enqueueing themselves through
+    /// `Task::on_success` or retries: a follow-up or retry past this depth is
+    /// marked failed instead. Should exceed the largest `max_retries` in use.
+    pub max_chain_depth: Option<u32>,
+    /// Most pending tasks a queue may hold before submissions are rejected
+    /// with `TaskError::QueueFull` (`None` for unlimited)
+    ///
+    /// Checked by `submit_task`, `submit_new_task`, `submit_tasks` and
+    /// `submit_group` at the cost of one extra round trip per submission (per
+    /// queue for batches). Concurrent submitters can overshoot it slightly.
+    /// Scheduled tasks, retries, reclaimed tasks, follow-ups and group
+    /// finalizers are not held back.
+    pub max_queue_depth: Option<u64>,
+    /// How long to wait before reconnecting after Redis becomes unreachable
+    pub reconnect_backoff: ReconnectBackoff,
+    /// Defaults for tasks submitted to particular queues, by queue name
+    pub queue_defaults: HashMap<String, QueueDefaults>,
+}
+
+/// Settings applied to tasks submitted to a queue that don't set their own
+///
+/// A task sets its priority or retry configuration with the
+/// `TaskDefinitionBuilder`, `TaskClient` options such as
+/// `submit_with_priority`, `TaskDefinition::set_priority` and
+/// `set_retry_config`, or a non-default value from its `Task` impl.
+/// Anything a task sets wins, even if it is the default value.
+#[derive(Debug, Clone, Default)]
+pub struct QueueDefaults {
+    /// Priority for tasks that don't set one
+    pub priority: Option<TaskPriority>,
+    /// Retry configuration for tasks that don't set one
+    pub retry_config: Option<RetryConfig>,
+}
+
+/// Backoff between attempts to reach an unreachable Redis
+///
+/// After a failed connection attempt, calls needing Redis fail immediately
+/// with `TaskError::Redis` until the delay has passed, instead of each
+/// trying to connect again. The delay starts at `initial_delay_ms` and
+/// doubles with every further failure up to `max_delay_ms`; with
+/// `full_jitter` a random delay between zero and that bound is used, so
+/// workers that lost Redis together do not reconnect together. The first
+/// successful connection resets it.
+#[derive(Debug, Clone, Copy)]
+pub struct ReconnectBackoff {
+    /// Delay after the first failure, in milliseconds
+    pub initial_delay_ms: u64,
+    /// Longest delay, in milliseconds
+    pub max_delay_ms: u64,
+    /// Pick a random delay up to the exponential bound
+    pub full_jitter: bool,
+}
+
+impl Default for ReconnectBackoff {
+    fn default() -> Self {
+        Self {
+            initial_delay_ms: 100,
+            max_delay_ms: 30_000,
+            full_jitter: true,
+        }
+    }
+}
+
+impl ReconnectBackoff {
+    /// Delay before the next attempt after `failures` consecutive failures
+    fn delay(&self, failures: u32) -> Duration {
+        let bound = self
+            .initial_delay_ms
+            .saturating_mul(1u64 << failures.saturating_sub(1).min(32))
+            .min(self.max_delay_ms);
+        let delay = if self.full_jitter {
+            rand::thread_rng().gen_range(0..=bound)
+        } else {
+            bound
+        };
+        Duration::from_millis(delay)
+    }
+}
+
+/// Priority aging settings
+///
+/// A task's queue score is its `TaskPriority` value plus one point for every
+/// `interval` seconds it has been waiting. The priority levels are 5 points
+/// apart, so with `interval: 60` a `Low` task overtakes newly submitted
+/// `Normal` tasks after 5 minutes and `Critical` ones after 15. Tasks of
+/// equal priority are taken oldest first.
+///
+/// The boost is folded into the score when a task enters its queue, as its
+/// priority minus its enqueue time in intervals; the order this gives at any
+/// moment is the same as adding the age, with no need to rescore. Tasks
+/// enqueued while aging was off keep their plain priority scores, which sort
+/// far above the negative aged scores, so change the setting on a drained
+/// queue.
+#[derive(Debug, Clone, Copy)]
+pub struct AgingConfig {
+    /// Seconds of waiting worth one priority point
+    pub interval: u64,
 }
 
 impl Default for TaskQueueConfig {
     fn default() -> Self {
         Self {
             redis_url: "redis://127.0.0.1:6379".to_string(),
+            key_prefix: "dtq".to_string(),
+            username: None,
+            password: None,
+            db: None,
+            connection_timeout: 10,
             default_queue: "default".to_string(),
             max_connections: 10,
             result_ttl: 86400, // 24 hours
             failed_ttl: 604800, // 7 days
             cleanup_interval: 3600, // 1 hour
+            idempotency_ttl: 86400, // 24 hours
+            visibility_timeout: 900, // 15 minutes
+            processing_max_age: 86400, // 24 hours
+            max_redeliveries: Some(10),
+            group_ttl: 604800, // 7 days
+            max_results_per_queue: None,
+            resource_capacities: HashMap::new(),
+            priority_aging: None,
+            compress_results: false,
+            compression_threshold: 1024,
+            max_payload_bytes: None,
+            max_chain_depth: None,
+            max_queue_depth: None,
+            reconnect_backoff: ReconnectBackoff::default(),
+            queue_defaults: HashMap::new(),
         }
     }
 }
 
 /// Task queue statistics
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct QueueStats {
     pub pending_tasks: u64,
     pub processing_tasks: u64,
     pub completed_tasks: u64,
     pub failed_tasks: u64,
     pub scheduled_tasks: u64,
+    pub stored_results: u64,
+    /// Tasks dropped on dequeue because their `expires_at` had passed
+    #[serde(default)]
+    pub expired_tasks: u64,
+}
+
+impl fmt::Display for QueueStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "pending: {}, processing: {}, scheduled: {}, completed: {}, failed: {}, expired: {}, stored results: {}",
+            self.pending_tasks,
+            self.processing_tasks,
+            self.scheduled_tasks,
+            self.completed_tasks,
+            self.failed_tasks,
+            self.expired_tasks,
+            self.stored_results
+        )
+    }
+}
+
+/// A queue known to Redis
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QueueInfo {
+    /// Queue name
+    pub name: String,
+    /// Whether workers are currently skipping the queue
+    pub paused: bool,
+}
+
+/// Result of submitting a task
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SubmitOutcome {
+    /// The task was added to the queue
+    Enqueued(TaskId),
+    /// A task with the same idempotency key is already pending or running
+    Deduplicated(TaskId),
+}
+
+impl SubmitOutcome {
+    /// Id of the enqueued task, or of the existing task for a duplicate
+    pub fn task_id(&self) -> TaskId {
+        match self {
+            SubmitOutcome::Enqueued(task_id) | SubmitOutcome::Deduplicated(task_id) => *task_id,
+        }
+    }
+
+    /// Whether the submission was dropped as a duplicate
+    pub fn is_deduplicated(&self) -> bool {
+        matches!(self, SubmitOutcome::Deduplicated(_))
+    }
+}
+
+/// A pending task together with its queue score
+#[derive(Debug, Clone)]
+pub struct QueuedTask {
+    /// The pending task
+    pub task: TaskDefinition,
+    /// Sorted set score (the task's priority less a fraction for its place
+    /// in submission order, or less its enqueue time with priority aging)
+    pub score: f64,
+}
+
+/// Connectivity summary returned by `TaskQueue::health_check`
+#[derive(Debug, Clone)]
+pub struct HealthStatus {
+    /// Whether the store answered within the connection timeout
+    pub healthy: bool,
+    /// Round-trip time of the ping, if it was answered
+    pub latency: Option<Duration>,
+    /// Connections open in the pool
+    pub connections: u32,
+    /// Open connections not currently in use
+    pub idle_connections: u32,
+    /// Why the check failed
+    pub error: Option<String>,
+}
+
+/// Liveness record a worker writes to Redis on every heartbeat
+#[derive(Debug, Clone)]
+pub struct WorkerRecord {
+    /// Worker identifier
+    pub worker_id: String,
+    /// Queues the worker processes
+    pub queues: Vec<String>,
+    /// Number of tasks the worker is executing
+    pub active_tasks: usize,
+    /// Time of the heartbeat that wrote this record
+    pub last_heartbeat: DateTime<Utc>,
+}
+
+/// Outcome of a namespace migration
+#[derive(Debug, Clone, Default)]
+pub struct NamespaceMigration {
+    /// Whether this was a dry run (nothing was moved)
+    pub dry_run: bool,
+    /// Keys moved (or that would be moved) as `(old, new)` pairs
+    pub moved: Vec<(String, String)>,
+    /// Keys left in place because the destination key already exists
+    pub conflicts: Vec<String>,
+}
+
+/// bb8 connection manager for Redis connections
+#[derive(Debug, Clone)]
+pub struct RedisConnectionManager {
+    client: Client,
+}
+
+impl RedisConnectionManager {
+    /// Create a new connection manager for the given Redis client
+    pub fn new(client: Client) -> Self {
+        Self { client }
+    }
+}
+
+#[async_trait]
+impl ManageConnection for RedisConnectionManager {
+    type Connection = Connection;
+    type Error = RedisError;
+
+    async fn connect(&self) -> Result<Self::Connection, Self::Error> {
+        self.client.get_async_connection().await
+    }
+
+    async fn is_valid(&self, conn: &mut Self::Connection) -> Result<(), Self::Error> {
+        redis::cmd("PING").query_async::<_, String>(conn).await?;
+        Ok(())
+    }
+
+    fn has_broken(&self, _conn: &mut Self::Connection) -> bool {
+        false
+    }
+}
+
+/// Escape glob metacharacters so `value` matches literally in `KEYS`/`SCAN` patterns
+fn escape_glob(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        if matches!(c, '*' | '?' | '[' | ']' | '\\') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
 }
 
+/// Pooled Redis connection checked out from the queue's pool
+pub type RedisConnection<'a> = PooledConnection<'a, RedisConnectionManager>;
+
 /// Distributed task queue with Redis backend
 #[derive(Debug)]
 pub struct TaskQueue {
-    client: Client,
+    storage: Storage,
     config: TaskQueueConfig,
-    connections: Arc<RwLock<HashMap<String, Connection>>>,
+    listeners: EventListeners,
+    results: Arc<dyn ResultBackend>,
+}
+
+/// Where a queue keeps its tasks
+#[derive(Debug)]
+enum Storage {
+    Redis {
+        client: Client,
+        pool: Pool<RedisConnectionManager>,
+        reconnect: StdMutex<ReconnectState>,
+    },
+    /// See `TaskQueue::from_backend`
+    Backend(Arc<dyn QueueBackend>),
+}
+
+/// Consecutive connection failures and when to try again
+/// (see `ReconnectBackoff`)
+#[derive(Debug, Default)]
+struct ReconnectState {
+    failures: u32,
+    retry_at: Option<Instant>,
 }
 
 impl TaskQueue {
     /// Create a new task queue with the given configuration
     pub async fn new(config: TaskQueueConfig) -> TaskResult<Self> {
-        let client = Client::open(config.redis_url.as_str())
-            .map_err(|e| TaskError::queue_operation("connect", e.to_string()))?;
+        if config.compress_results && !cfg!(feature = "compression") {
+            return Err(TaskError::config(
+                "compress_results requires the `compression` feature",
+            ));
+        }
+        if config.processing_max_age <= config.visibility_timeout {
+            return Err(TaskError::config(
+                "processing_max_age must exceed visibility_timeout",
+            ));
+        }
+        if config.reconnect_backoff.initial_delay_ms == 0
+            || config.reconnect_backoff.max_delay_ms < config.reconnect_backoff.initial_delay_ms
+        {
+            return Err(TaskError::config(
+                "reconnect_backoff needs 0 < initial_delay_ms <= max_delay_ms",
+            ));
+        }
 
-        // Test the connection
-        let mut conn = client
-            .get_async_connection()
+        let client = Self::open_client(&config)?;
+        let connection_timeout = Duration::from_secs(config.connection_timeout);
+
+        let pool = Pool::builder()
+            .max_size(config.max_connections)
+            .connection_timeout(connection_timeout)
+            .build(RedisConnectionManager::new(client.clone()))
             .await
             .map_err(|e| TaskError::queue_operation("connect", e.to_string()))?;
 
         // Test basic Redis operations
-        redis::cmd("PING")
-            .query_async::<_, String>(&mut conn)
+        let ping = async {
+            let mut conn = pool
+                .get()
+                .await
+                .map_err(|e| TaskError::queue_operation("connect", e.to_string()))?;
+
+            redis::cmd("PING")
+                .query_async::<_, String>(&mut *conn)
+                .await
+                .map_err(|e| TaskError::queue_operation("ping", e.to_string()))
+        };
+        tokio::time::timeout(connection_timeout, ping)
             .await
-            .map_err(|e| TaskError::queue_operation("ping", e.to_string()))?;
+            .map_err(|_| TaskError::timeout("connect"))??;
 
-        info!("Connected to Redis at {}", config.redis_url);
+        info!(
+            "Connected to Redis at {} (pool size {})",
+            config.redis_url, config.max_connections
+        );
+
+        let results = Arc::new(RedisResultBackend::new(
+            pool.clone(),
+            format!("{}:{}", config.key_prefix, RESULTS_KEY),
+            format!("{}:{}", config.key_prefix, FAILED_KEY),
+            format!("{}:{}:task:", config.key_prefix, QUEUE_KEY),
+            config.result_ttl,
+            config.failed_ttl,
+            config.max_results_per_queue,
+        ));
 
         Ok(Self {
-            client,
+            storage: Storage::Redis {
+                client,
+                pool,
+                reconnect: StdMutex::new(ReconnectState::default()),
+            },
             config,
-            connections: Arc::new(RwLock::new(HashMap::new())),
+            listeners: EventListeners::default(),
+            results,
         })
     }
 
-    /// Create a new task queue with default configuration
-    pub async fn new_default() -> TaskResult<Self> {
-        Self::new(TaskQueueConfig::default()).await
+    /// Create a task queue that keeps its tasks and results in `backend`
+    /// instead of Redis
+    ///
+    /// Workers, clients and schedulers work unchanged on such a queue. Only
+    /// the operations `QueueBackend` covers are available: the others,
+    /// including pausing queues, groups, listing tasks by status, worker
+    /// heartbeat records, reclaiming and cleanup, fail with
+    /// `TaskError::Config`. Tasks with idempotency keys or required
+    /// resources are rejected and queues are never paused. Workers skip the
+    /// Redis-only loops on such a queue (see `uses_redis`). `key_prefix` and
+    /// the Redis connection settings are ignored.
+    pub fn from_backend<B>(config: TaskQueueConfig, backend: B) -> TaskResult<Self>
+    where
+        B: QueueBackend + ResultBackend + 'static,
+    {
+        if config.compress_results && !cfg!(feature = "compression") {
+            return Err(TaskError::config(
+                "compress_results requires the `compression` feature",
+            ));
+        }
+
+        let backend = Arc::new(backend);
+        Ok(Self {
+            storage: Storage::Backend(backend.clone()),
+            config,
+            listeners: EventListeners::default(),
+            results: backend,
+        })
     }
 
-    /// Get a Redis connection
-    async fn get_connection(&self) -> TaskResult<Connection> {
-        self.client
-            .get_async_connection()
-            .await
-            .map_err(|e| TaskError::queue_operation("get_connection", e.to_string()))
+    /// Create a task queue held in process memory, needing no Redis server
+    ///
+    /// For tests and examples; see `MemoryBackend` and `from_backend`.
+    pub fn new_in_memory(config: TaskQueueConfig) -> TaskResult<Self> {
+        Self::from_backend(config, MemoryBackend::new())
     }
 
-    /// Submit a task to the queue
-    pub async fn submit_task(&self, mut task_def: TaskDefinition) -> TaskResult<TaskId> {
-        let mut conn = self.get_connection().await?;
-        
-        // Use default queue if not specified
-        if task_def.queue.is_empty() {
-            task_def.queue = self.config.default_queue.clone();
+    /// Whether the queue keeps its tasks in Redis rather than a `QueueBackend`
+    ///
+    /// Heartbeat records, reclaiming, cleanup and the other Redis-only
+    /// operations fail with `TaskError::Config` when this is `false`.
+    pub fn uses_redis(&self) -> bool {
+        self.backend().is_none()
+    }
+
+    /// The queue's backend, or `None` for Redis
+    fn backend(&self) -> Option<&dyn QueueBackend> {
+        match &self.storage {
+            Storage::Redis { .. } => None,
+            Storage::Backend(backend) => Some(backend.as_ref()),
         }
+    }
 
-        let task_json = serde_json::to_string(&task_def)?;
-        let queue_key = format!("{}:{}", QUEUE_KEY, task_def.queue);
-        let task_key = format!("{}:task:{}", QUEUE_KEY, task_def.id);
-        
-        // Add task to priority queue (using sorted set with priority as score)
-        let priority_score = task_def.priority.clone() as i32;
-        
-        redis::pipe()
-            .zadd(&queue_key, priority_score, &task_json)
-            .ignore()
-            .hset(
-                &task_key,
-                &[("data", &task_json)],
-            )
-            .ignore()
-            .query_async(&mut conn)
-            .await
-            .map_err(|e| TaskError::queue_operation("submit", e.to_string()))?;
+    /// Fill in the task's queue defaults (see `QueueDefaults`)
+    fn apply_queue_defaults(&self, task_def: &mut TaskDefinition) {
+        let Some(defaults) = self.config.queue_defaults.get(&task_def.queue) else {
+            return;
+        };
+        if let Some(priority) = &defaults.priority {
+            if !task_def.priority_explicit {
+                task_def.priority = priority.clone();
+            }
+        }
+        if let Some(retry_config) = &defaults.retry_config {
+            if !task_def.retry_config_explicit {
+                task_def.retry_config = retry_config.clone();
+            }
+        }
+    }
 
-        debug!("Submitted task {} to queue {}", task_def.id, task_def.queue);
-        Ok(task_def.id)
+    /// Reject a task whose payload exceeds `max_payload_bytes`
+    fn check_payload_size(&self, task_def: &TaskDefinition) -> TaskResult<()> {
+        match self.config.max_payload_bytes {
+            Some(max) if task_def.data.len() > max => Err(TaskError::config(format!(
+                "payload of task {} ({}) is {} bytes, over max_payload_bytes of {}",
+                task_def.id,
+                task_def.name,
+                task_def.data.len(),
+                max
+            ))),
+            _ => Ok(()),
+        }
     }
 
-    /// Submit a scheduled task
-    pub async fn submit_scheduled_task(&self, mut task_def: TaskDefinition) -> TaskResult<TaskId> {
-        let mut conn = self.get_connection().await?;
-        
-        if task_def.queue.is_empty() {
-            task_def.queue = self.config.default_queue.clone();
+    /// Reject `adding` more tasks to a queue if that would go past
+    /// `max_queue_depth`
+    async fn check_queue_depth(&self, queue_name: &str, adding: u64) -> TaskResult<()> {
+        let Some(max) = self.config.max_queue_depth else {
+            return Ok(());
+        };
+        let depth = match self.backend() {
+            Some(backend) => backend.stats(queue_name).await?.pending_tasks,
+            None => {
+                let mut conn = self.get_connection().await?;
+                redis::cmd("ZCARD")
+                    .arg(format!("{}:{}", self.key(QUEUE_KEY), queue_name))
+                    .query_async(&mut *conn)
+                    .await
+                    .map_err(|e| TaskError::queue_operation("check_queue_depth", e.to_string()))?
+            }
+        };
+        if depth + adding > max {
+            return Err(TaskError::QueueFull {
+                queue: queue_name.to_string(),
+                depth,
+            });
         }
+        Ok(())
+    }
 
-        let task_json = serde_json::to_string(&task_def)?;
-        let task_key = format!("{}:task:{}", QUEUE_KEY, task_def.id);
-        let scheduled_at_timestamp = task_def
-            .scheduled_at
-            .ok_or_else(|| TaskError::queue_operation("submit_scheduled", "missing scheduled_at"))?
-            .timestamp();
+    /// Reject a batch that would take any of its queues past `max_queue_depth`
+    async fn check_batch_depth(&self, task_defs: &[TaskDefinition]) -> TaskResult<()> {
+        if self.config.max_queue_depth.is_none() {
+            return Ok(());
+        }
+        let mut adding: BTreeMap<&str, u64> = BTreeMap::new();
+        for task_def in task_defs {
+            let queue_name = match task_def.queue.as_str() {
+                "" => self.config.default_queue.as_str(),
+                queue_name => queue_name,
+            };
+            *adding.entry(queue_name).or_default() += 1;
+        }
+        for (queue_name, count) in adding {
+            self.check_queue_depth(queue_name, count).await?;
+        }
+        Ok(())
+    }
 
-        // Add to scheduled tasks sorted set
-        redis::pipe()
-            .zadd(SCHEDULED_KEY, scheduled_at_timestamp, &task_json)
-            .ignore()
-            .hset(
-                &task_key,
-                &[("data", &task_json)],
-            )
-            .ignore()
-            .query_async(&mut conn)
-            .await
-            .map_err(|e| TaskError::queue_operation("submit_scheduled", e.to_string()))?;
+    /// Reject a task whose `chain_depth` exceeds `max_chain_depth`
+    pub fn check_chain_depth(&self, task_def: &TaskDefinition) -> TaskResult<()> {
+        match self.config.max_chain_depth {
+            Some(max) if task_def.chain_depth > max => Err(TaskError::permanent(format!(
+                "task {} ({}) exceeded max_chain_depth of {}; is it re-enqueueing itself?",
+                task_def.id, task_def.name, max
+            ))),
+            _ => Ok(()),
+        }
+    }
 
-        debug!("Submitted scheduled task {} for {:?}", task_def.id, task_def.scheduled_at);
-        Ok(task_def.id)
+    /// Reject what a backend cannot store
+    fn check_backend_task(task_def: &TaskDefinition) -> TaskResult<()> {
+        if task_def.idempotency_key.is_some() {
+            return Err(TaskError::config("idempotency keys require the Redis backend"));
+        }
+        if !task_def.required_resources.is_empty() {
+            return Err(TaskError::config("required resources require the Redis backend"));
+        }
+        Ok(())
     }
 
-    /// Get the next task from a queue
-    pub async fn get_next_task(&self, queue_name: &str) -> TaskResult<Option<TaskDefinition>> {
-        let mut conn = self.get_connection().await?;
-        let queue_key = format!("{}:{}", QUEUE_KEY, queue_name);
+    /// Store finished tasks in `backend` instead of Redis
+    ///
+    /// Task state still lives in Redis; only completed and failed outcomes go
+    /// to the backend, and clients waiting for results read them from there.
+    /// `result_ttl`, `failed_ttl` and `max_results_per_queue` only apply to
+    /// the default Redis backend.
+    pub fn with_result_backend<B>(mut self, backend: B) -> Self
+    where
+        B: ResultBackend + 'static,
+    {
+        self.results = Arc::new(backend);
+        self
+    }
 
-        // Get highest priority task (ZREVRANGE gets highest scores first)
-        let tasks: Vec<String> = redis::cmd("ZREVRANGE")
-            .arg(&queue_key)
-            .arg(0)
-            .arg(0)
-            .query_async(&mut conn)
-            .await
-            .map_err(|e| TaskError::queue_operation("get_next", e.to_string()))?;
+    /// Build the Redis client, applying any explicit credentials
+    fn open_client(config: &TaskQueueConfig) -> TaskResult<Client> {
+        if config.redis_url.starts_with("rediss://") && !cfg!(feature = "tls") {
+            return Err(TaskError::config("rediss:// URLs require the `tls` feature"));
+        }
 
-        if let Some(task_json) = tasks.first() {
-            let task_def: TaskDefinition = serde_json::from_str(task_json)?;
-            
-            // Move task to processing queue
-            redis::pipe()
-                .zrem(&queue_key, task_json)
-                .ignore()
-                .zadd(PROCESSING_KEY, chrono::Utc::now().timestamp(), task_json)
-                .ignore()
-                .query_async(&mut conn)
-                .await
-                .map_err(|e| TaskError::queue_operation("move_to_processing", e.to_string()))?;
+        let mut info = config
+            .redis_url
+            .as_str()
+            .into_connection_info()
+            .map_err(|e| TaskError::config(format!("invalid redis_url: {}", e)))?;
 
-            debug!("Retrieved task {} from queue {}", task_def.id, queue_name);
-            Ok(Some(task_def))
-        } else {
-            Ok(None)
+        if let Some(username) = &config.username {
+            info.redis.username = Some(username.clone());
+        }
+        if let Some(password) = &config.password {
+            info.redis.password = Some(password.clone());
+        }
+        if let Some(db) = config.db {
+            info.redis.db = i64::from(db);
         }
-    }
 
-    /// Move scheduled tasks that are ready to the appropriate queues
-    pub async fn process_scheduled_tasks(&self) -> TaskResult<u64> {
-        let mut conn = self.get_connection().await?;
-        let now = chrono::Utc::now().timestamp();
+        Client::open(info).map_err(|e| TaskError::queue_operation("connect", e.to_string()))
+    }
 
-        // Get all tasks scheduled before now
-        let scheduled_tasks: Vec<String> = redis::cmd("ZRANGEBYSCORE")
-            .arg(SCHEDULED_KEY)
-            .arg("-inf")
-            .arg(now)
-            .query_async(&mut conn)
-            .await
-            .map_err(|e| TaskError::queue_operation("get_scheduled", e.to_string()))?;
+    /// Create a new task queue with default configuration
+    pub async fn new_default() -> TaskResult<Self> {
+        Self::new(TaskQueueConfig::default()).await
+    }
 
-        let mut processed_count = 0;
-        
-        for task_json in scheduled_tasks {
-            let mut task_def: TaskDefinition = serde_json::from_str(&task_json)?;
-            task_def.status = TaskStatus::Pending;
-            
-            let updated_json = serde_json::to_string(&task_def)?;
-            let queue_key = format!("{}:{}", QUEUE_KEY, task_def.queue);
-            let priority_score = task_def.priority.clone() as i32;
+    /// Get the queue configuration
+    pub fn config(&self) -> &TaskQueueConfig {
+        &self.config
+    }
 
-            // Move from scheduled to queue
-            redis::pipe()
-                .zrem(SCHEDULED_KEY, &task_json)
-                .ignore()
-                .zadd(&queue_key, &updated_json, priority_score)
-                .ignore()
-                .hset(
-                    format!("{}:task:{}", QUEUE_KEY, task_def.id),
-                    &[("data", &updated_json)],
-                )
-                .ignore()
-                .query_async(&mut conn)
-                .await
-                .map_err(|e| TaskError::queue_operation("move_scheduled", e.to_string()))?;
+    /// Full Redis key for one of the queue's key names
+    fn key(&self, name: &str) -> String {
+        format!("{}:{}", self.config.key_prefix, name)
+    }
 
-            processed_count += 1;
-            debug!("Moved scheduled task {} to queue {}", task_def.id, task_def.queue);
+    /// Check out a Redis connection from the pool
+    ///
+    /// The connection is returned to the pool when dropped. While backing
+    /// off after a failed connection (see `ReconnectBackoff`) this fails
+    /// with `TaskError::Redis` without trying to connect.
+    async fn get_connection(&self) -> TaskResult<RedisConnection<'_>> {
+        let (pool, reconnect) = match &self.storage {
+            Storage::Redis { pool, reconnect, .. } => (pool, reconnect),
+            Storage::Backend(_) => {
+                return Err(TaskError::config(
+                    "this operation requires the Redis backend",
+                ))
+            }
+        };
+        if let Some(remaining) = self.reconnect_delay() {
+            return Err(TaskError::Redis(RedisError::from((
+                ErrorKind::IoError,
+                "Redis unavailable",
+                format!("reconnecting in {:?}", remaining),
+            ))));
         }
 
-        if processed_count > 0 {
-            info!("Processed {} scheduled tasks", processed_count);
+        match pool.get().await {
+            Ok(conn) => {
+                let mut state = reconnect.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+                if state.failures > 0 {
+                    info!("Reconnected to Redis after {} failed attempts", state.failures);
+                    *state = ReconnectState::default();
+                }
+                Ok(conn)
+            }
+            Err(e) => {
+                let mut state = reconnect.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+                state.failures = state.failures.saturating_add(1);
+                let delay = self.config.reconnect_backoff.delay(state.failures);
+                state.retry_at = Some(Instant::now() + delay);
+                if state.failures == 1 {
+                    warn!("Redis unavailable, backing off: {}", e);
+                } else {
+                    debug!("Reconnect attempt {} failed, retrying in {:?}: {}", state.failures, delay, e);
+                }
+                Err(match e {
+                    bb8::RunError::User(e) => TaskError::Redis(e),
+                    bb8::RunError::TimedOut => TaskError::Redis(RedisError::from((
+                        ErrorKind::IoError,
+                        "Timed out connecting to Redis",
+                    ))),
+                })
+            }
         }
+    }
 
-        Ok(processed_count)
+    /// Time left before the next attempt to reach an unreachable Redis
+    ///
+    /// `None` while Redis is reachable (or the queue is not on Redis). Workers
+    /// use this to pause polling during an outage.
+    pub fn reconnect_delay(&self) -> Option<Duration> {
+        let Storage::Redis { reconnect, .. } = &self.storage else {
+            return None;
+        };
+        let retry_at = reconnect
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .retry_at?;
+        let remaining = retry_at.saturating_duration_since(Instant::now());
+        (!remaining.is_zero()).then_some(remaining)
     }
 
-    /// Mark a task as completed
-    pub async fn mark_task_completed(&self, task_def: &TaskDefinition) -> TaskResult<()> {
-        let mut conn = self.get_connection().await?;
-        let task_json = serde_json::to_string(task_def)?;
+    /// Ping Redis and report whether it is reachable
+    ///
+    /// An unreachable or slow Redis is reported in the status rather than as
+    /// an error, so this suits liveness and readiness probes. Queues on
+    /// another backend are always healthy.
+    pub async fn health_check(&self) -> TaskResult<HealthStatus> {
+        let Storage::Redis { pool, .. } = &self.storage else {
+            return Ok(HealthStatus {
+                healthy: true,
+                latency: None,
+                connections: 0,
+                idle_connections: 0,
+                error: None,
+            });
+        };
 
-        redis::pipe()
-            .zrem(PROCESSING_KEY, &task_json)
-            .ignore()
-            .hset(
-                format!("{}:result:{}", RESULTS_KEY, task_def.id),
-                &[("data", &task_json)],
-            )
-            .ignore()
-            .expire(
-                format!("{}:result:{}", RESULTS_KEY, task_def.id),
-                self.config.result_ttl as usize,
-            )
-            .ignore()
-            .hset(
-                format!("{}:task:{}", QUEUE_KEY, task_def.id),
-                &[("data", &task_json)],
-            )
-            .ignore()
-            .query_async(&mut conn)
-            .await
-            .map_err(|e| TaskError::queue_operation("mark_completed", e.to_string()))?;
+        let started = Instant::now();
+        let ping = async {
+            let mut conn = self.get_connection().await?;
+            redis::cmd("PING")
+                .query_async::<_, String>(&mut *conn)
+                .await
+                .map_err(|e| TaskError::queue_operation("ping", e.to_string()))
+        };
+        let outcome = match tokio::time::timeout(Duration::from_secs(self.config.connection_timeout), ping).await {
+            Ok(Ok(_)) => Ok(started.elapsed()),
+            Ok(Err(e)) => Err(e.to_string()),
+            Err(_) => Err(TaskError::timeout("health_check").to_string()),
+        };
 
-        debug!("Marked task {} as completed", task_def.id);
-        Ok(())
+        let state = pool.state();
+        Ok(HealthStatus {
+            healthy: outcome.is_ok(),
+            latency: outcome.as_ref().ok().copied(),
+            connections: state.connections,
+            idle_connections: state.idle_connections,
+            error: outcome.err(),
+        })
     }
 
-    /// Mark a task as failed
-    pub async fn mark_task_failed(&self, task_def: &TaskDefinition) -> TaskResult<()> {
-        let mut conn = self.get_connection().await?;
-        let task_json = serde_json::to_string(task_def)?;
+    /// Subscribe to the completion events of a task
+    ///
+    /// The task id is published on the task's event channel whenever its
+    /// status changes: when it starts, is requeued, or reaches a terminal
+    /// state. Pub/sub needs a dedicated connection, so this
+    /// opens one outside the pool.
+    pub async fn subscribe_task_events(&self, task_id: TaskId) -> TaskResult<PubSub> {
+        self.subscribe_many_task_events(&[task_id]).await
+    }
 
-        redis::pipe()
-            .zrem(PROCESSING_KEY, &task_json)
-            .ignore()
-            .hset(
-                format!("{}:failed:{}", FAILED_KEY, task_def.id),
-                &[("data", &task_json)],
-            )
-            .ignore()
-            .expire(
-                format!("{}:failed:{}", FAILED_KEY, task_def.id),
-                self.config.failed_ttl as usize,
-            )
-            .ignore()
-            .hset(
-                format!("{}:task:{}", QUEUE_KEY, task_def.id),
-                &[("data", &task_json)],
-            )
-            .ignore()
-            .query_async(&mut conn)
+    /// Subscribe to the events of several tasks on one connection
+    ///
+    /// Each message's payload is the id of the task it concerns.
+    pub async fn subscribe_many_task_events(&self, task_ids: &[TaskId]) -> TaskResult<PubSub> {
+        let Storage::Redis { client, .. } = &self.storage else {
+            return Err(TaskError::config("task events require the Redis backend"));
+        };
+        let connect = client.get_async_connection();
+        let mut pubsub = tokio::time::timeout(Duration::from_secs(self.config.connection_timeout), connect)
             .await
-            .map_err(|e| TaskError::queue_operation("mark_failed", e.to_string()))?;
+            .map_err(|_| TaskError::timeout("subscribe"))?
+            .map_err(|e| TaskError::queue_operation("subscribe", e.to_string()))?
+            .into_pubsub();
 
-        debug!("Marked task {} as failed", task_def.id);
-        Ok(())
+        let channels: Vec<String> = task_ids
+            .iter()
+            .map(|task_id| format!("{}:{}", self.key(EVENTS_KEY), task_id))
+            .collect();
+        pubsub
+            .subscribe(channels)
+            .await
+            .map_err(|e| TaskError::queue_operation("subscribe", e.to_string()))?;
+
+        Ok(pubsub)
     }
 
-    /// Requeue a task for retry
-    pub async fn requeue_task(&self, task_def: &TaskDefinition) -> TaskResult<()> {
-        if task_def.scheduled_at.is_some() {
-            self.submit_scheduled_task(task_def.clone()).await?;
-        } else {
-            self.submit_task(task_def.clone()).await?;
+    /// Collect every key matching `pattern` using non-blocking `SCAN`
+    ///
+    /// The result is sorted and free of the duplicates `SCAN` may return.
+    async fn scan_keys(
+        conn: &mut RedisConnection<'_>,
+        pattern: &str,
+        operation: &str,
+    ) -> TaskResult<Vec<String>> {
+        let mut keys = Vec::new();
+        let mut cursor: u64 = 0;
+
+        loop {
+            let (next_cursor, batch): (u64, Vec<String>) = redis::cmd("SCAN")
+                .arg(cursor)
+                .arg("MATCH")
+                .arg(pattern)
+                .arg("COUNT")
+                .arg(SCAN_BATCH_SIZE)
+                .query_async(&mut **conn)
+                .await
+                .map_err(|e| TaskError::queue_operation(operation, e.to_string()))?;
+
+            keys.extend(batch);
+            cursor = next_cursor;
+            if cursor == 0 {
+                break;
+            }
+        }
+
+        keys.sort();
+        keys.dedup();
+        Ok(keys)
+    }
+
+    /// Register a listener for task lifecycle events
+    ///
+    /// Receives `Queued` events for tasks submitted through this queue, and
+    /// the events of workers running on it.
+    pub async fn add_event_listener<L>(&self, listener: L)
+    where
+        L: EventListener + 'static,
+    {
+        self.listeners.add(Arc::new(listener)).await;
+    }
+
+    /// Deliver an event to the registered listeners
+    pub(crate) async fn emit_event(&self, event: TaskEvent) {
+        self.listeners.emit(event).await;
+    }
+
+    /// Sorted set score for a task entering its queue now
+    ///
+    /// Without aging, the task's submission sequence number is subtracted as
+    /// a fraction of a point, so tasks of equal priority are taken in
+    /// submission order and never pass the next priority down. The fraction
+    /// wraps every `SEQUENCE_SPAN` submissions and is exact for priorities up
+    /// to 2^21 in magnitude. Aged scores already order tasks by enqueue time.
+    fn queue_score(&self, priority: &TaskPriority, sequence: u64) -> f64 {
+        let priority = priority.value() as f64;
+        match self.config.priority_aging {
+            Some(aging) => {
+                let now = chrono::Utc::now().timestamp_millis() as f64 / 1000.0;
+                priority - now / aging.interval.max(1) as f64
+            }
+            None => priority - (sequence % SEQUENCE_SPAN) as f64 / SEQUENCE_SPAN as f64,
+        }
+    }
+
+    /// Move a task entry that cannot be deserialized to the corrupt list
+    ///
+    /// The raw entry is kept for inspection. If it still carries a task id,
+    /// the id is also cleared from the processing set and from any resources
+    /// it lists, so a half-claimed entry holds nothing. `source_key` is the
+    /// sorted set the entry should be removed from, if it is still there.
+    async fn quarantine_task(
+        &self,
+        conn: &mut RedisConnection<'_>,
+        source_key: Option<&str>,
+        task_json: &str,
+        error: &serde_json::Error,
+    ) -> TaskResult<()> {
+        warn!("Quarantining corrupt task entry in {}: {}", self.key(CORRUPT_KEY), error);
+
+        let mut pipe = redis::pipe();
+        pipe.rpush(self.key(CORRUPT_KEY), task_json).ignore();
+        if let Some(source_key) = source_key {
+            pipe.zrem(source_key, task_json).ignore();
+        }
+        let raw: Option<serde_json::Value> = serde_json::from_str(task_json).ok();
+        if let Some(task_id) = raw.as_ref().and_then(|raw| raw.get("id")).and_then(|id| id.as_str()) {
+            pipe.zrem(self.key(PROCESSING_KEY), task_id).ignore();
+            let resources = raw
+                .as_ref()
+                .and_then(|raw| raw.get("required_resources"))
+                .and_then(|resources| resources.as_array());
+            for resource in resources.into_iter().flatten().filter_map(|resource| resource.as_str()) {
+                pipe.zrem(format!("{}:{}", self.key(RESOURCES_KEY), resource), task_id).ignore();
+            }
+        }
+        pipe.query_async::<_, ()>(&mut **conn)
+            .await
+            .map_err(|e| TaskError::queue_operation("quarantine_task", e.to_string()))
+    }
+
+    /// Reserve `count` consecutive submission sequence numbers, returning the
+    /// first
+    async fn reserve_sequence(&self, conn: &mut RedisConnection<'_>, count: usize) -> TaskResult<u64> {
+        let last: u64 = redis::cmd("INCRBY")
+            .arg(self.key(SEQUENCE_KEY))
+            .arg(count)
+            .query_async(&mut **conn)
+            .await
+            .map_err(|e| TaskError::queue_operation("reserve_sequence", e.to_string()))?;
+        Ok(last + 1 - count as u64)
+    }
+
+    /// Delayed set holding a queue's scheduled and retrying tasks
+    fn scheduled_key(&self, queue_name: &str) -> String {
+        format!("{}:{}", self.key(SCHEDULED_KEY), queue_name)
+    }
+
+    /// Prefix of the status index sets of a queue
+    fn status_key_prefix(&self, queue_name: &str) -> String {
+        format!("{}:{}:", self.key(STATUS_KEY), queue_name)
+    }
+
+    /// Arguments of the `index_status` Lua helper for the task's current status
+    ///
+    /// Finished tasks stay indexed for `result_ttl` (`failed_ttl` if they
+    /// failed); older entries of the same status are trimmed on each write.
+    fn status_index_args(&self, task_def: &TaskDefinition) -> TaskResult<Vec<String>> {
+        let now = chrono::Utc::now().timestamp();
+        let ttl = match task_def.status {
+            TaskStatus::Success | TaskStatus::Cancelled => self.config.result_ttl,
+            TaskStatus::Failed => self.config.failed_ttl,
+            _ => 0,
+        };
+        let cutoff = if ttl > 0 {
+            (now - ttl as i64).to_string()
+        } else {
+            String::new()
+        };
+        let statuses: Vec<&str> = TaskStatus::ALL.iter().map(TaskStatus::as_str).collect();
+        Ok(vec![
+            self.status_key_prefix(&task_def.queue),
+            task_def.id.to_string(),
+            task_def.status.as_str().to_string(),
+            now.to_string(),
+            cutoff,
+            serde_json::to_string(&statuses)?,
+        ])
+    }
+
+    /// Command recording the task's current status in its queue's status
+    /// index, to send in the same transaction as the status change itself
+    fn index_status_cmd(&self, task_def: &TaskDefinition) -> TaskResult<redis::Cmd> {
+        let mut cmd = redis::cmd("EVAL");
+        cmd.arg(SET_TASK_STATUS_SCRIPT).arg(0).arg(self.status_index_args(task_def)?);
+        Ok(cmd)
+    }
+
+    /// Emit a `Queued` event for a submitted task
+    async fn emit_queued(&self, task_def: &TaskDefinition) {
+        self.emit_event(TaskEvent::Queued {
+            task_id: task_def.id,
+            queue: task_def.queue.clone(),
+        })
+        .await;
+    }
+
+    /// Claim the task's idempotency key, if it has one
+    ///
+    /// Returns the id of another task already holding the key. A task
+    /// re-submitted for retry or after a reclaim still owns its key.
+    async fn claim_idempotency_key(
+        &self,
+        conn: &mut RedisConnection<'_>,
+        task_def: &TaskDefinition,
+    ) -> TaskResult<Option<TaskId>> {
+        let key = match &task_def.idempotency_key {
+            Some(key) => key,
+            None => return Ok(None),
+        };
+
+        let holder: Option<String> = Script::new(CLAIM_IDEMPOTENCY_KEY_SCRIPT)
+            .key(format!("{}:{}", self.key(IDEMPOTENCY_KEY), key))
+            .arg(task_def.id.to_string())
+            .arg(self.config.idempotency_ttl)
+            .invoke_async(&mut **conn)
+            .await
+            .map_err(|e| TaskError::queue_operation("claim_idempotency_key", e.to_string()))?;
+
+        match holder {
+            Some(holder) => {
+                let holder: TaskId = holder
+                    .parse()
+                    .map_err(|e: uuid::Error| TaskError::queue_operation("claim_idempotency_key", e.to_string()))?;
+                Ok((holder != task_def.id).then_some(holder))
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// List a queue's tasks in the given status, most recent transition first
+    ///
+    /// Backed by per-status index sets kept up to date on every transition.
+    /// Finished tasks are listed for `result_ttl` (`failed_ttl` if they
+    /// failed); entries whose task record has expired are dropped earlier.
+    pub async fn list_tasks_by_status(
+        &self,
+        queue_name: &str,
+        status: TaskStatus,
+        limit: usize,
+    ) -> TaskResult<Vec<TaskDefinition>> {
+        if limit == 0 {
+            return Ok(Vec::new());
+        }
+
+        let mut conn = self.get_connection().await?;
+        let index_key = format!("{}{}", self.status_key_prefix(queue_name), status.as_str());
+
+        let task_ids: Vec<String> = redis::cmd("ZREVRANGE")
+            .arg(&index_key)
+            .arg(0)
+            .arg(limit - 1)
+            .query_async(&mut *conn)
+            .await
+            .map_err(|e| TaskError::queue_operation("list_tasks_by_status", e.to_string()))?;
+
+        if task_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut pipe = redis::pipe();
+        for task_id in &task_ids {
+            pipe.hget(format!("{}:task:{}", self.key(QUEUE_KEY), task_id), "data");
+        }
+        let task_data: Vec<Option<String>> = pipe
+            .query_async(&mut *conn)
+            .await
+            .map_err(|e| TaskError::queue_operation("list_tasks_by_status", e.to_string()))?;
+
+        let mut tasks = Vec::with_capacity(task_ids.len());
+        let mut stale = Vec::new();
+        for (task_id, json) in task_ids.into_iter().zip(task_data) {
+            match json {
+                Some(json) => tasks.push(Self::read_task(&json)?),
+                None => stale.push(task_id),
+            }
+        }
+
+        if !stale.is_empty() {
+            redis::cmd("ZREM")
+                .arg(&index_key)
+                .arg(&stale)
+                .query_async::<_, ()>(&mut *conn)
+                .await
+                .map_err(|e| TaskError::queue_operation("list_tasks_by_status", e.to_string()))?;
+        }
+
+        Ok(tasks)
+    }
+
+    /// Get many tasks by ID in one round trip
+    ///
+    /// Every requested id is in the returned map; unknown tasks map to `None`.
+    pub async fn get_tasks(&self, task_ids: &[TaskId]) -> TaskResult<HashMap<TaskId, Option<TaskDefinition>>> {
+        if task_ids.is_empty() {
+            return Ok(HashMap::new());
+        }
+        if self.backend().is_some() {
+            let mut tasks = HashMap::with_capacity(task_ids.len());
+            for task_id in task_ids {
+                tasks.insert(*task_id, self.get_task(*task_id).await?);
+            }
+            return Ok(tasks);
+        }
+
+        let mut conn = self.get_connection().await?;
+        let mut pipe = redis::pipe();
+        for task_id in task_ids {
+            pipe.hget(format!("{}:task:{}", self.key(QUEUE_KEY), task_id), "data");
+        }
+
+        let task_data: Vec<Option<String>> = pipe
+            .query_async(&mut *conn)
+            .await
+            .map_err(|e| TaskError::queue_operation("get_tasks", e.to_string()))?;
+
+        task_ids
+            .iter()
+            .zip(task_data)
+            .map(|(task_id, json)| {
+                let task_def = json.map(|json| Self::read_task(&json)).transpose()?;
+                Ok((*task_id, task_def))
+            })
+            .collect()
+    }
+
+    /// Release the task's idempotency key so the work can be submitted again
+    async fn release_idempotency_key(
+        &self,
+        conn: &mut RedisConnection<'_>,
+        task_def: &TaskDefinition,
+    ) -> TaskResult<()> {
+        if let Some(key) = &task_def.idempotency_key {
+            Script::new(RELEASE_IDEMPOTENCY_KEY_SCRIPT)
+                .key(format!("{}:{}", self.key(IDEMPOTENCY_KEY), key))
+                .arg(task_def.id.to_string())
+                .invoke_async::<_, ()>(&mut **conn)
+                .await
+                .map_err(|e| TaskError::queue_operation("release_idempotency_key", e.to_string()))?;
+        }
+        Ok(())
+    }
+
+    /// Submit a task to the queue
+    ///
+    /// A duplicate of a pending or running task (same idempotency key)
+    /// returns the existing task's id; use `submit_task_with_outcome` to tell
+    /// the two cases apart.
+    pub async fn submit_task(&self, task_def: TaskDefinition) -> TaskResult<TaskId> {
+        Ok(self.submit_task_with_outcome(task_def).await?.task_id())
+    }
+
+    /// Submit a task to the queue, reporting whether it was deduplicated
+    pub async fn submit_task_with_outcome(&self, task_def: TaskDefinition) -> TaskResult<SubmitOutcome> {
+        self.enqueue_task(task_def, true).await
+    }
+
+    /// Put a task that is already in the system back on its queue, even past
+    /// `max_queue_depth`
+    async fn resubmit_task(&self, task_def: TaskDefinition) -> TaskResult<TaskId> {
+        Ok(self.enqueue_task(task_def, false).await?.task_id())
+    }
+
+    /// Enqueue a task, checking `max_queue_depth` if `limit_depth` is set
+    async fn enqueue_task(&self, mut task_def: TaskDefinition, limit_depth: bool) -> TaskResult<SubmitOutcome> {
+        // Use default queue if not specified
+        if task_def.queue.is_empty() {
+            task_def.queue = self.config.default_queue.clone();
+        }
+        self.apply_queue_defaults(&mut task_def);
+        self.check_payload_size(&task_def)?;
+        if limit_depth {
+            self.check_queue_depth(&task_def.queue, 1).await?;
+        }
+        if let Some(backend) = self.backend() {
+            Self::check_backend_task(&task_def)?;
+            backend.enqueue(&task_def, self.queue_score(&task_def.priority, 0), false).await?;
+            self.emit_queued(&task_def).await;
+            return Ok(SubmitOutcome::Enqueued(task_def.id));
+        }
+
+        let mut conn = self.get_connection().await?;
+
+        if let Some(existing) = self.claim_idempotency_key(&mut conn, &task_def).await? {
+            debug!("Task {} deduplicated against pending task {}", task_def.id, existing);
+            return Ok(SubmitOutcome::Deduplicated(existing));
+        }
+
+        let task_json = serde_json::to_string(&task_def)?;
+        let queue_key = format!("{}:{}", self.key(QUEUE_KEY), task_def.queue);
+        let task_key = format!("{}:task:{}", self.key(QUEUE_KEY), task_def.id);
+        
+        // Add task to priority queue (using sorted set with priority as score)
+        let sequence = self.reserve_sequence(&mut conn, 1).await?;
+        let priority_score = self.queue_score(&task_def.priority, sequence);
+        
+        redis::pipe()
+            .atomic()
+            .zadd(&queue_key, &task_json, priority_score)
+            .ignore()
+            .hset(&task_key, "data", &task_json)
+            .ignore()
+            .add_command(self.index_status_cmd(&task_def)?)
+            .ignore()
+            .query_async::<_, ()>(&mut *conn)
+            .await
+            .map_err(|e| TaskError::queue_operation("submit", e.to_string()))?;
+
+        debug!("Submitted task {} to queue {}", task_def.id, task_def.queue);
+        drop(conn);
+        self.emit_queued(&task_def).await;
+        Ok(SubmitOutcome::Enqueued(task_def.id))
+    }
+
+    /// Submit a task whose id must not be in use yet
+    ///
+    /// For tasks with application-supplied ids (`TaskDefinition::with_id`).
+    /// Fails with `TaskError::TaskExists` if a task with the id is pending,
+    /// processing or still has a stored result or failure, in the configured
+    /// result backend too. Idempotency keys are honored as in
+    /// `submit_task_with_outcome`.
+    pub async fn submit_new_task(&self, mut task_def: TaskDefinition) -> TaskResult<SubmitOutcome> {
+        if task_def.queue.is_empty() {
+            task_def.queue = self.config.default_queue.clone();
+        }
+        self.apply_queue_defaults(&mut task_def);
+        self.check_payload_size(&task_def)?;
+        self.check_queue_depth(&task_def.queue, 1).await?;
+        // A custom result backend keeps outcomes where the checks below can't see them
+        if self.results.get_result(task_def.id).await?.is_some() {
+            return Err(TaskError::TaskExists {
+                task_id: task_def.id.to_string(),
+            });
+        }
+        if let Some(backend) = self.backend() {
+            Self::check_backend_task(&task_def)?;
+            if !backend.enqueue(&task_def, self.queue_score(&task_def.priority, 0), true).await? {
+                return Err(TaskError::TaskExists {
+                    task_id: task_def.id.to_string(),
+                });
+            }
+            self.emit_queued(&task_def).await;
+            return Ok(SubmitOutcome::Enqueued(task_def.id));
+        }
+
+        let mut conn = self.get_connection().await?;
+
+        if let Some(existing) = self.claim_idempotency_key(&mut conn, &task_def).await? {
+            debug!("Task {} deduplicated against pending task {}", task_def.id, existing);
+            return Ok(SubmitOutcome::Deduplicated(existing));
+        }
+
+        let task_json = serde_json::to_string(&task_def)?;
+        let sequence = self.reserve_sequence(&mut conn, 1).await?;
+        let created: i32 = Script::new(SUBMIT_NEW_TASK_SCRIPT)
+            .key(format!("{}:task:{}", self.key(QUEUE_KEY), task_def.id))
+            .key(format!("{}:{}", self.key(QUEUE_KEY), task_def.queue))
+            .key(self.key(PROCESSING_KEY))
+            .key(format!("{}:result:{}", self.key(RESULTS_KEY), task_def.id))
+            .key(format!("{}:failed:{}", self.key(FAILED_KEY), task_def.id))
+            .arg(&task_json)
+            .arg(self.queue_score(&task_def.priority, sequence))
+            .arg(task_def.id.to_string())
+            .arg(self.status_index_args(&task_def)?)
+            .invoke_async(&mut *conn)
+            .await
+            .map_err(|e| TaskError::queue_operation("submit", e.to_string()))?;
+
+        if created == 0 {
+            self.release_idempotency_key(&mut conn, &task_def).await?;
+            return Err(TaskError::TaskExists {
+                task_id: task_def.id.to_string(),
+            });
+        }
+
+        debug!("Submitted task {} to queue {}", task_def.id, task_def.queue);
+        drop(conn);
+        self.emit_queued(&task_def).await;
+        Ok(SubmitOutcome::Enqueued(task_def.id))
+    }
+
+    /// Submit many tasks in a single round trip
+    ///
+    /// Returns the task ids in submission order. Tasks with an idempotency
+    /// key still go through `submit_task` one by one so they are
+    /// deduplicated; for those the returned id may be an existing task's.
+    pub async fn submit_tasks(&self, task_defs: Vec<TaskDefinition>) -> TaskResult<Vec<TaskId>> {
+        // Reject the whole batch before any of it is written
+        for task_def in &task_defs {
+            self.check_payload_size(task_def)?;
+        }
+        self.check_batch_depth(&task_defs).await?;
+        self.submit_checked_tasks(task_defs).await
+    }
+
+    /// Submit a batch that already passed the payload and depth checks
+    async fn submit_checked_tasks(&self, task_defs: Vec<TaskDefinition>) -> TaskResult<Vec<TaskId>> {
+
+        let mut task_ids = Vec::with_capacity(task_defs.len());
+        let mut pipelined = Vec::new();
+
+        for mut task_def in task_defs {
+            if task_def.idempotency_key.is_some() || self.backend().is_some() {
+                task_ids.push(self.enqueue_task(task_def, false).await?.task_id());
+                continue;
+            }
+
+            if task_def.queue.is_empty() {
+                task_def.queue = self.config.default_queue.clone();
+            }
+            self.apply_queue_defaults(&mut task_def);
+
+            task_ids.push(task_def.id);
+            pipelined.push(task_def);
+        }
+
+        if !pipelined.is_empty() {
+            let mut conn = self.get_connection().await?;
+            let first_sequence = self.reserve_sequence(&mut conn, pipelined.len()).await?;
+            let mut pipe = redis::pipe();
+
+            for (sequence, task_def) in (first_sequence..).zip(&pipelined) {
+                let task_json = serde_json::to_string(task_def)?;
+                pipe.zadd(
+                    format!("{}:{}", self.key(QUEUE_KEY), task_def.queue),
+                    &task_json,
+                    self.queue_score(&task_def.priority, sequence),
+                )
+                .ignore()
+                .hset(format!("{}:task:{}", self.key(QUEUE_KEY), task_def.id), "data", &task_json)
+                .ignore()
+                // New tasks are in no other status set yet
+                .zadd(
+                    format!("{}{}", self.status_key_prefix(&task_def.queue), task_def.status.as_str()),
+                    task_def.id.to_string(),
+                    chrono::Utc::now().timestamp(),
+                )
+                .ignore();
+            }
+
+            pipe.query_async::<_, ()>(&mut *conn)
+                .await
+                .map_err(|e| TaskError::queue_operation("submit_batch", e.to_string()))?;
+        }
+
+        debug!("Submitted {} tasks ({} pipelined)", task_ids.len(), pipelined.len());
+        for task_def in &pipelined {
+            self.emit_queued(task_def).await;
+        }
+        Ok(task_ids)
+    }
+
+    /// Submit a scheduled task
+    ///
+    /// Deduplicated like `submit_task`.
+    pub async fn submit_scheduled_task(&self, task_def: TaskDefinition) -> TaskResult<TaskId> {
+        Ok(self.submit_scheduled_task_with_outcome(task_def).await?.task_id())
+    }
+
+    /// Submit a scheduled task, reporting whether it was deduplicated
+    pub async fn submit_scheduled_task_with_outcome(
+        &self,
+        mut task_def: TaskDefinition,
+    ) -> TaskResult<SubmitOutcome> {
+        if task_def.queue.is_empty() {
+            task_def.queue = self.config.default_queue.clone();
+        }
+        self.apply_queue_defaults(&mut task_def);
+        self.check_payload_size(&task_def)?;
+        if let Some(backend) = self.backend() {
+            Self::check_backend_task(&task_def)?;
+            let scheduled_at = task_def
+                .scheduled_at
+                .ok_or_else(|| TaskError::queue_operation("submit_scheduled", "missing scheduled_at"))?;
+            backend.schedule(&task_def, scheduled_at).await?;
+            self.emit_queued(&task_def).await;
+            return Ok(SubmitOutcome::Enqueued(task_def.id));
+        }
+
+        let mut conn = self.get_connection().await?;
+
+        if let Some(existing) = self.claim_idempotency_key(&mut conn, &task_def).await? {
+            debug!("Scheduled task {} deduplicated against pending task {}", task_def.id, existing);
+            return Ok(SubmitOutcome::Deduplicated(existing));
+        }
+
+        let task_json = serde_json::to_string(&task_def)?;
+        let task_key = format!("{}:task:{}", self.key(QUEUE_KEY), task_def.id);
+        let scheduled_at = task_def
+            .scheduled_at
+            .ok_or_else(|| TaskError::queue_operation("submit_scheduled", "missing scheduled_at"))?;
+        // Scores are whole seconds; round up so the task never becomes due
+        // before its scheduled time (a retry must wait out its full backoff)
+        let scheduled_at_timestamp = if scheduled_at.timestamp_subsec_nanos() > 0 {
+            scheduled_at.timestamp().saturating_add(1)
+        } else {
+            scheduled_at.timestamp()
+        };
+
+        // Add to the queue's scheduled tasks sorted set
+        redis::pipe()
+            .atomic()
+            .zadd(self.scheduled_key(&task_def.queue), &task_json, scheduled_at_timestamp)
+            .ignore()
+            .sadd(self.key(SCHEDULED_QUEUES_KEY), &task_def.queue)
+            .ignore()
+            .hset(&task_key, "data", &task_json)
+            .ignore()
+            .add_command(self.index_status_cmd(&task_def)?)
+            .ignore()
+            .query_async::<_, ()>(&mut *conn)
+            .await
+            .map_err(|e| TaskError::queue_operation("submit_scheduled", e.to_string()))?;
+
+        debug!("Submitted scheduled task {} for {:?}", task_def.id, task_def.scheduled_at);
+        drop(conn);
+        self.emit_queued(&task_def).await;
+        Ok(SubmitOutcome::Enqueued(task_def.id))
+    }
+
+    /// Submit the task for one fire of a scheduled job and persist the job state
+    ///
+    /// Both writes happen atomically. If a task with the same id already
+    /// exists (the fire was submitted before a crash), nothing is enqueued and
+    /// `false` is returned, so deterministic task ids give exactly-once fires.
+    pub async fn fire_scheduled_job(
+        &self,
+        mut task_def: TaskDefinition,
+        job_id: &str,
+        job_json: &str,
+    ) -> TaskResult<bool> {
+        if task_def.queue.is_empty() {
+            task_def.queue = self.config.default_queue.clone();
+        }
+        self.apply_queue_defaults(&mut task_def);
+        self.check_payload_size(&task_def)?;
+        if let Some(backend) = self.backend() {
+            // Not atomic: a crash in between fires the job again on restart
+            Self::check_backend_task(&task_def)?;
+            let created = backend.enqueue(&task_def, self.queue_score(&task_def.priority, 0), true).await?;
+            backend.store_job(job_id, job_json).await?;
+            if created {
+                self.emit_queued(&task_def).await;
+            }
+            return Ok(created);
+        }
+
+        let mut conn = self.get_connection().await?;
+
+        let task_json = serde_json::to_string(&task_def)?;
+        let sequence = self.reserve_sequence(&mut conn, 1).await?;
+        let created: i32 = Script::new(FIRE_SCHEDULED_JOB_SCRIPT)
+            .key(format!("{}:task:{}", self.key(QUEUE_KEY), task_def.id))
+            .key(format!("{}:{}", self.key(QUEUE_KEY), task_def.queue))
+            .key(self.key(SCHEDULED_JOBS_KEY))
+            .arg(&task_json)
+            .arg(self.queue_score(&task_def.priority, sequence))
+            .arg(job_id)
+            .arg(job_json)
+            .arg(self.status_index_args(&task_def)?)
+            .invoke_async(&mut *conn)
+            .await
+            .map_err(|e| TaskError::queue_operation("fire_scheduled_job", e.to_string()))?;
+
+        debug!("Fired scheduled job {} as task {} (new: {})", job_id, task_def.id, created == 1);
+        if created == 1 {
+            drop(conn);
+            self.emit_queued(&task_def).await;
+        }
+        Ok(created == 1)
+    }
+
+    /// Persist a scheduled job's serialized state
+    pub async fn store_scheduled_job(&self, job_id: &str, job_json: &str) -> TaskResult<()> {
+        if let Some(backend) = self.backend() {
+            return backend.store_job(job_id, job_json).await;
+        }
+        let mut conn = self.get_connection().await?;
+
+        redis::cmd("HSET")
+            .arg(self.key(SCHEDULED_JOBS_KEY))
+            .arg(job_id)
+            .arg(job_json)
+            .query_async::<_, ()>(&mut *conn)
+            .await
+            .map_err(|e| TaskError::queue_operation("store_scheduled_job", e.to_string()))?;
+
+        Ok(())
+    }
+
+    /// Delete a persisted scheduled job
+    pub async fn remove_scheduled_job(&self, job_id: &str) -> TaskResult<()> {
+        if let Some(backend) = self.backend() {
+            return backend.remove_job(job_id).await;
+        }
+        let mut conn = self.get_connection().await?;
+
+        redis::cmd("HDEL")
+            .arg(self.key(SCHEDULED_JOBS_KEY))
+            .arg(job_id)
+            .query_async::<_, ()>(&mut *conn)
+            .await
+            .map_err(|e| TaskError::queue_operation("remove_scheduled_job", e.to_string()))?;
+
+        Ok(())
+    }
+
+    /// Load every persisted scheduled job as serialized JSON
+    pub async fn load_scheduled_jobs(&self) -> TaskResult<Vec<String>> {
+        if let Some(backend) = self.backend() {
+            return backend.load_jobs().await;
+        }
+        let mut conn = self.get_connection().await?;
+
+        redis::cmd("HVALS")
+            .arg(self.key(SCHEDULED_JOBS_KEY))
+            .query_async(&mut *conn)
+            .await
+            .map_err(|e| TaskError::queue_operation("load_scheduled_jobs", e.to_string()))
+    }
+
+    /// Get the next task from a queue
+    ///
+    /// Tasks whose `expires_at` has passed are cancelled and counted in the
+    /// queue's `expired_tasks` instead of being returned.
+    pub async fn get_next_task(&self, queue_name: &str) -> TaskResult<Option<TaskDefinition>> {
+        if let Some(backend) = self.backend() {
+            loop {
+                match backend.dequeue(queue_name).await? {
+                    Some(task_def) if task_def.is_expired() => self.expire_task(task_def).await?,
+                    task_def => return Ok(task_def),
+                }
+            }
+        }
+        let capacities = serde_json::to_string(&self.config.resource_capacities)?;
+
+        loop {
+            let mut conn = self.get_connection().await?;
+
+            // Pop the highest priority task (and its resources) in one atomic step,
+            // so two workers can never claim the same task
+            let task_json: Option<String> = Script::new(DEQUEUE_TASK_SCRIPT)
+                .key(format!("{}:{}", self.key(QUEUE_KEY), queue_name))
+                .key(self.key(PROCESSING_KEY))
+                .key(self.key(CORRUPT_KEY))
+                .arg(chrono::Utc::now().timestamp())
+                .arg(format!("{}:", self.key(RESOURCES_KEY)))
+                .arg(&capacities)
+                .arg(DEQUEUE_SCAN_WINDOW)
+                .invoke_async(&mut *conn)
+                .await
+                .map_err(|e| TaskError::queue_operation("get_next", e.to_string()))?;
+
+            // Either the queue is empty or every task near its head is waiting on busy resources
+            let Some(task_json) = task_json else {
+                return Ok(None);
+            };
+
+            match serde_json::from_str::<TaskDefinition>(&task_json) {
+                Ok(task_def) if task_def.is_expired() => {
+                    drop(conn);
+                    self.expire_task(task_def).await?;
+                }
+                Ok(task_def) => {
+                    debug!("Retrieved task {} from queue {}", task_def.id, queue_name);
+                    return Ok(Some(task_def));
+                }
+                // Claimed but unreadable; set it aside and try the next task
+                Err(e) => self.quarantine_task(&mut conn, None, &task_json, &e).await?,
+            }
+        }
+    }
+
+    /// Wait until a task can be claimed from any of the given queues
+    ///
+    /// Queues are tried in order and the highest-priority runnable task of
+    /// the first queue that has one is claimed, in the same atomic step as
+    /// `get_next_task`. Between rounds it waits at most `BLOCKING_WAIT_SLICE`,
+    /// so a newly submitted task, or one whose resources were released, is
+    /// picked up shortly after. Returns `None` if nothing could be claimed
+    /// within `timeout`.
+    pub async fn get_next_task_blocking(
+        &self,
+        queue_names: &[String],
+        timeout: Duration,
+    ) -> TaskResult<Option<TaskDefinition>> {
+        if queue_names.is_empty() {
+            return Ok(None);
+        }
+        let deadline = Instant::now() + timeout;
+        loop {
+            for queue_name in queue_names {
+                if let Some(task_def) = self.get_next_task(queue_name).await? {
+                    return Ok(Some(task_def));
+                }
+            }
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if remaining.is_zero() {
+                return Ok(None);
+            }
+            let slice = remaining.min(BLOCKING_WAIT_SLICE);
+            match self.backend() {
+                // Bounded, as a task enqueued just before waiting wakes no one
+                Some(backend) => backend.wait_for_task(slice).await,
+                // A blocking pop can't take the task's resources in the same
+                // step, and a crash between the two would lose the task
+                None => tokio::time::sleep(slice).await,
+            }
+        }
+    }
+
+    /// Cancel a claimed task whose `expires_at` has passed instead of running it
+    ///
+    /// Releases what the claim took, stores the task as `Cancelled` and
+    /// counts it in the queue's expired total.
+    async fn expire_task(&self, mut task_def: TaskDefinition) -> TaskResult<()> {
+        task_def.mark_expired();
+        if let Some(backend) = self.backend() {
+            backend.finish_task(&task_def).await?;
+            info!("Task {} expired before it could run", task_def.id);
+            return Ok(());
+        }
+        self.release_resources(&task_def).await?;
+
+        let mut conn = self.get_connection().await?;
+        let task_id = task_def.id.to_string();
+        redis::pipe()
+            .atomic()
+            .zrem(self.key(PROCESSING_KEY), &task_id)
+            .ignore()
+            .hset(format!("{}:task:{}", self.key(QUEUE_KEY), task_id), "data", serde_json::to_string(&task_def)?)
+            .ignore()
+            .incr(format!("{}:{}:expired", self.key(STATS_KEY), task_def.queue), 1)
+            .ignore()
+            .publish(format!("{}:{}", self.key(EVENTS_KEY), task_id), &task_id)
+            .ignore()
+            .add_command(self.index_status_cmd(&task_def)?)
+            .ignore()
+            .query_async::<_, ()>(&mut *conn)
+            .await
+            .map_err(|e| TaskError::queue_operation("expire_task", e.to_string()))?;
+
+        self.release_idempotency_key(&mut conn, &task_def).await?;
+        drop(conn);
+
+        self.finish_group_member(&task_def).await?;
+
+        info!("Task {} expired before it could run", task_id);
+        Ok(())
+    }
+
+    /// List pending tasks in priority order without dequeuing them
+    ///
+    /// Returns up to `limit` tasks starting at `offset`, highest priority
+    /// first. A missing or empty queue yields an empty list.
+    pub async fn peek_tasks(&self, queue_name: &str, offset: usize, limit: usize) -> TaskResult<Vec<QueuedTask>> {
+        if limit == 0 {
+            return Ok(Vec::new());
+        }
+
+        let mut conn = self.get_connection().await?;
+        let queue_key = format!("{}:{}", self.key(QUEUE_KEY), queue_name);
+
+        let entries: Vec<(String, f64)> = redis::cmd("ZREVRANGE")
+            .arg(&queue_key)
+            .arg(offset)
+            .arg(offset + limit - 1)
+            .arg("WITHSCORES")
+            .query_async(&mut *conn)
+            .await
+            .map_err(|e| TaskError::queue_operation("peek_tasks", e.to_string()))?;
+
+        Ok(entries
+            .into_iter()
+            .filter_map(|(task_json, score)| match serde_json::from_str(&task_json) {
+                Ok(task) => Some(QueuedTask { task, score }),
+                Err(e) => {
+                    warn!("Skipping corrupt task entry in {}: {}", queue_key, e);
+                    None
+                }
+            })
+            .collect())
+    }
+
+    /// Try to acquire a slot on every resource the task requires
+    ///
+    /// Either all resources are acquired or none are. Tasks without
+    /// resource requirements always succeed.
+    pub async fn acquire_resources(&self, task_def: &TaskDefinition) -> TaskResult<bool> {
+        if task_def.required_resources.is_empty() {
+            return Ok(true);
+        }
+
+        let mut conn = self.get_connection().await?;
+        let script = Script::new(ACQUIRE_RESOURCES_SCRIPT);
+        let mut invocation = script.prepare_invoke();
+        invocation
+            .arg(task_def.id.to_string())
+            .arg(chrono::Utc::now().timestamp());
+
+        for resource in &task_def.required_resources {
+            let capacity = self
+                .config
+                .resource_capacities
+                .get(resource)
+                .copied()
+                .unwrap_or(1);
+            invocation
+                .key(format!("{}:{}", self.key(RESOURCES_KEY), resource))
+                .arg(capacity);
+        }
+
+        let acquired: i32 = invocation
+            .invoke_async(&mut *conn)
+            .await
+            .map_err(|e| TaskError::queue_operation("acquire_resources", e.to_string()))?;
+
+        Ok(acquired == 1)
+    }
+
+    /// Release the resource slots held by a task
+    pub async fn release_resources(&self, task_def: &TaskDefinition) -> TaskResult<()> {
+        if task_def.required_resources.is_empty() {
+            return Ok(());
+        }
+
+        let mut conn = self.get_connection().await?;
+        let holder = task_def.id.to_string();
+        let mut pipe = redis::pipe();
+
+        for resource in &task_def.required_resources {
+            pipe.zrem(format!("{}:{}", self.key(RESOURCES_KEY), resource), &holder)
+                .ignore();
+        }
+
+        pipe.query_async::<_, ()>(&mut *conn)
+            .await
+            .map_err(|e| TaskError::queue_operation("release_resources", e.to_string()))?;
+
+        debug!("Released resources {:?} for task {}", task_def.required_resources, task_def.id);
+        Ok(())
+    }
+
+    /// Move up to `max_to_move` scheduled tasks that are ready to their queues
+    ///
+    /// Tasks that have been due the longest move first, whichever queue they
+    /// belong to, and enter their queue with their priority score. Due tasks
+    /// beyond the bound are left for the next call, so a large batch becoming
+    /// due at once is fed to the queues gradually.
+    pub async fn process_scheduled_tasks(&self, max_to_move: usize) -> TaskResult<u64> {
+        if max_to_move == 0 {
+            return Ok(0);
+        }
+        if let Some(backend) = self.backend() {
+            let due = backend.take_due_tasks(Utc::now(), max_to_move).await?;
+            for mut task_def in due.iter().cloned() {
+                task_def.status = TaskStatus::Pending;
+                backend.enqueue(&task_def, self.queue_score(&task_def.priority, 0), false).await?;
+            }
+            return Ok(due.len() as u64);
+        }
+
+        let mut conn = self.get_connection().await?;
+        let now = chrono::Utc::now().timestamp();
+
+        let queue_names: Vec<String> = redis::cmd("SMEMBERS")
+            .arg(self.key(SCHEDULED_QUEUES_KEY))
+            .query_async(&mut *conn)
+            .await
+            .map_err(|e| TaskError::queue_operation("get_scheduled", e.to_string()))?;
+
+        // Tasks scheduled before per-queue sets existed wait in the shared set
+        let scheduled_keys = queue_names
+            .iter()
+            .map(|queue_name| self.scheduled_key(queue_name))
+            .chain(std::iter::once(self.key(SCHEDULED_KEY)));
+
+        // Each set's earliest due tasks, merged by due time
+        let mut due: Vec<(String, String, f64)> = Vec::new();
+        for scheduled_key in scheduled_keys {
+            let tasks: Vec<(String, f64)> = redis::cmd("ZRANGEBYSCORE")
+                .arg(&scheduled_key)
+                .arg("-inf")
+                .arg(now)
+                .arg("WITHSCORES")
+                .arg("LIMIT")
+                .arg(0)
+                .arg(max_to_move)
+                .query_async(&mut *conn)
+                .await
+                .map_err(|e| TaskError::queue_operation("get_scheduled", e.to_string()))?;
+            due.extend(
+                tasks
+                    .into_iter()
+                    .map(|(task_json, due_at)| (scheduled_key.clone(), task_json, due_at)),
+            );
+        }
+        due.sort_by(|a, b| a.2.total_cmp(&b.2));
+        due.truncate(max_to_move);
+        if due.is_empty() {
+            return Ok(0);
+        }
+
+        let mut sequence = self.reserve_sequence(&mut conn, due.len()).await?;
+        let mut processed_count = 0;
+        
+        for (scheduled_key, task_json, _) in due {
+            let mut task_def: TaskDefinition = match serde_json::from_str(&task_json) {
+                Ok(task_def) => task_def,
+                Err(e) => {
+                    self.quarantine_task(&mut conn, Some(&scheduled_key), &task_json, &e).await?;
+                    continue;
+                }
+            };
+            task_def.status = TaskStatus::Pending;
+            
+            let updated_json = serde_json::to_string(&task_def)?;
+            let queue_key = format!("{}:{}", self.key(QUEUE_KEY), task_def.queue);
+            let priority_score = self.queue_score(&task_def.priority, sequence);
+            sequence += 1;
+
+            // Move from scheduled to queue
+            redis::pipe()
+                .atomic()
+                .zrem(&scheduled_key, &task_json)
+                .ignore()
+                .zadd(&queue_key, &updated_json, priority_score)
+                .ignore()
+                .hset(format!("{}:task:{}", self.key(QUEUE_KEY), task_def.id), "data", &updated_json)
+                .ignore()
+                .add_command(self.index_status_cmd(&task_def)?)
+                .ignore()
+                .query_async::<_, ()>(&mut *conn)
+                .await
+                .map_err(|e| TaskError::queue_operation("move_scheduled", e.to_string()))?;
+
+            processed_count += 1;
+            debug!("Moved scheduled task {} to queue {}", task_def.id, task_def.queue);
+        }
+
+        if processed_count > 0 {
+            info!("Processed {} scheduled tasks", processed_count);
+        }
+
+        Ok(processed_count)
+    }
+
+    /// Record that a worker started executing a task
+    pub async fn mark_task_started(&self, task_def: &TaskDefinition) -> TaskResult<()> {
+        if let Some(backend) = self.backend() {
+            return backend.save_task(task_def).await;
+        }
+        let mut conn = self.get_connection().await?;
+
+        redis::pipe()
+            .atomic()
+            .hset(format!("{}:task:{}", self.key(QUEUE_KEY), task_def.id), "data", serde_json::to_string(task_def)?)
+            .ignore()
+            .add_command(self.index_status_cmd(task_def)?)
+            .ignore()
+            .query_async::<_, ()>(&mut *conn)
+            .await
+            .map_err(|e| TaskError::queue_operation("mark_started", e.to_string()))?;
+        self.publish_task_event(&mut conn, task_def).await
+    }
+
+    /// Mark a task as completed
+    ///
+    /// For a task with `store_result` off, the record is kept without its
+    /// output and nothing is written to the result backend.
+    pub async fn mark_task_completed(&self, task_def: &TaskDefinition) -> TaskResult<()> {
+        // A task submitted with `no_result` keeps its output nowhere
+        let stored = if task_def.store_result {
+            self.compress_task_result(task_def)?
+        } else {
+            Cow::Owned(TaskDefinition {
+                result: None,
+                ..task_def.clone()
+            })
+        };
+        if let Some(backend) = self.backend() {
+            backend.finish_task(&stored).await?;
+            if task_def.store_result {
+                self.results.store_result(&stored).await?;
+            }
+            debug!("Marked task {} as completed", task_def.id);
+            return Ok(());
+        }
+
+        let mut conn = self.get_connection().await?;
+        let task_json = serde_json::to_string(&stored)?;
+
+        redis::pipe()
+            .atomic()
+            .zrem(self.key(PROCESSING_KEY), task_def.id.to_string())
+            .ignore()
+            .hset(format!("{}:task:{}", self.key(QUEUE_KEY), task_def.id), "data", &task_json)
+            .ignore()
+            .incr(format!("{}:{}:completed", self.key(STATS_KEY), task_def.queue), 1)
+            .ignore()
+            .add_command(self.index_status_cmd(task_def)?)
+            .ignore()
+            .query_async::<_, ()>(&mut *conn)
+            .await
+            .map_err(|e| TaskError::queue_operation("mark_completed", e.to_string()))?;
+
+        // Store the result before announcing it, so waiters can read it
+        if task_def.store_result {
+            self.results.store_result(&stored).await?;
+        }
+        self.publish_task_event(&mut conn, task_def).await?;
+
+        self.release_idempotency_key(&mut conn, task_def).await?;
+        drop(conn);
+
+        self.finish_group_member(task_def).await?;
+
+        debug!("Marked task {} as completed", task_def.id);
+        Ok(())
+    }
+
+    /// Mark a task as failed
+    pub async fn mark_task_failed(&self, task_def: &TaskDefinition) -> TaskResult<()> {
+        if let Some(backend) = self.backend() {
+            backend.finish_task(task_def).await?;
+            self.results.store_failure(task_def).await?;
+            debug!("Marked task {} as failed", task_def.id);
+            return Ok(());
+        }
+        let mut conn = self.get_connection().await?;
+        let task_json = serde_json::to_string(task_def)?;
+
+        redis::pipe()
+            .atomic()
+            .zrem(self.key(PROCESSING_KEY), task_def.id.to_string())
+            .ignore()
+            .hset(format!("{}:task:{}", self.key(QUEUE_KEY), task_def.id), "data", &task_json)
+            .ignore()
+            .incr(format!("{}:{}:failed", self.key(STATS_KEY), task_def.queue), 1)
+            .ignore()
+            .add_command(self.index_status_cmd(task_def)?)
+            .ignore()
+            .query_async::<_, ()>(&mut *conn)
+            .await
+            .map_err(|e| TaskError::queue_operation("mark_failed", e.to_string()))?;
+
+        self.results.store_failure(task_def).await?;
+        self.publish_task_event(&mut conn, task_def).await?;
+
+        self.release_idempotency_key(&mut conn, task_def).await?;
+        drop(conn);
+
+        self.finish_group_member(task_def).await?;
+
+        debug!("Marked task {} as failed", task_def.id);
+        Ok(())
+    }
+
+    /// Submit a group's tasks, tracking them until all have finished
+    ///
+    /// The group's finalizer, if any, is enqueued by whichever call records
+    /// the last member's outcome (see `TaskGroup`).
+    pub async fn submit_group(&self, group: TaskGroup) -> TaskResult<GroupId> {
+        for task_def in &group.members {
+            self.check_payload_size(task_def)?;
+        }
+        self.check_batch_depth(&group.members).await?;
+        let group_key = format!("{}:{}", self.key(GROUP_KEY), group.id);
+        let member_ids: Vec<TaskId> = group.members.iter().map(|task_def| task_def.id).collect();
+        let finalizer = match &group.finalizer {
+            Some(finalizer) => serde_json::to_string(finalizer)?,
+            None => String::new(),
+        };
+
+        let mut conn = self.get_connection().await?;
+        redis::pipe()
+            .atomic()
+            .hset_multiple(
+                &group_key,
+                &[
+                    ("remaining", member_ids.len().to_string()),
+                    ("failed", "0".to_string()),
+                    ("policy", serde_json::to_string(&group.policy)?),
+                    ("finalizer", finalizer),
+                    ("members", serde_json::to_string(&member_ids)?),
+                ],
+            )
+            .ignore()
+            .expire(&group_key, self.config.group_ttl as i64)
+            .ignore()
+            .query_async::<_, ()>(&mut *conn)
+            .await
+            .map_err(|e| TaskError::queue_operation("submit_group", e.to_string()))?;
+        drop(conn);
+
+        self.submit_checked_tasks(group.members).await?;
+
+        debug!("Submitted group {} of {} tasks", group.id, member_ids.len());
+        Ok(group.id)
+    }
+
+    /// Record a finished group member, enqueueing the group's finalizer
+    /// after the last one
+    async fn finish_group_member(&self, task_def: &TaskDefinition) -> TaskResult<()> {
+        let Some(group_id) = task_def.group_id else {
+            return Ok(());
+        };
+
+        let mut conn = self.get_connection().await?;
+        let group_key = format!("{}:{}", self.key(GROUP_KEY), group_id);
+        let results_key = format!("{}:results", group_key);
+
+        let remaining: i64 = Script::new(GROUP_MEMBER_DONE_SCRIPT)
+            .key(&group_key)
+            .key(&results_key)
+            .arg(task_def.id.to_string())
+            .arg(serde_json::to_string(&GroupMemberResult::from_task(task_def))?)
+            .arg(u8::from(task_def.status != TaskStatus::Success))
+            .arg(self.config.group_ttl)
+            .invoke_async(&mut *conn)
+            .await
+            .map_err(|e| TaskError::queue_operation("finish_group_member", e.to_string()))?;
+        if remaining != 0 {
+            return Ok(());
+        }
+
+        // Only the last member gets here
+        let (finalizer, policy, failed, members): (Option<String>, Option<String>, Option<u64>, Option<String>) =
+            redis::cmd("HMGET")
+                .arg(&group_key)
+                .arg("finalizer")
+                .arg("policy")
+                .arg("failed")
+                .arg("members")
+                .query_async(&mut *conn)
+                .await
+                .map_err(|e| TaskError::queue_operation("finish_group", e.to_string()))?;
+        let results: HashMap<String, String> = redis::cmd("HGETALL")
+            .arg(&results_key)
+            .query_async(&mut *conn)
+            .await
+            .map_err(|e| TaskError::queue_operation("finish_group", e.to_string()))?;
+        drop(conn);
+
+        let failed = failed.unwrap_or(0);
+        let finalizer = Self::group_finalizer(group_id, finalizer, policy, failed, members, results)?;
+        // Enqueue before deleting the group, so a crash in between leaves the
+        // group to expire rather than losing the finalizer
+        if let Some(finalizer) = finalizer {
+            let finalizer_id = self.resubmit_task(finalizer).await?;
+            info!("Group {} finished, enqueued finalizer {}", group_id, finalizer_id);
+        }
+
+        let mut conn = self.get_connection().await?;
+        redis::cmd("DEL")
+            .arg(&group_key)
+            .arg(&results_key)
+            .query_async::<_, ()>(&mut *conn)
+            .await
+            .map_err(|e| TaskError::queue_operation("finish_group", e.to_string()))?;
+        Ok(())
+    }
+
+    /// The finalizer to enqueue for a finished group, with its members'
+    /// results, or `None` if it has none or its policy skips it
+    fn group_finalizer(
+        group_id: GroupId,
+        finalizer: Option<String>,
+        policy: Option<String>,
+        failed: u64,
+        members: Option<String>,
+        mut results: HashMap<String, String>,
+    ) -> TaskResult<Option<TaskDefinition>> {
+        let finalizer = match finalizer.filter(|finalizer| !finalizer.is_empty()) {
+            Some(finalizer) => finalizer,
+            None => {
+                info!("Group {} finished ({} failed)", group_id, failed);
+                return Ok(None);
+            }
+        };
+        let policy: FinalizerPolicy = match policy {
+            Some(policy) => serde_json::from_str(&policy)?,
+            None => FinalizerPolicy::default(),
+        };
+        if policy == FinalizerPolicy::AllSucceeded && failed > 0 {
+            warn!(
+                "Group {} finished with {} failed tasks, not running its finalizer",
+                group_id, failed
+            );
+            return Ok(None);
+        }
+
+        let member_ids: Vec<TaskId> = match members {
+            Some(members) => serde_json::from_str(&members)?,
+            None => Vec::new(),
+        };
+        let member_results = member_ids
+            .iter()
+            .filter_map(|task_id| results.remove(&task_id.to_string()))
+            .map(|json| serde_json::from_str(&json))
+            .collect::<Result<Vec<GroupMemberResult>, _>>()?;
+
+        group::finalizer_with_results(serde_json::from_str(&finalizer)?, member_results).map(Some)
+    }
+
+    /// Notify subscribers (see `subscribe_task_events`) that a task's status changed
+    async fn publish_task_event(&self, conn: &mut RedisConnection<'_>, task_def: &TaskDefinition) -> TaskResult<()> {
+        redis::cmd("PUBLISH")
+            .arg(format!("{}:{}", self.key(EVENTS_KEY), task_def.id))
+            .arg(task_def.id.to_string())
+            .query_async::<_, ()>(&mut **conn)
+            .await
+            .map_err(|e| TaskError::queue_operation("publish_task_event", e.to_string()))
+    }
+
+    /// Get a finished task's stored outcome from the result backend
+    pub async fn get_result(&self, task_id: TaskId) -> TaskResult<Option<TaskDefinition>> {
+        self.results
+            .get_result(task_id)
+            .await?
+            .map(Self::decompress_task_result)
+            .transpose()
+    }
+
+    /// Copy of the task with its result compressed, if results are compressed
+    fn compress_task_result<'a>(&self, task_def: &'a TaskDefinition) -> TaskResult<Cow<'a, TaskDefinition>> {
+        match &task_def.result {
+            Some(result) if self.config.compress_results => {
+                let compressed = compression::compress_result(result, self.config.compression_threshold)?;
+                let mut stored = task_def.clone();
+                // Short results are kept as they are
+                stored.result_compressed = compression::is_compressed(&compressed);
+                stored.result = Some(compressed);
+                Ok(Cow::Owned(stored))
+            }
+            _ => Ok(Cow::Borrowed(task_def)),
+        }
+    }
+
+    /// Restore a result compressed by `compress_task_result`
+    ///
+    /// Only results flagged as compressed are touched, so an output that
+    /// merely looks compressed is returned as it was produced.
+    fn decompress_task_result(mut task_def: TaskDefinition) -> TaskResult<TaskDefinition> {
+        if task_def.result_compressed {
+            if let Some(result) = task_def.result.as_deref() {
+                task_def.result = Some(compression::decompress_result(result)?);
+            }
+            task_def.result_compressed = false;
+        }
+        Ok(task_def)
+    }
+
+    /// Parse a stored task record
+    fn read_task(json: &str) -> TaskResult<TaskDefinition> {
+        Self::decompress_task_result(serde_json::from_str(json)?)
+    }
+
+    /// Submit the follow-up a finished task produced (see `Task::on_success`)
+    ///
+    /// The follow-up records `parent` as its parent, defaults to the parent's
+    /// queue and is one deeper in the chain. A follow-up past
+    /// `max_chain_depth` is marked failed instead of being enqueued, and the
+    /// error is returned.
+    pub async fn submit_follow_up(&self, parent: &TaskDefinition, mut next_task: TaskDefinition) -> TaskResult<TaskId> {
+        next_task.parent_id = Some(parent.id);
+        next_task.chain_depth = parent.chain_depth + 1;
+        if next_task.queue.is_empty() {
+            next_task.queue = parent.queue.clone();
+        }
+        if let Err(e) = self.check_chain_depth(&next_task) {
+            next_task.mark_failed(&e.to_string());
+            self.mark_task_failed(&next_task).await?;
+            return Err(e);
+        }
+        self.resubmit_task(next_task).await
+    }
+
+    /// Requeue a task for retry
+    ///
+    /// A task with `scheduled_at` in the future (as `TaskDefinition::mark_retry`
+    /// sets it to the backoff time) goes to the scheduled set and is not
+    /// dequeued again until `process_scheduled_tasks` moves it back to its
+    /// queue once that time has passed. While it waits it is neither pending
+    /// nor processing, so it holds no worker slot. Other tasks go straight
+    /// back to their queue.
+    ///
+    /// ```
+    /// use distributed_task_queue::{Task, TaskDefinition, TaskError, TaskQueue};
+    ///
+    /// #[derive(Debug, serde::Serialize, serde::Deserialize)]
+    /// struct Flaky;
+    ///
+    /// #[async_trait::async_trait]
+    /// impl Task for Flaky {
+    ///     type Output = ();
+    ///     type Error = TaskError;
+    ///     async fn execute(&self) -> Result<(), TaskError> { Err(TaskError::task_execution("flaked")) }
+    /// }
+    ///
+    /// # tokio_test::block_on(async {
+    /// let queue = TaskQueue::new_in_memory(Default::default())?;
+    /// queue.submit_task(TaskDefinition::new(&Flaky, "jobs".to_string())?).await?;
+    ///
+    /// let mut task_def = queue.get_next_task("jobs").await?.unwrap();
+    /// task_def.mark_retry("flaked")?; // backs off 5 seconds by default
+    /// queue.requeue_task(&task_def).await?;
+    ///
+    /// let stats = queue.get_stats("jobs").await?;
+    /// assert_eq!((stats.pending_tasks, stats.processing_tasks, stats.scheduled_tasks), (0, 0, 1));
+    /// assert!(queue.get_next_task("jobs").await?.is_none());
+    /// assert_eq!(queue.process_scheduled_tasks(100).await?, 0); // not due yet
+    /// # Ok::<(), TaskError>(())
+    /// # })?;
+    /// # Ok::<(), TaskError>(())
+    /// ```
+    pub async fn requeue_task(&self, task_def: &TaskDefinition) -> TaskResult<()> {
+        self.remove_from_processing(task_def).await?;
+
+        // A task that already waited out its backoff (e.g. one deferred at a
+        // concurrency cap) must not wait for another scheduler tick
+        if task_def.scheduled_at.is_some_and(|scheduled_at| scheduled_at > Utc::now()) {
+            self.submit_scheduled_task(task_def.clone()).await?;
+        } else {
+            self.resubmit_task(task_def.clone()).await?;
+        }
+        if self.backend().is_none() {
+            let mut conn = self.get_connection().await?;
+            self.publish_task_event(&mut conn, task_def).await?;
+        }
+        
+        debug!("Requeued task {} for retry", task_def.id);
+        Ok(())
+    }
+
+    /// Run a task that failed for good once more
+    ///
+    /// The task is reset to `Pending` with a fresh retry budget, its stored
+    /// failure is removed and it is submitted to its original queue. Returns
+    /// `false` if the task has not failed.
+    pub async fn retry_failed_task(&self, task_id: TaskId) -> TaskResult<bool> {
+        let mut task_def = self.get_task(task_id).await?.ok_or_else(|| TaskError::TaskNotFound {
+            task_id: task_id.to_string(),
+        })?;
+
+        if task_def.status != TaskStatus::Failed {
+            debug!("Task {} is {:?}, not retrying it", task_id, task_def.status);
+            return Ok(false);
+        }
+
+        self.results.remove_result(task_id).await?;
+        task_def.reset_for_rerun();
+        self.resubmit_task(task_def).await?;
+
+        info!("Re-submitted failed task {}", task_id);
+        Ok(true)
+    }
+
+    /// Cancel a task that has not started executing yet
+    ///
+    /// Removes the task from its pending or scheduled set and stores it with
+    /// status `Cancelled`. A task already dequeued by a worker is skipped by
+    /// that worker before it runs. Returns `false` if the task is running,
+    /// already finished, or was claimed by a worker while being cancelled.
+    pub async fn cancel_task(&self, task_id: TaskId) -> TaskResult<bool> {
+        if let Some(backend) = self.backend() {
+            let mut task_def = backend.get_task(task_id).await?.ok_or_else(|| TaskError::TaskNotFound {
+                task_id: task_id.to_string(),
+            })?;
+            if !task_def.is_cancellable() {
+                debug!("Task {} is {:?} and can no longer be cancelled", task_id, task_def.status);
+                return Ok(false);
+            }
+            if !backend.remove_waiting(&task_def).await? {
+                debug!("Task {} was claimed before it could be cancelled", task_id);
+                return Ok(false);
+            }
+            task_def.mark_cancelled();
+            backend.save_task(&task_def).await?;
+            info!("Cancelled task {}", task_id);
+            return Ok(true);
         }
-        
-        debug!("Requeued task {} for retry", task_def.id);
+        let mut conn = self.get_connection().await?;
+        let task_key = format!("{}:task:{}", self.key(QUEUE_KEY), task_id);
+
+        let task_json: Option<String> = redis::cmd("HGET")
+            .arg(&task_key)
+            .arg("data")
+            .query_async(&mut *conn)
+            .await
+            .map_err(|e| TaskError::queue_operation("cancel_task", e.to_string()))?;
+
+        let task_json = task_json.ok_or_else(|| TaskError::TaskNotFound {
+            task_id: task_id.to_string(),
+        })?;
+        let mut task_def: TaskDefinition = serde_json::from_str(&task_json)?;
+
+        if !task_def.is_cancellable() {
+            debug!("Task {} is {:?} and can no longer be cancelled", task_id, task_def.status);
+            return Ok(false);
+        }
+
+        task_def.mark_cancelled();
+        let cancelled_json = serde_json::to_string(&task_def)?;
+
+        let removed: u32 = Script::new(CANCEL_TASK_SCRIPT)
+            .key(&task_key)
+            .key(format!("{}:{}", self.key(QUEUE_KEY), task_def.queue))
+            .key(self.scheduled_key(&task_def.queue))
+            .key(self.key(SCHEDULED_KEY))
+            .arg(&task_json)
+            .arg(&cancelled_json)
+            .arg(format!("{}:{}", self.key(EVENTS_KEY), task_id))
+            .arg(task_id.to_string())
+            .arg(self.status_index_args(&task_def)?)
+            .invoke_async(&mut *conn)
+            .await
+            .map_err(|e| TaskError::queue_operation("cancel_task", e.to_string()))?;
+        if removed == 0 {
+            // Claimed by a worker (or moved) between reading and cancelling it
+            debug!("Task {} was claimed before it could be cancelled", task_id);
+            return Ok(false);
+        }
+
+        self.release_idempotency_key(&mut conn, &task_def).await?;
+        drop(conn);
+
+        self.finish_group_member(&task_def).await?;
+
+        info!("Cancelled task {}", task_id);
+        Ok(true)
+    }
+
+    /// Remove a dequeued task from the processing set without recording a result
+    pub async fn remove_from_processing(&self, task_def: &TaskDefinition) -> TaskResult<()> {
+        if let Some(backend) = self.backend() {
+            return backend.remove_from_processing(task_def.id).await;
+        }
+        let mut conn = self.get_connection().await?;
+
+        redis::cmd("ZREM")
+            .arg(self.key(PROCESSING_KEY))
+            .arg(task_def.id.to_string())
+            .query_async::<_, ()>(&mut *conn)
+            .await
+            .map_err(|e| TaskError::queue_operation("remove_from_processing", e.to_string()))?;
+
+        Ok(())
+    }
+
+    /// Record a running task's progress in its task hash
+    ///
+    /// `percent` is capped at 100.
+    pub async fn set_task_progress(&self, task_id: TaskId, percent: u8, message: Option<&str>) -> TaskResult<()> {
+        if let Some(backend) = self.backend() {
+            return backend.set_progress(task_id, percent.min(100), message).await;
+        }
+        let mut conn = self.get_connection().await?;
+        let progress = serde_json::to_string(&(percent.min(100), message))?;
+
+        redis::cmd("HSET")
+            .arg(format!("{}:task:{}", self.key(QUEUE_KEY), task_id))
+            .arg("progress")
+            .arg(progress)
+            .query_async::<_, ()>(&mut *conn)
+            .await
+            .map_err(|e| TaskError::queue_operation("set_progress", e.to_string()))?;
+
         Ok(())
     }
 
+    /// Get the last progress a task reported, if any
+    pub async fn get_task_progress(&self, task_id: TaskId) -> TaskResult<Option<(u8, Option<String>)>> {
+        if let Some(backend) = self.backend() {
+            return backend.get_progress(task_id).await;
+        }
+        let mut conn = self.get_connection().await?;
+
+        let progress: Option<String> = redis::cmd("HGET")
+            .arg(format!("{}:task:{}", self.key(QUEUE_KEY), task_id))
+            .arg("progress")
+            .query_async(&mut *conn)
+            .await
+            .map_err(|e| TaskError::queue_operation("get_progress", e.to_string()))?;
+
+        match progress {
+            Some(json) => Ok(Some(serde_json::from_str(&json)?)),
+            None => Ok(None),
+        }
+    }
+
     /// Get task by ID
     pub async fn get_task(&self, task_id: TaskId) -> TaskResult<Option<TaskDefinition>> {
+        if let Some(backend) = self.backend() {
+            return backend.get_task(task_id).await?.map(Self::decompress_task_result).transpose();
+        }
         let mut conn = self.get_connection().await?;
         
         let task_data: Option<String> = redis::cmd("HGET")
-            .arg(format!("{}:task:{}", QUEUE_KEY, task_id))
+            .arg(format!("{}:task:{}", self.key(QUEUE_KEY), task_id))
             .arg("data")
-            .query_async(&mut conn)
+            .query_async(&mut *conn)
             .await
             .map_err(|e| TaskError::queue_operation("get_task", e.to_string()))?;
 
         match task_data {
-            Some(json) => {
-                let task_def: TaskDefinition = serde_json::from_str(&json)?;
-                Ok(Some(task_def))
-            }
+            Some(json) => Ok(Some(Self::read_task(&json)?)),
             None => Ok(None),
         }
     }
 
     /// Get queue statistics
     pub async fn get_stats(&self, queue_name: &str) -> TaskResult<QueueStats> {
+        if let Some(backend) = self.backend() {
+            return backend.stats(queue_name).await;
+        }
         let mut conn = self.get_connection().await?;
-        let queue_key = format!("{}:{}", QUEUE_KEY, queue_name);
+        let queue_key = format!("{}:{}", self.key(QUEUE_KEY), queue_name);
 
         let pending_tasks: u64 = redis::cmd("ZCARD")
             .arg(&queue_key)
-            .query_async(&mut conn)
+            .query_async(&mut *conn)
             .await
             .map_err(|e| TaskError::queue_operation("get_stats", e.to_string()))?;
 
         let processing_tasks: u64 = redis::cmd("ZCARD")
-            .arg(PROCESSING_KEY)
-            .query_async(&mut conn)
+            .arg(self.key(PROCESSING_KEY))
+            .query_async(&mut *conn)
             .await
             .map_err(|e| TaskError::queue_operation("get_stats", e.to_string()))?;
 
         let scheduled_tasks: u64 = redis::cmd("ZCARD")
-            .arg(SCHEDULED_KEY)
-            .query_async(&mut conn)
+            .arg(self.scheduled_key(queue_name))
+            .query_async(&mut *conn)
+            .await
+            .map_err(|e| TaskError::queue_operation("get_stats", e.to_string()))?;
+
+        // Results past their expiry time are gone even if still indexed
+        let stored_results: u64 = redis::cmd("ZCOUNT")
+            .arg(format!("{}:expiry:{}", self.key(RESULTS_KEY), queue_name))
+            .arg(format!("({}", chrono::Utc::now().timestamp()))
+            .arg("+inf")
+            .query_async(&mut *conn)
+            .await
+            .map_err(|e| TaskError::queue_operation("get_stats", e.to_string()))?;
+
+        let (completed_tasks, failed_tasks, expired_tasks): (Option<u64>, Option<u64>, Option<u64>) = redis::pipe()
+            .get(format!("{}:{}:completed", self.key(STATS_KEY), queue_name))
+            .get(format!("{}:{}:failed", self.key(STATS_KEY), queue_name))
+            .get(format!("{}:{}:expired", self.key(STATS_KEY), queue_name))
+            .query_async(&mut *conn)
             .await
             .map_err(|e| TaskError::queue_operation("get_stats", e.to_string()))?;
 
@@ -375,27 +2751,125 @@ impl TaskQueue {
             pending_tasks,
             processing_tasks,
             scheduled_tasks,
-            completed_tasks: 0, // Would need additional tracking
-            failed_tasks: 0,    // Would need additional tracking
+            stored_results,
+            completed_tasks: completed_tasks.unwrap_or(0),
+            failed_tasks: failed_tasks.unwrap_or(0),
+            expired_tasks: expired_tasks.unwrap_or(0),
         })
     }
 
+    /// Statistics summed over every queue
+    ///
+    /// Covers the queues `list_queues` finds, those with scheduled tasks and
+    /// those with completed, failed or expired counts, plus the default
+    /// queue. `processing_tasks` is already global and is taken as is. On
+    /// other backends only queues with pending tasks are found.
+    pub async fn get_global_stats(&self) -> TaskResult<QueueStats> {
+        let mut queue_names: BTreeSet<String> =
+            self.list_queues().await?.into_iter().map(|info| info.name).collect();
+        queue_names.insert(self.config.default_queue.clone());
+
+        if self.backend().is_none() {
+            let mut conn = self.get_connection().await?;
+            let scheduled: Vec<String> = redis::cmd("SMEMBERS")
+                .arg(self.key(SCHEDULED_QUEUES_KEY))
+                .query_async(&mut *conn)
+                .await
+                .map_err(|e| TaskError::queue_operation("get_global_stats", e.to_string()))?;
+            queue_names.extend(scheduled);
+
+            let stats_prefix = format!("{}:", self.key(STATS_KEY));
+            let counters =
+                Self::scan_keys(&mut conn, &format!("{}*", escape_glob(&stats_prefix)), "get_global_stats").await?;
+            queue_names.extend(counters.iter().filter_map(|key| {
+                let (queue_name, counter) = key.strip_prefix(&stats_prefix)?.rsplit_once(':')?;
+                matches!(counter, "completed" | "failed" | "expired").then(|| queue_name.to_string())
+            }));
+        }
+
+        let mut total = QueueStats::default();
+        for queue_name in &queue_names {
+            let stats = self.get_stats(queue_name).await?;
+            total.pending_tasks += stats.pending_tasks;
+            total.processing_tasks = stats.processing_tasks;
+            total.completed_tasks += stats.completed_tasks;
+            total.failed_tasks += stats.failed_tasks;
+            total.scheduled_tasks += stats.scheduled_tasks;
+            total.stored_results += stats.stored_results;
+            total.expired_tasks += stats.expired_tasks;
+        }
+        Ok(total)
+    }
+
+    /// Reset the completed, failed and expired counters of a queue
+    pub async fn reset_stats(&self, queue_name: &str) -> TaskResult<()> {
+        let mut conn = self.get_connection().await?;
+
+        redis::cmd("DEL")
+            .arg(format!("{}:{}:completed", self.key(STATS_KEY), queue_name))
+            .arg(format!("{}:{}:failed", self.key(STATS_KEY), queue_name))
+            .arg(format!("{}:{}:expired", self.key(STATS_KEY), queue_name))
+            .query_async::<_, ()>(&mut *conn)
+            .await
+            .map_err(|e| TaskError::queue_operation("reset_stats", e.to_string()))?;
+
+        debug!("Reset stats for queue {}", queue_name);
+        Ok(())
+    }
+
+    /// Remove every pending task from a queue
+    ///
+    /// Deletes the queue and the task records of its pending entries in one
+    /// atomic step. Tasks being processed, scheduled tasks and stored results
+    /// are left alone. Returns the number of removed tasks.
+    pub async fn purge_queue(&self, queue_name: &str) -> TaskResult<u64> {
+        if let Some(backend) = self.backend() {
+            return backend.purge_queue(queue_name).await;
+        }
+        let mut conn = self.get_connection().await?;
+
+        let removed: u64 = Script::new(PURGE_QUEUE_SCRIPT)
+            .key(format!("{}:{}", self.key(QUEUE_KEY), queue_name))
+            .key(format!("{}{}", self.status_key_prefix(queue_name), TaskStatus::Pending.as_str()))
+            .arg(format!("{}:task:", self.key(QUEUE_KEY)))
+            .arg(format!("{}:", self.key(IDEMPOTENCY_KEY)))
+            .invoke_async(&mut *conn)
+            .await
+            .map_err(|e| TaskError::queue_operation("purge_queue", e.to_string()))?;
+
+        info!("Purged {} pending tasks from queue {}", removed, queue_name);
+        Ok(removed)
+    }
+
     /// List all available queues
-    pub async fn list_queues(&self) -> TaskResult<Vec<String>> {
+    ///
+    /// Includes queues with pending tasks and paused queues, whether or not
+    /// they hold any tasks.
+    pub async fn list_queues(&self) -> TaskResult<Vec<QueueInfo>> {
+        if let Some(backend) = self.backend() {
+            return Ok(backend
+                .queue_names()
+                .await?
+                .into_iter()
+                .map(|name| QueueInfo { name, paused: false })
+                .collect());
+        }
         let mut conn = self.get_connection().await?;
         
-        let keys: Vec<String> = redis::cmd("KEYS")
-            .arg(format!("{}:*", QUEUE_KEY))
-            .query_async(&mut conn)
-            .await
-            .map_err(|e| TaskError::queue_operation("list_queues", e.to_string()))?;
+        let queue_prefix = format!("{}:", self.key(QUEUE_KEY));
+        let paused_prefix = format!("{}:", self.key(PAUSED_KEY));
+        
+        let keys =
+            Self::scan_keys(&mut conn, &format!("{}*", escape_glob(&queue_prefix)), "list_queues").await?;
+        let paused_keys =
+            Self::scan_keys(&mut conn, &format!("{}*", escape_glob(&paused_prefix)), "list_queues").await?;
 
-        let queues: Vec<String> = keys
+        let mut queues: BTreeMap<String, bool> = keys
             .into_iter()
             .filter_map(|key| {
-                if let Some(queue_name) = key.strip_prefix(&format!("{}:", QUEUE_KEY)) {
+                if let Some(queue_name) = key.strip_prefix(&queue_prefix) {
                     if !queue_name.contains(':') {
-                        Some(queue_name.to_string())
+                        Some((queue_name.to_string(), false))
                     } else {
                         None
                     }
@@ -405,28 +2879,618 @@ impl TaskQueue {
             })
             .collect();
 
-        Ok(queues)
+        for key in paused_keys {
+            if let Some(queue_name) = key.strip_prefix(&paused_prefix) {
+                queues.insert(queue_name.to_string(), true);
+            }
+        }
+
+        Ok(queues
+            .into_iter()
+            .map(|(name, paused)| QueueInfo { name, paused })
+            .collect())
     }
 
-    /// Cleanup expired tasks and data
-    pub async fn cleanup_expired_tasks(&self) -> TaskResult<u64> {
+    /// Stop workers from taking tasks from a queue
+    ///
+    /// Tasks can still be submitted to a paused queue; they wait there until
+    /// the queue is resumed.
+    pub async fn pause_queue(&self, queue_name: &str) -> TaskResult<()> {
         let mut conn = self.get_connection().await?;
-        let now = chrono::Utc::now().timestamp();
-        let cutoff_time = now - (self.config.result_ttl as i64);
 
-        // Remove old processing tasks (tasks stuck in processing state)
-        let removed_count: u64 = redis::cmd("ZREMRANGEBYSCORE")
-            .arg(PROCESSING_KEY)
+        redis::cmd("SET")
+            .arg(format!("{}:{}", self.key(PAUSED_KEY), queue_name))
+            .arg(1)
+            .query_async::<_, ()>(&mut *conn)
+            .await
+            .map_err(|e| TaskError::queue_operation("pause_queue", e.to_string()))?;
+
+        info!("Paused queue {}", queue_name);
+        Ok(())
+    }
+
+    /// Let workers take tasks from a paused queue again
+    pub async fn resume_queue(&self, queue_name: &str) -> TaskResult<()> {
+        let mut conn = self.get_connection().await?;
+
+        redis::cmd("DEL")
+            .arg(format!("{}:{}", self.key(PAUSED_KEY), queue_name))
+            .query_async::<_, ()>(&mut *conn)
+            .await
+            .map_err(|e| TaskError::queue_operation("resume_queue", e.to_string()))?;
+
+        info!("Resumed queue {}", queue_name);
+        Ok(())
+    }
+
+    /// Check whether a queue is paused
+    pub async fn is_queue_paused(&self, queue_name: &str) -> TaskResult<bool> {
+        if self.backend().is_some() {
+            return Ok(false);
+        }
+        let mut conn = self.get_connection().await?;
+
+        redis::cmd("EXISTS")
+            .arg(format!("{}:{}", self.key(PAUSED_KEY), queue_name))
+            .query_async(&mut *conn)
+            .await
+            .map_err(|e| TaskError::queue_operation("is_queue_paused", e.to_string()))
+    }
+
+    /// The paused queues among `queue_names`, in one round trip
+    pub async fn paused_queues(&self, queue_names: &[String]) -> TaskResult<HashSet<String>> {
+        if queue_names.is_empty() || self.backend().is_some() {
+            return Ok(HashSet::new());
+        }
+
+        let mut conn = self.get_connection().await?;
+
+        let mut pipe = redis::pipe();
+        for queue_name in queue_names {
+            pipe.exists(format!("{}:{}", self.key(PAUSED_KEY), queue_name));
+        }
+        let paused: Vec<bool> = pipe
+            .query_async(&mut *conn)
+            .await
+            .map_err(|e| TaskError::queue_operation("paused_queues", e.to_string()))?;
+
+        Ok(queue_names
+            .iter()
+            .zip(paused)
+            .filter(|(_, paused)| *paused)
+            .map(|(queue_name, _)| queue_name.clone())
+            .collect())
+    }
+
+    /// Move every key under the `from` namespace prefix to the `to` prefix
+    ///
+    /// Keys are discovered with `SCAN` and moved in batches with `RENAMENX`,
+    /// so an interrupted migration can simply be run again. Keys whose
+    /// destination already exists are left untouched and reported as conflicts.
+    pub async fn migrate_namespace(&self, from: &str, to: &str) -> TaskResult<NamespaceMigration> {
+        self.run_namespace_migration(from, to, false).await
+    }
+
+    /// Report which keys `migrate_namespace` would move without changing anything
+    pub async fn migrate_namespace_dry_run(
+        &self,
+        from: &str,
+        to: &str,
+    ) -> TaskResult<NamespaceMigration> {
+        self.run_namespace_migration(from, to, true).await
+    }
+
+    async fn run_namespace_migration(
+        &self,
+        from: &str,
+        to: &str,
+        dry_run: bool,
+    ) -> TaskResult<NamespaceMigration> {
+        let from = from.trim_end_matches(':');
+        let to = to.trim_end_matches(':');
+
+        if from.is_empty() || to.is_empty() {
+            return Err(TaskError::config("namespace prefixes must not be empty"));
+        }
+        if from == to || to.starts_with(&format!("{}:", from)) {
+            return Err(TaskError::config(format!(
+                "cannot migrate namespace '{}' into '{}'",
+                from, to
+            )));
+        }
+
+        let mut conn = self.get_connection().await?;
+        let mut report = NamespaceMigration {
+            dry_run,
+            ..Default::default()
+        };
+
+        // Collect first so renames don't disturb the SCAN cursor
+        let keys = Self::scan_keys(&mut conn, &format!("{}:*", escape_glob(from)), "migrate_namespace").await?;
+
+        for chunk in keys.chunks(MIGRATION_BATCH_SIZE) {
+            let targets: Vec<String> = chunk
+                .iter()
+                .map(|key| format!("{}{}", to, &key[from.len()..]))
+                .collect();
+
+            if dry_run {
+                let mut pipe = redis::pipe();
+                for target in &targets {
+                    pipe.exists(target);
+                }
+                let exists: Vec<bool> = pipe
+                    .query_async(&mut *conn)
+                    .await
+                    .map_err(|e| TaskError::queue_operation("migrate_namespace", e.to_string()))?;
+
+                for ((key, target), exists) in chunk.iter().zip(targets).zip(exists) {
+                    if exists {
+                        report.conflicts.push(key.clone());
+                    } else {
+                        report.moved.push((key.clone(), target));
+                    }
+                }
+            } else {
+                let mut pipe = redis::pipe();
+                for (key, target) in chunk.iter().zip(&targets) {
+                    pipe.cmd("RENAMENX").arg(key).arg(target);
+                }
+                let renamed: Vec<bool> = pipe
+                    .query_async(&mut *conn)
+                    .await
+                    .map_err(|e| TaskError::queue_operation("migrate_namespace", e.to_string()))?;
+
+                for ((key, target), renamed) in chunk.iter().zip(targets).zip(renamed) {
+                    if renamed {
+                        report.moved.push((key.clone(), target));
+                    } else {
+                        report.conflicts.push(key.clone());
+                    }
+                }
+            }
+        }
+
+        if !report.conflicts.is_empty() {
+            warn!(
+                "{} keys under '{}' were not migrated because the destination exists",
+                report.conflicts.len(),
+                from
+            );
+        }
+        info!(
+            "{} {} keys from namespace '{}' to '{}'",
+            if dry_run { "Would migrate" } else { "Migrated" },
+            report.moved.len(),
+            from,
+            to
+        );
+
+        Ok(report)
+    }
+
+    /// Write a worker's heartbeat record, expiring after `ttl_seconds`
+    ///
+    /// A worker that stops sending heartbeats disappears from
+    /// `list_active_workers` once its record expires.
+    pub async fn record_worker_heartbeat(&self, record: &WorkerRecord, ttl_seconds: u64) -> TaskResult<()> {
+        if self.backend().is_some() {
+            return Err(TaskError::config("worker heartbeat records require the Redis backend"));
+        }
+        let mut conn = self.get_connection().await?;
+        let worker_key = format!("{}:{}", self.key(WORKERS_KEY), record.worker_id);
+
+        redis::pipe()
+            .hset_multiple(
+                &worker_key,
+                &[
+                    ("worker_id", record.worker_id.clone()),
+                    ("queues", serde_json::to_string(&record.queues)?),
+                    ("active_tasks", record.active_tasks.to_string()),
+                    ("last_heartbeat", record.last_heartbeat.to_rfc3339()),
+                ],
+            )
+            .ignore()
+            .expire(&worker_key, ttl_seconds as i64)
+            .ignore()
+            .query_async::<_, ()>(&mut *conn)
+            .await
+            .map_err(|e| TaskError::queue_operation("record_heartbeat", e.to_string()))?;
+
+        Ok(())
+    }
+
+    /// Remove a worker's heartbeat record
+    pub async fn remove_worker(&self, worker_id: &str) -> TaskResult<()> {
+        if self.backend().is_some() {
+            return Err(TaskError::config("worker heartbeat records require the Redis backend"));
+        }
+        let mut conn = self.get_connection().await?;
+
+        redis::cmd("DEL")
+            .arg(format!("{}:{}", self.key(WORKERS_KEY), worker_id))
+            .query_async::<_, ()>(&mut *conn)
+            .await
+            .map_err(|e| TaskError::queue_operation("remove_worker", e.to_string()))?;
+
+        Ok(())
+    }
+
+    /// List workers whose heartbeat record has not expired
+    pub async fn list_active_workers(&self) -> TaskResult<Vec<WorkerRecord>> {
+        let mut conn = self.get_connection().await?;
+        let keys = Self::scan_keys(&mut conn, &format!("{}:*", escape_glob(&self.key(WORKERS_KEY))), "list_workers").await?;
+
+        let mut workers = Vec::new();
+        for key in keys {
+            let fields: HashMap<String, String> = redis::cmd("HGETALL")
+                .arg(&key)
+                .query_async(&mut *conn)
+                .await
+                .map_err(|e| TaskError::queue_operation("list_workers", e.to_string()))?;
+
+            // The record may have expired between SCAN and HGETALL
+            let (Some(worker_id), Some(last_heartbeat)) =
+                (fields.get("worker_id"), fields.get("last_heartbeat"))
+            else {
+                continue;
+            };
+
+            let last_heartbeat = match DateTime::parse_from_rfc3339(last_heartbeat) {
+                Ok(time) => time.with_timezone(&Utc),
+                Err(e) => {
+                    warn!("Skipping worker record {} with invalid heartbeat: {}", key, e);
+                    continue;
+                }
+            };
+
+            workers.push(WorkerRecord {
+                worker_id: worker_id.clone(),
+                queues: fields
+                    .get("queues")
+                    .and_then(|queues| serde_json::from_str(queues).ok())
+                    .unwrap_or_default(),
+                active_tasks: fields
+                    .get("active_tasks")
+                    .and_then(|count| count.parse().ok())
+                    .unwrap_or(0),
+                last_heartbeat,
+            });
+        }
+
+        Ok(workers)
+    }
+
+    /// List tasks claimed by workers, longest-running first, with the time
+    /// each was claimed
+    ///
+    /// Entries that cannot be read back as a task are skipped with a warning.
+    pub async fn list_processing_tasks(&self, limit: usize) -> TaskResult<Vec<(TaskDefinition, DateTime<Utc>)>> {
+        if limit == 0 {
+            return Ok(Vec::new());
+        }
+
+        let mut conn = self.get_connection().await?;
+
+        let entries: Vec<(String, f64)> = redis::cmd("ZRANGE")
+            .arg(self.key(PROCESSING_KEY))
+            .arg(0)
+            .arg(limit - 1)
+            .arg("WITHSCORES")
+            .query_async(&mut *conn)
+            .await
+            .map_err(|e| TaskError::queue_operation("list_processing", e.to_string()))?;
+
+        let entries: Vec<(TaskId, DateTime<Utc>)> = entries
+            .into_iter()
+            .filter_map(|(member, score)| {
+                let task_id = match member.parse::<TaskId>() {
+                    Ok(task_id) => task_id,
+                    Err(_) => {
+                        warn!("Skipping malformed processing entry {}", member);
+                        return None;
+                    }
+                };
+                let claimed_at = DateTime::from_timestamp(score as i64, 0).unwrap_or_default();
+                Some((task_id, claimed_at))
+            })
+            .collect();
+
+        if entries.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut pipe = redis::pipe();
+        for (task_id, _) in &entries {
+            pipe.hget(format!("{}:task:{}", self.key(QUEUE_KEY), task_id), "data");
+        }
+        let task_data: Vec<Option<String>> = pipe
+            .query_async(&mut *conn)
+            .await
+            .map_err(|e| TaskError::queue_operation("list_processing", e.to_string()))?;
+
+        let mut tasks = Vec::with_capacity(entries.len());
+        for ((task_id, claimed_at), json) in entries.into_iter().zip(task_data) {
+            let Some(json) = json else {
+                warn!("Skipping processing task {} without a task record", task_id);
+                continue;
+            };
+            match serde_json::from_str::<TaskDefinition>(&json) {
+                Ok(task_def) => tasks.push((task_def, claimed_at)),
+                Err(e) => warn!("Skipping corrupt record of processing task {}: {}", task_id, e),
+            }
+        }
+
+        Ok(tasks)
+    }
+
+    /// Re-queue tasks that have been processing for longer than `visibility_timeout` seconds
+    ///
+    /// Such tasks were most likely dequeued by a worker that crashed. Each one
+    /// is reset to `Pending`, has its `reclaim_count` incremented and is
+    /// submitted to its original queue, so it is delivered again; see
+    /// `cleanup_expired_tasks` for tasks that are given up on instead.
+    /// Returns the number of reclaimed tasks.
+    pub async fn reclaim_stalled_tasks(&self, visibility_timeout: u64) -> TaskResult<u64> {
+        if self.backend().is_some() {
+            return Err(TaskError::config("reclaiming tasks requires the Redis backend"));
+        }
+        let mut conn = self.get_connection().await?;
+        let cutoff_time = chrono::Utc::now().timestamp() - visibility_timeout as i64;
+
+        let stalled: Vec<String> = redis::cmd("ZRANGEBYSCORE")
+            .arg(self.key(PROCESSING_KEY))
             .arg("-inf")
             .arg(cutoff_time)
-            .query_async(&mut conn)
+            .query_async(&mut *conn)
             .await
-            .map_err(|e| TaskError::queue_operation("cleanup", e.to_string()))?;
+            .map_err(|e| TaskError::queue_operation("reclaim_stalled", e.to_string()))?;
+        drop(conn);
+
+        let mut reclaimed_count = 0;
+
+        for member in stalled {
+            if let Some(task_def) = self.reclaim_processing_entry(&member).await? {
+                warn!(
+                    "Reclaimed stalled task {} into queue {} (reclaim {})",
+                    task_def.id, task_def.queue, task_def.reclaim_count
+                );
+                reclaimed_count += 1;
+            }
+        }
+
+        Ok(reclaimed_count)
+    }
+
+    /// Re-queue running tasks whose worker's heartbeat record has expired
+    ///
+    /// Unlike `reclaim_stalled_tasks`, this recovers the tasks of a crashed
+    /// worker as soon as its heartbeat record expires rather than after the
+    /// visibility timeout. Tasks are matched to workers by the `worker_id`
+    /// recorded when they started, so claimed tasks that have not started yet
+    /// are left to `reclaim_stalled_tasks`, as are tasks started within the
+    /// last `DEAD_WORKER_GRACE_SECONDS`, before their worker's first heartbeat
+    /// may have been written. Returns the number of reclaimed tasks.
+    pub async fn reclaim_dead_worker_tasks(&self) -> TaskResult<u64> {
+        if self.backend().is_some() {
+            return Err(TaskError::config("reclaiming tasks requires the Redis backend"));
+        }
+        let live_workers: HashSet<String> = self
+            .list_active_workers()
+            .await?
+            .into_iter()
+            .map(|record| record.worker_id)
+            .collect();
+
+        let members: Vec<String> = {
+            let mut conn = self.get_connection().await?;
+            redis::cmd("ZRANGE")
+                .arg(self.key(PROCESSING_KEY))
+                .arg(0)
+                .arg(-1)
+                .query_async(&mut *conn)
+                .await
+                .map_err(|e| TaskError::queue_operation("reclaim_dead_workers", e.to_string()))?
+        };
+        let task_ids: Vec<TaskId> = members.iter().filter_map(|member| member.parse().ok()).collect();
+        let tasks = self.get_tasks(&task_ids).await?;
+        let started_before = Utc::now() - chrono::Duration::seconds(DEAD_WORKER_GRACE_SECONDS);
+
+        let mut reclaimed_count = 0;
+
+        for task_def in tasks.into_values().flatten() {
+            let orphaned = task_def.status == TaskStatus::Running
+                && task_def.started_at.is_some_and(|started_at| started_at < started_before)
+                && task_def
+                    .worker_id
+                    .as_ref()
+                    .is_some_and(|worker_id| !live_workers.contains(worker_id));
+            if !orphaned {
+                continue;
+            }
+
+            if let Some(task_def) = self.reclaim_processing_entry(&task_def.id.to_string()).await? {
+                warn!(
+                    "Reclaimed task {} of dead worker into queue {} (reclaim {})",
+                    task_def.id, task_def.queue, task_def.reclaim_count
+                );
+                reclaimed_count += 1;
+            }
+        }
+
+        Ok(reclaimed_count)
+    }
+
+    /// Remove a processing entry and re-submit its task to its queue
+    ///
+    /// Returns the re-submitted task, or `None` if another caller removed the
+    /// entry first or the task is gone or already finished.
+    async fn reclaim_processing_entry(&self, member: &str) -> TaskResult<Option<TaskDefinition>> {
+        // Only the caller that removes the entry gets to reclaim it
+        let removed: u64 = {
+            let mut conn = self.get_connection().await?;
+            redis::cmd("ZREM")
+                .arg(self.key(PROCESSING_KEY))
+                .arg(member)
+                .query_async(&mut *conn)
+                .await
+                .map_err(|e| TaskError::queue_operation("reclaim", e.to_string()))?
+        };
+        if removed == 0 {
+            return Ok(None);
+        }
+
+        let task_id = match member.parse::<TaskId>() {
+            Ok(task_id) => task_id,
+            Err(_) => {
+                warn!("Dropped malformed processing entry {}", member);
+                return Ok(None);
+            }
+        };
+
+        let task_def = match self.get_task(task_id).await {
+            Ok(Some(task_def)) => task_def,
+            Ok(None) => {
+                warn!("Reclaimed task {} no longer exists, dropping it", task_id);
+                return Ok(None);
+            }
+            Err(TaskError::Serialization(e)) => {
+                warn!("Dropped processing task {} with a corrupt record: {}", task_id, e);
+                return Ok(None);
+            }
+            Err(e) => return Err(e),
+        };
+
+        if task_def.status.is_terminal() {
+            return Ok(None);
+        }
+
+        self.redeliver(task_def).await
+    }
+
+    /// Re-submit a task its worker did not finish, or fail it once it has
+    /// used up `max_redeliveries`
+    ///
+    /// Returns the re-submitted task, or `None` if it was failed instead.
+    async fn redeliver(&self, mut task_def: TaskDefinition) -> TaskResult<Option<TaskDefinition>> {
+        self.release_resources(&task_def).await?;
+        if self
+            .config
+            .max_redeliveries
+            .is_some_and(|max_redeliveries| task_def.reclaim_count >= max_redeliveries)
+        {
+            let error = format!("Gave up after {} redeliveries", task_def.reclaim_count);
+            warn!("Task {}: {}", task_def.id, error);
+            task_def.mark_failed(&error);
+            self.mark_task_failed(&task_def).await?;
+            return Ok(None);
+        }
+        task_def.mark_reclaimed();
+        self.resubmit_task(task_def.clone()).await?;
+        Ok(Some(task_def))
+    }
+
+    /// Hand back a task whose handler finished without acknowledging it
+    /// (see `WorkerConfig::manual_ack`)
+    ///
+    /// With Redis the task stays in the processing set, and the reclaim loop
+    /// redelivers it after the visibility timeout. A backend has no reclaim
+    /// loop, so the task is redelivered right away. Either way it is failed
+    /// once it has been redelivered `max_redeliveries` times.
+    pub async fn release_unacked(&self, task_def: &TaskDefinition) -> TaskResult<()> {
+        if self.backend().is_some() {
+            self.redeliver(task_def.clone()).await?;
+        }
+        Ok(())
+    }
+
+    /// Give up on tasks that have been processing for longer than
+    /// `processing_max_age` seconds
+    ///
+    /// Tasks past the visibility timeout are normally redelivered by
+    /// `reclaim_stalled_tasks` long before this; an entry that is still there
+    /// was never reclaimed, for example because no worker ran the reclaim
+    /// loop. It is removed rather than executed again, and its task, if it
+    /// has not finished, is marked failed. Returns the number of removed
+    /// entries.
+    pub async fn cleanup_expired_tasks(&self) -> TaskResult<u64> {
+        if self.backend().is_some() {
+            return Err(TaskError::config("cleaning up expired tasks requires the Redis backend"));
+        }
+        let cutoff_time = chrono::Utc::now().timestamp() - self.config.processing_max_age as i64;
+
+        let abandoned: Vec<String> = {
+            let mut conn = self.get_connection().await?;
+            redis::cmd("ZRANGEBYSCORE")
+                .arg(self.key(PROCESSING_KEY))
+                .arg("-inf")
+                .arg(cutoff_time)
+                .query_async(&mut *conn)
+                .await
+                .map_err(|e| TaskError::queue_operation("cleanup", e.to_string()))?
+        };
+
+        let mut removed_count = 0;
+        for member in abandoned {
+            if self.abandon_processing_entry(&member).await? {
+                removed_count += 1;
+            }
+        }
 
         if removed_count > 0 {
-            warn!("Cleaned up {} stuck processing tasks", removed_count);
+            warn!("Gave up on {} tasks abandoned in processing", removed_count);
         }
 
         Ok(removed_count)
     }
-} 
\ No newline at end of file
+
+    /// Remove a processing entry for good, failing its task if it has not
+    /// finished. Returns `false` if another caller removed it first.
+    async fn abandon_processing_entry(&self, member: &str) -> TaskResult<bool> {
+        let removed: u64 = {
+            let mut conn = self.get_connection().await?;
+            redis::cmd("ZREM")
+                .arg(self.key(PROCESSING_KEY))
+                .arg(member)
+                .query_async(&mut *conn)
+                .await
+                .map_err(|e| TaskError::queue_operation("cleanup", e.to_string()))?
+        };
+        if removed == 0 {
+            return Ok(false);
+        }
+
+        let Ok(task_id) = member.parse::<TaskId>() else {
+            return Ok(true);
+        };
+        let mut task_def = match self.get_task(task_id).await {
+            Ok(Some(task_def)) if !task_def.status.is_terminal() => task_def,
+            Ok(_) | Err(TaskError::Serialization(_)) => return Ok(true),
+            Err(e) => return Err(e),
+        };
+
+        self.release_resources(&task_def).await?;
+        task_def.mark_failed(&format!(
+            "Abandoned after more than {} seconds in processing",
+            self.config.processing_max_age
+        ));
+        self.mark_task_failed(&task_def).await?;
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn queue_score_keeps_equal_priorities_in_submission_order() {
+        let queue = TaskQueue::new_in_memory(TaskQueueConfig::default()).unwrap();
+        let scores: Vec<f64> = (0..3).map(|sequence| queue.queue_score(&TaskPriority::Normal, sequence)).collect();
+
+        // Highest score is dequeued first
+        assert!(scores[0] > scores[1] && scores[1] > scores[2], "{:?}", scores);
+        assert!(queue.queue_score(&TaskPriority::Normal, SEQUENCE_SPAN - 1) > queue.queue_score(&TaskPriority::Low, 0));
+        assert!(queue.queue_score(&TaskPriority::High, SEQUENCE_SPAN - 1) > scores[0]);
+    }
+}
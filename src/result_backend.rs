@@ -0,0 +1,241 @@
+//! Storage for task results
+
+use async_trait::async_trait;
+use bb8::Pool;
+use redis::Script;
+use tracing::debug;
+
+use crate::error::{TaskError, TaskResult};
+use crate::queue::RedisConnectionManager;
+use crate::task::{TaskDefinition, TaskId};
+
+/// Record a stored result in its queue's result indexes and evict the
+/// oldest results beyond the cap.
+///
+/// KEYS[1]: result index sorted set, scored by when each result was stored
+/// (in milliseconds, older versions used seconds), KEYS[2]: expiry sorted
+/// set, scored by when each result expires.
+/// ARGV[1]: task id, ARGV[2]: current time in milliseconds, ARGV[3]: expiry timestamp
+/// (`+inf` for none), ARGV[4]: cap (0 for unlimited), ARGV[5]: result key
+/// prefix, ARGV[6]: task key prefix. Indexes written before the expiry set
+/// existed get their expiry entries from the results' remaining TTLs.
+/// Returns the number of evicted results.
+const RECORD_RESULT_SCRIPT: &str = r#"
+local now = math.floor(tonumber(ARGV[2]) / 1000)
+if redis.call('EXISTS', KEYS[2]) == 0 then
+    for _, id in ipairs(redis.call('ZRANGE', KEYS[1], 0, -1)) do
+        local ttl = redis.call('TTL', ARGV[5] .. id)
+        if ttl == -2 then
+            redis.call('ZREM', KEYS[1], id)
+        elseif ttl == -1 then
+            redis.call('ZADD', KEYS[2], '+inf', id)
+        else
+            redis.call('ZADD', KEYS[2], now + ttl, id)
+        end
+    end
+end
+redis.call('ZADD', KEYS[1], ARGV[2], ARGV[1])
+redis.call('ZADD', KEYS[2], ARGV[3], ARGV[1])
+for _, id in ipairs(redis.call('ZRANGEBYSCORE', KEYS[2], '-inf', now)) do
+    redis.call('ZREM', KEYS[1], id)
+end
+redis.call('ZREMRANGEBYSCORE', KEYS[2], '-inf', now)
+local cap = tonumber(ARGV[4])
+if cap == 0 then
+    return 0
+end
+local excess = redis.call('ZCARD', KEYS[1]) - cap
+if excess <= 0 then
+    return 0
+end
+local evicted = redis.call('ZRANGE', KEYS[1], 0, excess - 1)
+for _, id in ipairs(evicted) do
+    redis.call('DEL', ARGV[5] .. id, ARGV[6] .. id)
+    redis.call('ZREM', KEYS[2], id)
+end
+redis.call('ZREMRANGEBYRANK', KEYS[1], 0, excess - 1)
+return #evicted
+"#;
+
+/// Where the outcomes of finished tasks are stored
+///
+/// The queue writes every finished task here, and `TaskClient::wait_for_result`
+/// reads results back from it. The default, `RedisResultBackend`, keeps them
+/// in Redis with a TTL. Implement this to store results somewhere durable,
+/// such as a SQL database, and install it with `TaskQueue::with_result_backend`.
+#[async_trait]
+pub trait ResultBackend: Send + Sync + std::fmt::Debug {
+    /// Store a successfully completed task
+    async fn store_result(&self, task_def: &TaskDefinition) -> TaskResult<()>;
+
+    /// Store a task that failed for good
+    async fn store_failure(&self, task_def: &TaskDefinition) -> TaskResult<()>;
+
+    /// Get a finished task (completed or failed), or `None` if there is no
+    /// stored outcome for it
+    async fn get_result(&self, task_id: TaskId) -> TaskResult<Option<TaskDefinition>>;
+
+    /// Forget a task's stored outcome, so it can run again
+    ///
+    /// Called before a failed task is re-submitted by
+    /// `TaskQueue::retry_failed_task`. The default keeps the old outcome,
+    /// which `wait_for_result` would then return for the new run.
+    async fn remove_result(&self, _task_id: TaskId) -> TaskResult<()> {
+        Ok(())
+    }
+}
+
+/// Stores results in Redis, expiring them after a TTL
+///
+/// A task's own `result_ttl` takes precedence over the backend's; a TTL of 0
+/// keeps the result until it is evicted.
+/// Successful results are indexed per queue so the store can be capped with
+/// `TaskQueueConfig::max_results_per_queue`.
+#[derive(Debug, Clone)]
+pub struct RedisResultBackend {
+    pool: Pool<RedisConnectionManager>,
+    results_key: String,
+    failed_key: String,
+    task_key_prefix: String,
+    result_ttl: u64,
+    failed_ttl: u64,
+    max_results_per_queue: Option<u64>,
+}
+
+impl RedisResultBackend {
+    /// Create a backend storing results under `results_key` and failures
+    /// under `failed_key`
+    pub(crate) fn new(
+        pool: Pool<RedisConnectionManager>,
+        results_key: String,
+        failed_key: String,
+        task_key_prefix: String,
+        result_ttl: u64,
+        failed_ttl: u64,
+        max_results_per_queue: Option<u64>,
+    ) -> Self {
+        Self {
+            pool,
+            results_key,
+            failed_key,
+            task_key_prefix,
+            result_ttl,
+            failed_ttl,
+            max_results_per_queue,
+        }
+    }
+
+    fn result_key(&self, task_id: TaskId) -> String {
+        format!("{}:result:{}", self.results_key, task_id)
+    }
+
+    fn failure_key(&self, task_id: TaskId) -> String {
+        format!("{}:failed:{}", self.failed_key, task_id)
+    }
+}
+
+#[async_trait]
+impl ResultBackend for RedisResultBackend {
+    async fn store_result(&self, task_def: &TaskDefinition) -> TaskResult<()> {
+        let mut conn = self
+            .pool
+            .get()
+            .await
+            .map_err(|e| TaskError::queue_operation("get_connection", e.to_string()))?;
+        let task_json = serde_json::to_string(task_def)?;
+        let result_key = self.result_key(task_def.id);
+        let result_ttl = task_def.result_ttl.unwrap_or(self.result_ttl);
+
+        let mut pipe = redis::pipe();
+        pipe.hset(&result_key, "data", &task_json).ignore();
+        if result_ttl > 0 {
+            pipe.expire(&result_key, result_ttl as i64).ignore();
+        } else {
+            pipe.persist(&result_key).ignore();
+        }
+        pipe.query_async::<_, ()>(&mut *conn)
+            .await
+            .map_err(|e| TaskError::queue_operation("store_result", e.to_string()))?;
+
+        // Track the result so the store can be capped per queue
+        let stored_at = chrono::Utc::now();
+        let now = stored_at.timestamp();
+        let expires_at = if result_ttl > 0 {
+            (now + result_ttl as i64).to_string()
+        } else {
+            "+inf".to_string()
+        };
+        let evicted: u64 = Script::new(RECORD_RESULT_SCRIPT)
+            .key(format!("{}:index:{}", self.results_key, task_def.queue))
+            .key(format!("{}:expiry:{}", self.results_key, task_def.queue))
+            .arg(task_def.id.to_string())
+            .arg(stored_at.timestamp_millis())
+            .arg(expires_at)
+            .arg(self.max_results_per_queue.unwrap_or(0))
+            .arg(format!("{}:result:", self.results_key))
+            .arg(&self.task_key_prefix)
+            .invoke_async(&mut *conn)
+            .await
+            .map_err(|e| TaskError::queue_operation("store_result", e.to_string()))?;
+
+        if evicted > 0 {
+            debug!("Evicted {} old results from queue {}", evicted, task_def.queue);
+        }
+
+        Ok(())
+    }
+
+    async fn store_failure(&self, task_def: &TaskDefinition) -> TaskResult<()> {
+        let mut conn = self
+            .pool
+            .get()
+            .await
+            .map_err(|e| TaskError::queue_operation("get_connection", e.to_string()))?;
+        let task_json = serde_json::to_string(task_def)?;
+        let failure_key = self.failure_key(task_def.id);
+
+        redis::pipe()
+            .hset(&failure_key, "data", &task_json)
+            .ignore()
+            .expire(&failure_key, self.failed_ttl as i64)
+            .ignore()
+            .query_async::<_, ()>(&mut *conn)
+            .await
+            .map_err(|e| TaskError::queue_operation("store_failure", e.to_string()))
+    }
+
+    async fn get_result(&self, task_id: TaskId) -> TaskResult<Option<TaskDefinition>> {
+        let mut conn = self
+            .pool
+            .get()
+            .await
+            .map_err(|e| TaskError::queue_operation("get_connection", e.to_string()))?;
+
+        let (result, failure): (Option<String>, Option<String>) = redis::pipe()
+            .hget(self.result_key(task_id), "data")
+            .hget(self.failure_key(task_id), "data")
+            .query_async(&mut *conn)
+            .await
+            .map_err(|e| TaskError::queue_operation("get_result", e.to_string()))?;
+
+        match result.or(failure) {
+            Some(json) => Ok(Some(serde_json::from_str(&json)?)),
+            None => Ok(None),
+        }
+    }
+
+    async fn remove_result(&self, task_id: TaskId) -> TaskResult<()> {
+        let mut conn = self
+            .pool
+            .get()
+            .await
+            .map_err(|e| TaskError::queue_operation("get_connection", e.to_string()))?;
+
+        redis::cmd("DEL")
+            .arg(self.result_key(task_id))
+            .arg(self.failure_key(task_id))
+            .query_async::<_, ()>(&mut *conn)
+            .await
+            .map_err(|e| TaskError::queue_operation("remove_result", e.to_string()))
+    }
+}
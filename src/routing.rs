@@ -0,0 +1,63 @@
+//! Submit-time routing of tasks to queues
+
+use std::collections::HashMap;
+
+use crate::task::{TaskDefinition, TaskPriority};
+
+/// Picks the queue a task is submitted to
+///
+/// Consulted by `TaskClient` for every task it submits, with `task_def.queue`
+/// set to the queue the caller asked for.
+pub trait RoutingPolicy: Send + Sync + std::fmt::Debug {
+    /// Name of the queue to submit the task to
+    fn route(&self, task_def: &TaskDefinition) -> String;
+}
+
+/// Submits every task to the queue the caller asked for
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DefaultRoutingPolicy;
+
+impl RoutingPolicy for DefaultRoutingPolicy {
+    fn route(&self, task_def: &TaskDefinition) -> String {
+        task_def.queue.clone()
+    }
+}
+
+/// Routes tasks to a dedicated queue per priority
+///
+/// Priorities without a route go to the queue the caller asked for.
+///
+/// ```
+/// use distributed_task_queue::routing::PriorityRoutingPolicy;
+/// use distributed_task_queue::TaskPriority;
+///
+/// let policy = PriorityRoutingPolicy::new()
+///     .route_priority(TaskPriority::High, "fast")
+///     .route_priority(TaskPriority::Critical, "fast");
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct PriorityRoutingPolicy {
+    routes: HashMap<TaskPriority, String>,
+}
+
+impl PriorityRoutingPolicy {
+    /// Create a policy with no routes
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Send tasks of `priority` to `queue`
+    pub fn route_priority<S: Into<String>>(mut self, priority: TaskPriority, queue: S) -> Self {
+        self.routes.insert(priority, queue.into());
+        self
+    }
+}
+
+impl RoutingPolicy for PriorityRoutingPolicy {
+    fn route(&self, task_def: &TaskDefinition) -> String {
+        self.routes
+            .get(&task_def.priority)
+            .cloned()
+            .unwrap_or_else(|| task_def.queue.clone())
+    }
+}
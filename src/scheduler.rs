@@ -1,17 +1,18 @@
 //! Task scheduler for managing scheduled and periodic tasks
 
-use chrono::{DateTime, Datelike, Duration, Utc};
+use chrono::{DateTime, Datelike, Duration, TimeZone, Timelike, Utc};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
-use std::sync::Arc;
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex as StdMutex};
 use tokio::sync::RwLock;
-use tokio::time::{interval, sleep};
+use tokio::time::interval;
 use tracing::{debug, error, info, warn};
 use uuid::Uuid;
 
 use crate::client::TaskClient;
 use crate::error::{TaskError, TaskResult};
-use crate::task::{Task, TaskId, TaskPriority};
+use crate::task::{SerializableTask, TaskId, TaskPriority};
 
 /// Unique identifier for scheduled job definitions
 pub type ScheduledJobId = Uuid;
@@ -33,7 +34,7 @@ pub enum ScheduleExpression {
     Daily { hour: u32, minute: u32 },
     /// Run weekly on specific day and time (0=Sunday, 1=Monday, etc.)
     Weekly { day: u32, hour: u32, minute: u32 },
-    /// Cron expression (basic implementation)
+    /// Cron expression (evaluated in UTC; see `CronSchedule` for the syntax)
     Cron(String),
 }
 
@@ -67,7 +68,7 @@ impl ScheduleExpression {
                     .and_utc();
                 
                 if next <= from {
-                    next = next + Duration::days(1);
+                    next += Duration::days(1);
                 }
                 Some(next)
             }
@@ -86,19 +87,30 @@ impl ScheduleExpression {
                     + Duration::days(days_until_target as i64);
                 
                 if next <= from {
-                    next = next + Duration::weeks(1);
+                    next += Duration::weeks(1);
                 }
                 Some(next)
             }
-            ScheduleExpression::Cron(_expr) => {
-                // Basic cron implementation would go here
-                // For now, just return None
-                warn!("Cron expressions not fully implemented yet");
-                None
-            }
+            ScheduleExpression::Cron(expr) => match CronSchedule::parse(expr) {
+                Ok(cron) => cron.next_after(from),
+                Err(e) => {
+                    warn!("Invalid cron expression '{}': {}", expr, e);
+                    None
+                }
+            },
         }
     }
 
+    /// Check that the expression can be evaluated
+    ///
+    /// Fails for a malformed cron expression, which would otherwise never fire.
+    pub fn validate(&self) -> TaskResult<()> {
+        if let ScheduleExpression::Cron(expr) = self {
+            CronSchedule::parse(expr)?;
+        }
+        Ok(())
+    }
+
     /// Check if this is a recurring schedule
     pub fn is_recurring(&self) -> bool {
         matches!(
@@ -113,6 +125,300 @@ impl ScheduleExpression {
     }
 }
 
+/// Set of allowed values for a single cron field
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct CronField {
+    /// Bit `n` is set when value `n` is allowed
+    allowed: u64,
+    /// Whether the field was restricted (did not start with `*`)
+    restricted: bool,
+}
+
+impl CronField {
+    /// Parse a field such as `*`, `*/15`, `1-5`, `1,15,30` or `10-40/10`
+    fn parse(field: &str, min: u32, max: u32) -> TaskResult<Self> {
+        let mut allowed = 0u64;
+
+        for part in field.split(',') {
+            let (range, step) = match part.split_once('/') {
+                Some((range, step)) => {
+                    let step: u32 = step
+                        .parse()
+                        .map_err(|_| TaskError::scheduler(format!("invalid step '{}'", step)))?;
+                    if step == 0 {
+                        return Err(TaskError::scheduler("cron step must be greater than zero"));
+                    }
+                    (range, step)
+                }
+                None => (part, 1),
+            };
+
+            let (start, end) = if range == "*" {
+                (min, max)
+            } else if let Some((start, end)) = range.split_once('-') {
+                (Self::parse_value(start, min, max)?, Self::parse_value(end, min, max)?)
+            } else {
+                let start = Self::parse_value(range, min, max)?;
+                // `a/n` means "from a to the end of the range every n"
+                if part.contains('/') {
+                    (start, max)
+                } else {
+                    (start, start)
+                }
+            };
+
+            if start > end {
+                return Err(TaskError::scheduler(format!("invalid cron range '{}'", range)));
+            }
+
+            for value in (start..=end).step_by(step as usize) {
+                allowed |= 1 << value;
+            }
+        }
+
+        Ok(Self {
+            allowed,
+            restricted: !field.starts_with('*'),
+        })
+    }
+
+    fn parse_value(value: &str, min: u32, max: u32) -> TaskResult<u32> {
+        let parsed: u32 = value
+            .parse()
+            .map_err(|_| TaskError::scheduler(format!("invalid cron value '{}'", value)))?;
+        if parsed < min || parsed > max {
+            return Err(TaskError::scheduler(format!(
+                "cron value {} out of range {}-{}",
+                parsed, min, max
+            )));
+        }
+        Ok(parsed)
+    }
+
+    fn contains(&self, value: u32) -> bool {
+        self.allowed & (1 << value) != 0
+    }
+}
+
+/// Parsed cron expression
+///
+/// Takes the standard 5 fields (minute hour day-of-month month day-of-week),
+/// or 6 with a leading seconds field for schedules finer than a minute, or
+/// one of the macros `@yearly` (`@annually`), `@monthly`, `@weekly`, `@daily`
+/// (`@midnight`) and `@hourly`. A 5-field expression fires at second 0.
+///
+/// All times are evaluated in UTC. As in classic cron, when both day-of-month
+/// and day-of-week are restricted a day matches if either field matches.
+///
+/// ```
+/// use chrono::{TimeZone, Utc};
+/// use distributed_task_queue::scheduler::CronSchedule;
+///
+/// let from = Utc.with_ymd_and_hms(2024, 5, 1, 10, 20, 5).unwrap();
+///
+/// let hourly = CronSchedule::parse("@hourly")?;
+/// assert_eq!(hourly.next_after(from), Utc.with_ymd_and_hms(2024, 5, 1, 11, 0, 0).single());
+///
+/// // Every 15 seconds
+/// let frequent = CronSchedule::parse("*/15 * * * * *")?;
+/// assert_eq!(frequent.next_after(from), Utc.with_ymd_and_hms(2024, 5, 1, 10, 20, 15).single());
+///
+/// assert!(CronSchedule::parse("@fortnightly").is_err());
+/// assert!(CronSchedule::parse("61 * * * *").is_err());
+/// # Ok::<(), distributed_task_queue::TaskError>(())
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CronSchedule {
+    seconds: CronField,
+    minutes: CronField,
+    hours: CronField,
+    days_of_month: CronField,
+    months: CronField,
+    days_of_week: CronField,
+}
+
+impl CronSchedule {
+    /// How far ahead to search for a matching time before giving up
+    const MAX_LOOKAHEAD_DAYS: i64 = 366 * 5;
+
+    /// Parse a 5- or 6-field cron expression or a macro
+    pub fn parse(expr: &str) -> TaskResult<Self> {
+        let expr = expr.trim();
+        let expanded = match expr {
+            "@yearly" | "@annually" => "0 0 1 1 *",
+            "@monthly" => "0 0 1 * *",
+            "@weekly" => "0 0 * * 0",
+            "@daily" | "@midnight" => "0 0 * * *",
+            "@hourly" => "0 * * * *",
+            _ if expr.starts_with('@') => {
+                return Err(TaskError::scheduler(format!("unknown cron macro '{}'", expr)));
+            }
+            _ => expr,
+        };
+
+        let mut fields: Vec<&str> = expanded.split_whitespace().collect();
+        let seconds = match fields.len() {
+            5 => CronField::parse("0", 0, 59)?,
+            6 => CronField::parse(fields.remove(0), 0, 59)?,
+            found => {
+                return Err(TaskError::scheduler(format!(
+                    "expected 5 or 6 cron fields, found {}",
+                    found
+                )));
+            }
+        };
+
+        let mut days_of_week = CronField::parse(fields[4], 0, 7)?;
+        // Both 0 and 7 mean Sunday
+        if days_of_week.contains(7) {
+            days_of_week.allowed |= 1;
+        }
+
+        Ok(Self {
+            seconds,
+            minutes: CronField::parse(fields[0], 0, 59)?,
+            hours: CronField::parse(fields[1], 0, 23)?,
+            days_of_month: CronField::parse(fields[2], 1, 31)?,
+            months: CronField::parse(fields[3], 1, 12)?,
+            days_of_week,
+        })
+    }
+
+    /// Next matching time strictly after `from`
+    pub fn next_after(&self, from: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let mut next = from.with_nanosecond(0)? + Duration::seconds(1);
+        let limit = from + Duration::days(Self::MAX_LOOKAHEAD_DAYS);
+
+        while next <= limit {
+            if !self.months.contains(next.month()) {
+                let (year, month) = if next.month() == 12 {
+                    (next.year() + 1, 1)
+                } else {
+                    (next.year(), next.month() + 1)
+                };
+                next = Utc.with_ymd_and_hms(year, month, 1, 0, 0, 0).single()?;
+                continue;
+            }
+
+            if !self.day_matches(next) {
+                next = next.date_naive().and_hms_opt(0, 0, 0)?.and_utc() + Duration::days(1);
+                continue;
+            }
+
+            if !self.hours.contains(next.hour()) {
+                next = next.with_minute(0)?.with_second(0)? + Duration::hours(1);
+                continue;
+            }
+
+            if !self.minutes.contains(next.minute()) {
+                next = next.with_second(0)? + Duration::minutes(1);
+                continue;
+            }
+
+            match (next.second()..60).find(|&second| self.seconds.contains(second)) {
+                Some(second) => return next.with_second(second),
+                None => next = next.with_second(0)? + Duration::minutes(1),
+            }
+        }
+
+        None
+    }
+
+    fn day_matches(&self, time: DateTime<Utc>) -> bool {
+        let dom = self.days_of_month.contains(time.day());
+        let dow = self
+            .days_of_week
+            .contains(time.weekday().num_days_from_sunday());
+
+        if self.days_of_month.restricted && self.days_of_week.restricted {
+            dom || dow
+        } else {
+            dom && dow
+        }
+    }
+}
+
+/// Maximum number of entries kept in a job's run history
+const MAX_JOB_HISTORY: usize = 50;
+
+/// How late a fire may run before it counts as missed
+const MISFIRE_GRACE_SECONDS: i64 = 60;
+
+/// What to do with a fire that was missed, e.g. while no scheduler was running
+///
+/// A fire counts as missed once it is more than a minute overdue.
+///
+/// ```
+/// use chrono::{Duration, Utc};
+/// use distributed_task_queue::scheduler::{JobRunOutcome, MisfirePolicy, ScheduleExpression, ScheduledJob};
+/// use distributed_task_queue::{Task, TaskError};
+///
+/// #[derive(Debug, serde::Serialize, serde::Deserialize)]
+/// struct Report;
+///
+/// #[async_trait::async_trait]
+/// impl Task for Report {
+///     type Output = ();
+///     type Error = TaskError;
+///     async fn execute(&self) -> Result<(), TaskError> { Ok(()) }
+/// }
+///
+/// let daily = ScheduleExpression::Daily { hour: 6, minute: 0 };
+/// let yesterday = Utc::now() - Duration::days(1);
+///
+/// // CatchUp: the missed run fires once, then the schedule resumes
+/// let mut job = ScheduledJob::new("report".into(), &Report, "reports".into(), daily.clone())?;
+/// job.next_run = Some(yesterday);
+/// assert!(job.is_ready() && job.is_misfired(Utc::now()));
+/// job.mark_executed(true);
+/// assert_eq!(job.run_count, 1);
+/// assert!(job.next_run.unwrap() > Utc::now());
+///
+/// // Skip: the missed run is dropped and the job waits for the next one
+/// let mut job = ScheduledJob::new("report".into(), &Report, "reports".into(), daily)?
+///     .with_misfire_policy(MisfirePolicy::Skip);
+/// job.next_run = Some(yesterday);
+/// job.skip_misfire();
+/// assert_eq!(job.run_count, 0);
+/// assert_eq!(job.history.last().unwrap().outcome, JobRunOutcome::Missed);
+/// assert!(job.next_run.unwrap() > Utc::now());
+/// # Ok::<(), TaskError>(())
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum MisfirePolicy {
+    /// Fire once as soon as possible, however many fires were missed; a
+    /// one-time job created after its time fires right away
+    #[default]
+    CatchUp,
+    /// Drop missed fires and wait for the next occurrence; a one-time job
+    /// that missed its time never fires
+    Skip,
+}
+
+/// What happened to a scheduled fire of a job
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum JobRunOutcome {
+    /// The task was submitted
+    Submitted,
+    /// Submitting the task failed
+    Failed,
+    /// The fire was skipped on request
+    Skipped,
+    /// The fire was missed and dropped (see `MisfirePolicy::Skip`)
+    Missed,
+}
+
+/// Entry in a scheduled job's run history
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JobRunRecord {
+    /// When the fire was scheduled for
+    pub scheduled_for: Option<DateTime<Utc>>,
+    /// When the outcome was recorded
+    pub recorded_at: DateTime<Utc>,
+    /// What happened
+    pub outcome: JobRunOutcome,
+}
+
 /// Configuration for a scheduled job
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ScheduledJob {
@@ -140,16 +446,33 @@ pub struct ScheduledJob {
     pub last_run: Option<DateTime<Utc>>,
     /// Number of times this job has been executed
     pub run_count: u64,
+    /// Disable the job once it has run this many times, recurring or not
+    #[serde(default)]
+    pub max_runs: Option<u64>,
+    /// The job does not fire before this time
+    #[serde(default)]
+    pub valid_from: Option<DateTime<Utc>>,
+    /// The job does not fire after this time, and is disabled once it passes
+    #[serde(default)]
+    pub valid_until: Option<DateTime<Utc>>,
     /// Number of failed executions
     pub failure_count: u64,
     /// Job creation time
     pub created_at: DateTime<Utc>,
     /// Job last update time
     pub updated_at: DateTime<Utc>,
+    /// Most recent fires of this job, oldest first
+    #[serde(default)]
+    pub history: Vec<JobRunRecord>,
+    /// What to do with fires that were missed
+    #[serde(default)]
+    pub misfire_policy: MisfirePolicy,
 }
 
 impl ScheduledJob {
     /// Create a new scheduled job
+    ///
+    /// Fails if `schedule` is a malformed cron expression.
     pub fn new<T>(
         name: String,
         task: &T,
@@ -157,12 +480,12 @@ impl ScheduledJob {
         schedule: ScheduleExpression,
     ) -> TaskResult<Self>
     where
-        T: Task + Serialize,
+        T: SerializableTask,
     {
+        schedule.validate()?;
         let now = Utc::now();
-        let next_run = schedule.next_execution(now);
         
-        Ok(Self {
+        let mut job = Self {
             id: ScheduledJobId::new_v4(),
             name,
             task_type: task.name().to_string(),
@@ -172,25 +495,135 @@ impl ScheduledJob {
             schedule,
             enabled: true,
             max_retries: 3,
-            next_run,
+            next_run: None,
             last_run: None,
             run_count: 0,
+            max_runs: None,
+            valid_from: None,
+            valid_until: None,
             failure_count: 0,
             created_at: now,
             updated_at: now,
-        })
+            history: Vec::new(),
+            misfire_policy: MisfirePolicy::default(),
+        };
+        job.update_next_run();
+        Ok(job)
+    }
+
+    /// Set what happens to fires that were missed
+    pub fn with_misfire_policy(mut self, misfire_policy: MisfirePolicy) -> Self {
+        self.misfire_policy = misfire_policy;
+        self.update_next_run();
+        self
+    }
+
+    /// Limit the job to `max_runs` executions
+    pub fn with_max_runs(mut self, max_runs: u64) -> Self {
+        self.max_runs = Some(max_runs);
+        self
+    }
+
+    /// Only fire the job from `valid_from` on
+    pub fn with_valid_from(mut self, valid_from: DateTime<Utc>) -> Self {
+        self.valid_from = Some(valid_from);
+        self.update_next_run();
+        self
+    }
+
+    /// Stop firing the job after `valid_until`
+    pub fn with_valid_until(mut self, valid_until: DateTime<Utc>) -> Self {
+        self.valid_until = Some(valid_until);
+        self.update_next_run();
+        self
+    }
+
+    /// Whether the job has used up its `max_runs`
+    pub fn runs_exhausted(&self) -> bool {
+        self.max_runs.is_some_and(|max_runs| self.run_count >= max_runs)
+    }
+
+    /// Whether `at` lies within the job's `valid_from`/`valid_until` window
+    pub fn in_active_window(&self, at: DateTime<Utc>) -> bool {
+        self.valid_from.is_none_or(|valid_from| at >= valid_from)
+            && self.valid_until.is_none_or(|valid_until| at <= valid_until)
+    }
+
+    /// Whether `valid_until` has passed at `at`, so the job can never fire again
+    pub fn window_closed(&self, at: DateTime<Utc>) -> bool {
+        self.valid_until.is_some_and(|valid_until| at > valid_until)
+    }
+
+    /// Next fire time after `from`, skipping fire times outside the active window
+    pub fn next_execution(&self, from: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let mut next = self.schedule.next_execution(from)?;
+
+        if let Some(valid_from) = self.valid_from {
+            if next < valid_from {
+                // Fire times are strictly after their starting point, so this
+                // allows a fire exactly at `valid_from`
+                next = self.schedule.next_execution(valid_from - Duration::seconds(1))?;
+            }
+        }
+
+        match self.valid_until {
+            Some(valid_until) if next > valid_until => None,
+            _ => Some(next),
+        }
     }
 
     /// Update the next run time based on the schedule
     pub fn update_next_run(&mut self) {
         let now = Utc::now();
-        self.next_run = self.schedule.next_execution(now);
+        self.next_run = self.next_execution(now).or_else(|| self.missed_once(now));
+        self.updated_at = now;
+    }
+
+    /// The passed time of a one-time job that has not run, if it is to be
+    /// caught up
+    fn missed_once(&self, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        match self.schedule {
+            ScheduleExpression::Once(time)
+                if time <= now
+                    && self.run_count == 0
+                    && self.misfire_policy == MisfirePolicy::CatchUp
+                    && self.in_active_window(time) =>
+            {
+                Some(time)
+            }
+            _ => None,
+        }
+    }
+
+    /// Whether the upcoming fire is overdue by more than the grace period
+    pub fn is_misfired(&self, now: DateTime<Utc>) -> bool {
+        self.next_run
+            .is_some_and(|next_run| now - next_run > Duration::seconds(MISFIRE_GRACE_SECONDS))
+    }
+
+    /// Drop a missed fire, and any others missed since
+    ///
+    /// `next_run` moves to the first occurrence after now. A job without one,
+    /// such as a one-time job, is disabled.
+    pub fn skip_misfire(&mut self) {
+        let now = Utc::now();
+        self.record_run(JobRunOutcome::Missed, now);
+        self.next_run = self.next_execution(now);
+        if self.next_run.is_none() {
+            self.enabled = false;
+        }
         self.updated_at = now;
     }
 
     /// Mark job as executed
     pub fn mark_executed(&mut self, success: bool) {
         let now = Utc::now();
+        let outcome = if success {
+            JobRunOutcome::Submitted
+        } else {
+            JobRunOutcome::Failed
+        };
+        self.record_run(outcome, now);
         self.last_run = Some(now);
         self.run_count += 1;
         self.updated_at = now;
@@ -199,23 +632,69 @@ impl ScheduledJob {
             self.failure_count += 1;
         }
         
-        // Update next run time if it's a recurring job
-        if self.schedule.is_recurring() {
-            self.next_run = self.schedule.next_execution(now);
+        // Update next run time if it's a recurring job with runs left
+        if self.runs_exhausted() {
+            self.next_run = None;
+            self.enabled = false;
+        } else if self.schedule.is_recurring() {
+            self.next_run = self.next_execution(now);
+            // No fire time left before `valid_until`
+            if self.next_run.is_none() && self.valid_until.is_some() {
+                self.enabled = false;
+            }
         } else {
             self.next_run = None;
             self.enabled = false; // Disable one-time jobs after execution
         }
     }
 
+    /// Skip the upcoming fire without executing it
+    ///
+    /// The job stays enabled and `next_run` moves to the following occurrence.
+    /// One-time jobs have no following occurrence and are left without a next run.
+    pub fn skip_next(&mut self) {
+        let now = Utc::now();
+        self.record_run(JobRunOutcome::Skipped, now);
+        self.next_run = self
+            .next_run
+            .and_then(|upcoming| self.next_execution(upcoming));
+        self.updated_at = now;
+    }
+
+    /// Append to the run history, dropping the oldest entries beyond the cap
+    fn record_run(&mut self, outcome: JobRunOutcome, now: DateTime<Utc>) {
+        self.history.push(JobRunRecord {
+            scheduled_for: self.next_run,
+            recorded_at: now,
+            outcome,
+        });
+        if self.history.len() > MAX_JOB_HISTORY {
+            let excess = self.history.len() - MAX_JOB_HISTORY;
+            self.history.drain(..excess);
+        }
+    }
+
+    /// Deterministic task id for the fire of this job scheduled at `fire_time`
+    ///
+    /// Resubmitting the same fire always produces the same id, which lets the
+    /// queue detect and ignore a duplicate submission.
+    pub fn fire_task_id(&self, fire_time: DateTime<Utc>) -> TaskId {
+        TaskId::new_v5(&self.id, fire_time.to_rfc3339().as_bytes())
+    }
+
     /// Check if the job is ready to run
     pub fn is_ready(&self) -> bool {
         if !self.enabled {
             return false;
         }
+
+        let now = Utc::now();
+        if !self.in_active_window(now) {
+            return false;
+        }
         
         match self.next_run {
-            Some(next_run) => Utc::now() >= next_run,
+            Some(next_run) => now >= next_run,
             None => false,
         }
     }
@@ -226,6 +705,41 @@ pub struct TaskScheduler {
     client: Arc<TaskClient>,
     jobs: Arc<RwLock<HashMap<ScheduledJobId, ScheduledJob>>>,
     shutdown_signal: Arc<RwLock<bool>>,
+    /// Jobs being fired right now, so overlapping ticks skip them
+    ///
+    /// Ticks overlap when `start` runs more than once on a shared scheduler.
+    /// Separate schedulers are kept apart by the fire's deterministic task
+    /// id instead (see `execute_job`).
+    in_flight: Arc<StdMutex<HashSet<ScheduledJobId>>>,
+}
+
+/// Marks a job as being fired until dropped
+struct InFlightJob {
+    in_flight: Arc<StdMutex<HashSet<ScheduledJobId>>>,
+    job_id: ScheduledJobId,
+}
+
+impl InFlightJob {
+    /// Mark `job_id` as being fired, or return `None` if it already is
+    fn claim(in_flight: &Arc<StdMutex<HashSet<ScheduledJobId>>>, job_id: ScheduledJobId) -> Option<Self> {
+        let claimed = in_flight
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .insert(job_id);
+        claimed.then(|| Self {
+            in_flight: in_flight.clone(),
+            job_id,
+        })
+    }
+}
+
+impl Drop for InFlightJob {
+    fn drop(&mut self) {
+        self.in_flight
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .remove(&self.job_id);
+    }
 }
 
 impl TaskScheduler {
@@ -235,6 +749,7 @@ impl TaskScheduler {
             client,
             jobs: Arc::new(RwLock::new(HashMap::new())),
             shutdown_signal: Arc::new(RwLock::new(false)),
+            in_flight: Arc::new(StdMutex::new(HashSet::new())),
         }
     }
 
@@ -244,6 +759,8 @@ impl TaskScheduler {
         
         info!("Adding scheduled job: {} ({})", job.name, job_id);
         debug!("Job schedule: {:?}", job.schedule);
+
+        self.persist_job(&job).await?;
         
         let mut jobs = self.jobs.write().await;
         jobs.insert(job_id, job);
@@ -257,6 +774,10 @@ impl TaskScheduler {
         let removed = jobs.remove(&job_id).is_some();
         
         if removed {
+            self.client
+                .queue()
+                .remove_scheduled_job(&job_id.to_string())
+                .await?;
             info!("Removed scheduled job: {}", job_id);
         }
         
@@ -270,6 +791,7 @@ impl TaskScheduler {
         if let Some(job) = jobs.get_mut(&job_id) {
             job.enabled = enabled;
             job.updated_at = Utc::now();
+            self.persist_job(job).await?;
             
             info!("Job {} {}", job_id, if enabled { "enabled" } else { "disabled" });
         } else {
@@ -279,6 +801,58 @@ impl TaskScheduler {
         Ok(())
     }
 
+    /// Skip a job's upcoming fire while keeping it enabled
+    ///
+    /// Returns the job's new next run time.
+    pub async fn skip_next(&self, job_id: ScheduledJobId) -> TaskResult<Option<DateTime<Utc>>> {
+        let mut jobs = self.jobs.write().await;
+
+        let job = jobs
+            .get_mut(&job_id)
+            .ok_or_else(|| TaskError::scheduler(format!("Job not found: {}", job_id)))?;
+
+        let skipped = job.next_run;
+        job.skip_next();
+        self.persist_job(job).await?;
+
+        info!("Skipped run of job {} at {:?}, next run {:?}", job_id, skipped, job.next_run);
+        Ok(job.next_run)
+    }
+
+    /// Load jobs persisted in Redis by a previous scheduler process
+    ///
+    /// Jobs already known to this scheduler are left as they are. Call this
+    /// on startup instead of re-adding jobs, so each fire keeps its identity
+    /// across restarts. Returns the number of jobs restored.
+    pub async fn restore_jobs(&self) -> TaskResult<usize> {
+        let stored = self.client.queue().load_scheduled_jobs().await?;
+        let mut jobs = self.jobs.write().await;
+        let mut restored = 0;
+
+        for job_json in stored {
+            let job: ScheduledJob = serde_json::from_str(&job_json)?;
+            if let Entry::Vacant(entry) = jobs.entry(job.id) {
+                debug!("Restored scheduled job: {} ({})", job.name, job.id);
+                entry.insert(job);
+                restored += 1;
+            }
+        }
+
+        if restored > 0 {
+            info!("Restored {} scheduled jobs", restored);
+        }
+
+        Ok(restored)
+    }
+
+    /// Persist a job's current state
+    async fn persist_job(&self, job: &ScheduledJob) -> TaskResult<()> {
+        self.client
+            .queue()
+            .store_scheduled_job(&job.id.to_string(), &serde_json::to_string(job)?)
+            .await
+    }
+
     /// Get a job by ID
     pub async fn get_job(&self, job_id: ScheduledJobId) -> Option<ScheduledJob> {
         let jobs = self.jobs.read().await;
@@ -325,77 +899,145 @@ impl TaskScheduler {
     }
 
     /// Process jobs that are ready to run
+    ///
+    /// A job stays claimed until its fire has been submitted and its next run
+    /// recorded, so a tick that overlaps a slow submission skips it rather
+    /// than firing it twice.
     async fn process_ready_jobs(&self) -> TaskResult<()> {
+        self.disable_closed_jobs().await;
+
         let ready_jobs = {
             let jobs = self.jobs.read().await;
             jobs.values()
                 .filter(|job| job.is_ready())
-                .cloned()
+                .filter_map(|job| Some((job.clone(), InFlightJob::claim(&self.in_flight, job.id)?)))
                 .collect::<Vec<_>>()
         };
         
-                 for mut job in ready_jobs {
-             debug!("Executing scheduled job: {} ({})", job.name, job.id);
-             
-             // Submit the task
-             let result = self.execute_job(&job).await;
-             let job_name = job.name.clone();
-             let job_id = job.id;
-             
-             // Update job status
-             job.mark_executed(result.is_ok());
-             
-             // Update the job in the collection
-             {
-                 let mut jobs = self.jobs.write().await;
-                 if job.enabled || job.schedule.is_recurring() {
-                     jobs.insert(job_id, job);
-                 } else {
-                     jobs.remove(&job_id);
-                 }
-             }
-             
-             match result {
-                 Ok(task_id) => {
-                     info!("Scheduled job {} submitted successfully (task: {})", job_name, task_id);
-                 }
-                 Err(e) => {
-                     error!("Failed to execute scheduled job {}: {}", job_name, e);
-                 }
-             }
-         }
+        for (mut job, _in_flight) in ready_jobs {
+            let job_name = job.name.clone();
+            let job_id = job.id;
+
+            let result = if job.misfire_policy == MisfirePolicy::Skip && job.is_misfired(Utc::now()) {
+                let missed = job.next_run;
+                job.skip_misfire();
+                warn!("Scheduled job {} missed its run at {:?}, next run {:?}", job_name, missed, job.next_run);
+                if let Err(e) = self.persist_job(&job).await {
+                    error!("Failed to persist scheduled job {}: {}", job_name, e);
+                }
+                None
+            } else {
+                debug!("Executing scheduled job: {} ({})", job.name, job.id);
+
+                // Submit the task and persist the advanced job in one step
+                let result = self.execute_job(&mut job).await;
+
+                // A failed submission still advances the schedule
+                if result.is_err() {
+                    job.mark_executed(false);
+                    if let Err(e) = self.persist_job(&job).await {
+                        error!("Failed to persist scheduled job {}: {}", job_name, e);
+                    }
+                }
+                Some(result)
+            };
+
+            // Update the job in the collection
+            {
+                let mut jobs = self.jobs.write().await;
+                if job.enabled || job.schedule.is_recurring() {
+                    jobs.insert(job_id, job);
+                } else {
+                    jobs.remove(&job_id);
+                    if let Err(e) = self.client.queue().remove_scheduled_job(&job_id.to_string()).await {
+                        error!("Failed to remove finished scheduled job {}: {}", job_name, e);
+                    }
+                }
+            }
+
+            match result {
+                Some(Ok(task_id)) => {
+                    info!("Scheduled job {} submitted successfully (task: {})", job_name, task_id);
+                }
+                Some(Err(e)) => {
+                    error!("Failed to execute scheduled job {}: {}", job_name, e);
+                }
+                None => {}
+            }
+        }
         
         Ok(())
     }
 
+    /// Disable enabled jobs whose `valid_until` has passed
+    ///
+    /// Such a job is never ready again, so without this it would stay enabled
+    /// if its last fire in the window was missed, e.g. while no scheduler ran.
+    async fn disable_closed_jobs(&self) {
+        let now = Utc::now();
+        let closed: Vec<ScheduledJob> = {
+            let mut jobs = self.jobs.write().await;
+            jobs.values_mut()
+                .filter(|job| job.enabled && job.window_closed(now))
+                .map(|job| {
+                    job.enabled = false;
+                    job.next_run = None;
+                    job.updated_at = now;
+                    job.clone()
+                })
+                .collect()
+        };
+
+        for job in closed {
+            info!("Scheduled job {} is past its valid_until, disabling it", job.name);
+            if let Err(e) = self.persist_job(&job).await {
+                error!("Failed to persist scheduled job {}: {}", job.name, e);
+            }
+        }
+    }
+
     /// Execute a single job
-    async fn execute_job(&self, job: &ScheduledJob) -> TaskResult<TaskId> {
+    ///
+    /// The task id is derived from the job id and its intended fire time, and
+    /// the job's advanced state is persisted atomically with the submission,
+    /// so a fire is never lost or duplicated across a crash. On success `job`
+    /// is updated to its post-execution state.
+    async fn execute_job(&self, job: &mut ScheduledJob) -> TaskResult<TaskId> {
         // Parse the task data and submit it
         // Note: In a real implementation, you'd want a registry of task types
         // For now, we'll submit the raw task data
+        let fire_time = job.next_run.unwrap_or_else(Utc::now);
         
         // Create a dummy task for submission
-        let task_def = crate::task::TaskDefinition {
-            id: crate::task::TaskId::new_v4(),
-            name: job.task_type.clone(),
-            data: job.task_data.clone(),
-            priority: job.priority.clone(),
-            status: crate::task::TaskStatus::Pending,
-            retry_config: crate::task::RetryConfig::default(),
-            retry_count: 0,
-            created_at: Utc::now(),
-            updated_at: Utc::now(),
-            scheduled_at: None,
-            started_at: None,
-            finished_at: None,
-            result: None,
-            error: None,
-            queue: job.queue.clone(),
-            worker_id: None,
-            estimated_duration: None,
+        let mut task_def = crate::task::TaskDefinition {
+            id: job.fire_task_id(fire_time),
+            ..crate::task::TaskDefinition::from_raw(job.task_type.clone(), job.task_data.clone(), job.queue.clone())
         };
-        
-        self.client.queue().submit_task(task_def).await
+        // As with `TaskDefinition::new`, a non-default priority counts as the
+        // task's own, so the queue's default priority doesn't replace it
+        if job.priority != TaskPriority::default() {
+            task_def.set_priority(job.priority.clone());
+        }
+        let task_id = task_def.id;
+
+        let mut executed = job.clone();
+        executed.mark_executed(true);
+
+        let submitted = self
+            .client
+            .queue()
+            .fire_scheduled_job(task_def, &job.id.to_string(), &serde_json::to_string(&executed)?)
+            .await?;
+
+        if !submitted {
+            warn!(
+                "Scheduled job {} already fired for {}, skipping duplicate submission",
+                job.name, fire_time
+            );
+        }
+
+        *job = executed;
+        Ok(task_id)
     }
 
     /// Signal the scheduler to shutdown
@@ -415,16 +1057,21 @@ impl TaskScheduler {
         
         let mut ready_jobs = 0;
         let mut recurring_jobs = 0;
+        let mut bounded_jobs = 0;
         let mut total_executions = 0;
         let mut total_failures = 0;
         
         for job in jobs.values() {
+            // Jobs outside their active window are never ready
             if job.is_ready() {
                 ready_jobs += 1;
             }
             if job.schedule.is_recurring() {
                 recurring_jobs += 1;
             }
+            if job.max_runs.is_some() {
+                bounded_jobs += 1;
+            }
             total_executions += job.run_count;
             total_failures += job.failure_count;
         }
@@ -435,6 +1082,7 @@ impl TaskScheduler {
             disabled_jobs,
             ready_jobs,
             recurring_jobs,
+            bounded_jobs,
             total_executions,
             total_failures,
         }
@@ -449,6 +1097,7 @@ pub struct SchedulerStats {
     pub disabled_jobs: usize,
     pub ready_jobs: usize,
     pub recurring_jobs: usize,
+    pub bounded_jobs: usize,
     pub total_executions: u64,
     pub total_failures: u64,
 }
@@ -464,7 +1113,7 @@ impl TaskScheduler {
         at: DateTime<Utc>,
     ) -> TaskResult<ScheduledJobId>
     where
-        T: Task + Serialize,
+        T: SerializableTask,
     {
         let job = ScheduledJob::new(name, task, queue, ScheduleExpression::Once(at))?;
         self.add_job(job).await
@@ -479,7 +1128,7 @@ impl TaskScheduler {
         delay_seconds: u64,
     ) -> TaskResult<ScheduledJobId>
     where
-        T: Task + Serialize,
+        T: SerializableTask,
     {
         let job = ScheduledJob::new(name, task, queue, ScheduleExpression::Delay(delay_seconds))?;
         self.add_job(job).await
@@ -494,7 +1143,7 @@ impl TaskScheduler {
         seconds: u64,
     ) -> TaskResult<ScheduledJobId>
     where
-        T: Task + Serialize,
+        T: SerializableTask,
     {
         let job = ScheduledJob::new(name, task, queue, ScheduleExpression::EverySeconds(seconds))?;
         self.add_job(job).await
@@ -509,7 +1158,7 @@ impl TaskScheduler {
         minutes: u64,
     ) -> TaskResult<ScheduledJobId>
     where
-        T: Task + Serialize,
+        T: SerializableTask,
     {
         let job = ScheduledJob::new(name, task, queue, ScheduleExpression::EveryMinutes(minutes))?;
         self.add_job(job).await
@@ -525,7 +1174,7 @@ impl TaskScheduler {
         minute: u32,
     ) -> TaskResult<ScheduledJobId>
     where
-        T: Task + Serialize,
+        T: SerializableTask,
     {
         let job = ScheduledJob::new(
             name,
@@ -547,7 +1196,7 @@ impl TaskScheduler {
         minute: u32,
     ) -> TaskResult<ScheduledJobId>
     where
-        T: Task + Serialize,
+        T: SerializableTask,
     {
         let job = ScheduledJob::new(
             name,
@@ -557,4 +1206,320 @@ impl TaskScheduler {
         )?;
         self.add_job(job).await
     }
-} 
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::TaskError;
+
+    #[derive(Debug, Serialize, Deserialize)]
+    struct Noop;
+
+    #[async_trait::async_trait]
+    impl crate::task::Task for Noop {
+        type Output = ();
+        type Error = TaskError;
+
+        async fn execute(&self) -> Result<Self::Output, Self::Error> {
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn job_past_valid_until_is_disabled_after_downtime() {
+        let queue = Arc::new(crate::queue::TaskQueue::new_in_memory(Default::default()).unwrap());
+        let scheduler = TaskScheduler::new(Arc::new(TaskClient::from_queue(queue)));
+        let mut job = ScheduledJob::new(
+            "window".to_string(),
+            &Noop,
+            "default".to_string(),
+            ScheduleExpression::EveryMinutes(1),
+        )
+        .unwrap();
+        // The scheduler was down from before the last fire until after the window
+        let now = Utc::now();
+        job.next_run = Some(now - Duration::minutes(10));
+        job.valid_until = Some(now - Duration::minutes(5));
+        let job_id = scheduler.add_job(job).await.unwrap();
+
+        scheduler.process_ready_jobs().await.unwrap();
+
+        let job = scheduler.get_job(job_id).await.unwrap();
+        assert!(!job.enabled);
+        assert_eq!(job.next_run, None);
+        assert_eq!(job.run_count, 0);
+        assert_eq!(scheduler.list_jobs_by_status(true).await.len(), 0);
+    }
+
+    /// Run one tick for a daily job whose last fire was missed a day ago,
+    /// returning the job and how many tasks it queued
+    async fn run_missed_daily_job(misfire_policy: MisfirePolicy) -> (ScheduledJob, u64) {
+        let queue = Arc::new(crate::queue::TaskQueue::new_in_memory(Default::default()).unwrap());
+        let scheduler = TaskScheduler::new(Arc::new(TaskClient::from_queue(queue.clone())));
+        let mut job = ScheduledJob::new(
+            "daily".to_string(),
+            &Noop,
+            "default".to_string(),
+            ScheduleExpression::Daily { hour: 3, minute: 0 },
+        )
+        .unwrap()
+        .with_misfire_policy(misfire_policy);
+        job.next_run = Some(Utc::now() - Duration::days(1));
+        let job_id = scheduler.add_job(job).await.unwrap();
+
+        scheduler.process_ready_jobs().await.unwrap();
+
+        let queued = queue.get_stats("default").await.unwrap().pending_tasks;
+        (scheduler.get_job(job_id).await.unwrap(), queued)
+    }
+
+    #[tokio::test]
+    async fn catch_up_fires_a_missed_daily_job_once() {
+        let (job, queued) = run_missed_daily_job(MisfirePolicy::CatchUp).await;
+
+        assert_eq!(queued, 1);
+        assert_eq!(job.run_count, 1);
+        assert_eq!(job.history.last().map(|record| record.outcome), Some(JobRunOutcome::Submitted));
+        assert!(job.next_run.is_some_and(|next_run| next_run > Utc::now()));
+    }
+
+    #[tokio::test]
+    async fn skip_drops_a_missed_daily_job_until_its_next_time() {
+        let (job, queued) = run_missed_daily_job(MisfirePolicy::Skip).await;
+
+        assert_eq!(queued, 0);
+        assert_eq!(job.run_count, 0);
+        assert!(job.enabled);
+        assert_eq!(job.history.last().map(|record| record.outcome), Some(JobRunOutcome::Missed));
+        let next_run = job.next_run.unwrap();
+        assert!(next_run > Utc::now());
+        assert_eq!((next_run.hour(), next_run.minute()), (3, 0));
+    }
+
+    #[tokio::test]
+    async fn fires_take_their_queue_defaults() {
+        let mut config = crate::queue::TaskQueueConfig::default();
+        config.queue_defaults.insert(
+            "defaults".to_string(),
+            crate::queue::QueueDefaults {
+                priority: Some(TaskPriority::High),
+                retry_config: Some(crate::task::RetryConfig {
+                    max_retries: 7,
+                    ..Default::default()
+                }),
+            },
+        );
+        let queue = Arc::new(crate::queue::TaskQueue::new_in_memory(config).unwrap());
+        let scheduler = TaskScheduler::new(Arc::new(TaskClient::from_queue(queue.clone())));
+        let job = |name: &str| {
+            let mut job = ScheduledJob::new(
+                name.to_string(),
+                &Noop,
+                "defaults".to_string(),
+                ScheduleExpression::EveryMinutes(1),
+            )
+            .unwrap();
+            job.next_run = Some(Utc::now() - Duration::seconds(1));
+            job
+        };
+        let unset = job("unset");
+        let mut critical = job("critical");
+        critical.priority = TaskPriority::Critical;
+        let unset_id = unset.fire_task_id(unset.next_run.unwrap());
+        let critical_id = critical.fire_task_id(critical.next_run.unwrap());
+        scheduler.add_job(unset).await.unwrap();
+        scheduler.add_job(critical).await.unwrap();
+
+        scheduler.process_ready_jobs().await.unwrap();
+
+        let unset = queue.get_task(unset_id).await.unwrap().unwrap();
+        assert_eq!(unset.priority, TaskPriority::High);
+        assert_eq!(unset.retry_config.max_retries, 7);
+        let critical = queue.get_task(critical_id).await.unwrap().unwrap();
+        assert_eq!(critical.priority, TaskPriority::Critical);
+        assert_eq!(critical.retry_config.max_retries, 7);
+    }
+
+    #[tokio::test]
+    async fn oversized_fire_is_rejected_and_recorded_as_failed() {
+        let config = crate::queue::TaskQueueConfig {
+            max_payload_bytes: Some(2),
+            ..Default::default()
+        };
+        let queue = Arc::new(crate::queue::TaskQueue::new_in_memory(config).unwrap());
+        let scheduler = TaskScheduler::new(Arc::new(TaskClient::from_queue(queue.clone())));
+        let mut job = ScheduledJob::new(
+            "oversized".to_string(),
+            &Noop,
+            "default".to_string(),
+            ScheduleExpression::EveryMinutes(1),
+        )
+        .unwrap();
+        job.next_run = Some(Utc::now() - Duration::seconds(1));
+        let job_id = scheduler.add_job(job).await.unwrap();
+
+        scheduler.process_ready_jobs().await.unwrap();
+
+        assert!(queue.get_next_task("default").await.unwrap().is_none());
+        let job = scheduler.get_job(job_id).await.unwrap();
+        assert_eq!(job.failure_count, 1);
+        assert_eq!(job.history.last().map(|record| record.outcome), Some(JobRunOutcome::Failed));
+    }
+
+    #[test]
+    fn cron_step_hours_fire_on_even_hours() {
+        let cron = CronSchedule::parse("0 */2 * * *").unwrap();
+        let at = |hour, minute| Utc.with_ymd_and_hms(2024, 3, 5, hour, minute, 0).unwrap();
+
+        assert_eq!(cron.next_after(at(3, 15)), Some(at(4, 0)));
+        assert_eq!(cron.next_after(at(4, 0)), Some(at(6, 0)));
+        assert_eq!(
+            cron.next_after(at(23, 30)),
+            Some(Utc.with_ymd_and_hms(2024, 3, 6, 0, 0, 0).unwrap())
+        );
+    }
+
+    #[test]
+    fn cron_job_reschedules_after_execution() {
+        let schedule = ScheduleExpression::Cron("0 */2 * * *".to_string());
+        let mut job = ScheduledJob::new("even-hours".to_string(), &Noop, "default".to_string(), schedule).unwrap();
+        let first = job.next_run.unwrap();
+        assert_eq!((first.hour() % 2, first.minute(), first.second()), (0, 0, 0));
+
+        job.mark_executed(true);
+
+        assert!(job.enabled);
+        assert_eq!(job.run_count, 1);
+        let next = job.next_run.unwrap();
+        assert!(next > job.last_run.unwrap());
+        assert_eq!((next.hour() % 2, next.minute(), next.second()), (0, 0, 0));
+    }
+
+    /// Counts `Queued` events, taking its time over each one
+    #[derive(Clone, Default)]
+    struct SlowListener {
+        queued: Arc<std::sync::atomic::AtomicUsize>,
+        entered: Arc<tokio::sync::Notify>,
+    }
+
+    #[async_trait::async_trait]
+    impl crate::events::EventListener for SlowListener {
+        async fn on_event(&self, event: crate::events::TaskEvent) {
+            if let crate::events::TaskEvent::Queued { .. } = event {
+                self.queued.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+                self.entered.notify_waiters();
+                tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn overlapping_ticks_submit_each_fire_once() {
+        let queue = Arc::new(crate::queue::TaskQueue::new_in_memory(Default::default()).unwrap());
+        let listener = SlowListener::default();
+        queue.add_event_listener(listener.clone()).await;
+        let client = Arc::new(TaskClient::from_queue(queue));
+        let scheduler = TaskScheduler::new(client.clone());
+        let other_scheduler = TaskScheduler::new(client);
+        let mut job = ScheduledJob::new(
+            "overlap".to_string(),
+            &Noop,
+            "default".to_string(),
+            ScheduleExpression::EveryMinutes(1),
+        )
+        .unwrap();
+        let fire_time = Utc::now() - Duration::seconds(1);
+        job.next_run = Some(fire_time);
+        let job_id = scheduler.add_job(job.clone()).await.unwrap();
+        other_scheduler.add_job(job).await.unwrap();
+
+        // Both later ticks start while the first is still submitting
+        let entered = listener.entered.notified();
+        tokio::pin!(entered);
+        entered.as_mut().enable();
+        let overlapping = async {
+            entered.await;
+            tokio::join!(scheduler.process_ready_jobs(), other_scheduler.process_ready_jobs())
+        };
+        let (first, (second, third)) = tokio::join!(scheduler.process_ready_jobs(), overlapping);
+        for result in [first, second, third] {
+            result.unwrap();
+        }
+        scheduler.process_ready_jobs().await.unwrap();
+
+        assert_eq!(listener.queued.load(std::sync::atomic::Ordering::SeqCst), 1);
+        let job = scheduler.get_job(job_id).await.unwrap();
+        assert_eq!(job.run_count, 1);
+        assert!(job.next_run.unwrap() > fire_time);
+    }
+
+    #[tokio::test]
+    async fn fire_is_not_repeated_after_a_crash_before_persisting() {
+        let queue = Arc::new(crate::queue::TaskQueue::new_in_memory(Default::default()).unwrap());
+        let client = Arc::new(TaskClient::from_queue(queue.clone()));
+        let scheduler = TaskScheduler::new(client.clone());
+        let mut job = ScheduledJob::new(
+            "crash".to_string(),
+            &Noop,
+            "default".to_string(),
+            ScheduleExpression::EveryMinutes(1),
+        )
+        .unwrap();
+        job.next_run = Some(Utc::now() - Duration::seconds(1));
+        scheduler.add_job(job.clone()).await.unwrap();
+        scheduler.process_ready_jobs().await.unwrap();
+
+        // A restarted scheduler that only saw the job as it was before the
+        // fire, as if the process died before persisting the advanced state
+        let restarted = TaskScheduler::new(client.clone());
+        restarted.add_job(job.clone()).await.unwrap();
+        restarted.process_ready_jobs().await.unwrap();
+        assert_eq!(queue.get_stats("default").await.unwrap().pending_tasks, 1);
+        let fired = queue.get_next_task("default").await.unwrap().unwrap();
+        assert_eq!(fired.id, job.fire_task_id(job.next_run.unwrap()));
+
+        // The persisted state has moved on, so restoring it doesn't fire again
+        let restored = TaskScheduler::new(client);
+        assert_eq!(restored.restore_jobs().await.unwrap(), 1);
+        restored.process_ready_jobs().await.unwrap();
+        assert!(queue.get_next_task("default").await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn skipped_fire_is_recorded_and_the_next_one_still_runs() {
+        let queue = Arc::new(crate::queue::TaskQueue::new_in_memory(Default::default()).unwrap());
+        let scheduler = TaskScheduler::new(Arc::new(TaskClient::from_queue(queue.clone())));
+        let mut job = ScheduledJob::new(
+            "skip".to_string(),
+            &Noop,
+            "default".to_string(),
+            ScheduleExpression::EveryMinutes(1),
+        )
+        .unwrap();
+        // Two fires are due: the skipped one and the one after it
+        let skipped = Utc::now() - Duration::seconds(90);
+        let following = skipped + Duration::minutes(1);
+        job.next_run = Some(skipped);
+        let job_id = scheduler.add_job(job.clone()).await.unwrap();
+
+        assert_eq!(scheduler.skip_next(job_id).await.unwrap(), Some(following));
+        scheduler.process_ready_jobs().await.unwrap();
+
+        let fired = queue.get_next_task("default").await.unwrap().unwrap();
+        assert_eq!(fired.id, job.fire_task_id(following));
+        assert!(queue.get_next_task("default").await.unwrap().is_none());
+        let job = scheduler.get_job(job_id).await.unwrap();
+        let outcomes: Vec<_> = job
+            .history
+            .iter()
+            .map(|record| (record.scheduled_for, record.outcome))
+            .collect();
+        assert!(matches!(
+            outcomes[..],
+            [(Some(first), JobRunOutcome::Skipped), (Some(second), JobRunOutcome::Submitted)]
+                if first == skipped && second == following
+        ));
+        assert!(job.next_run.is_some_and(|next_run| next_run > Utc::now()));
+    }
+}
@@ -0,0 +1,177 @@
+//! Serialization formats for task payloads and results
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use crate::error::{TaskError, TaskResult};
+
+/// Converts task payloads and results to and from their stored form
+pub trait Serializer {
+    /// The format written by this serializer
+    fn format(&self) -> SerializationFormat;
+
+    /// Serialize a value to its stored string form
+    fn serialize<T: Serialize + ?Sized>(&self, value: &T) -> TaskResult<String>;
+
+    /// Deserialize a value from its stored string form
+    fn deserialize<T: DeserializeOwned>(&self, data: &str) -> TaskResult<T>;
+}
+
+/// Format of a task's serialized `data` and `result`
+///
+/// Recorded on each `TaskDefinition` so any worker can decode the task,
+/// whichever format its submitter chose.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum SerializationFormat {
+    /// JSON text
+    #[default]
+    Json,
+    /// Base64-encoded MessagePack (requires the `msgpack` feature)
+    MessagePack,
+    /// Base64-encoded raw bytes (requires the `binary` feature)
+    ///
+    /// Carries opaque payloads such as protobuf messages or images; see
+    /// `TaskDefinition::from_bytes` and `TaskHandler::handle_bytes`. Typed
+    /// values cannot be serialized in this format.
+    Binary,
+}
+
+impl SerializationFormat {
+    /// Serialize a value in this format
+    pub fn serialize<T: Serialize + ?Sized>(&self, value: &T) -> TaskResult<String> {
+        match self {
+            SerializationFormat::Json => JsonSerializer.serialize(value),
+            #[cfg(feature = "msgpack")]
+            SerializationFormat::MessagePack => MessagePackSerializer.serialize(value),
+            #[cfg(not(feature = "msgpack"))]
+            SerializationFormat::MessagePack => Err(msgpack_disabled()),
+            SerializationFormat::Binary => Err(binary_not_typed()),
+        }
+    }
+
+    /// Deserialize a value stored in this format
+    pub fn deserialize<T: DeserializeOwned>(&self, data: &str) -> TaskResult<T> {
+        match self {
+            SerializationFormat::Json => JsonSerializer.deserialize(data),
+            #[cfg(feature = "msgpack")]
+            SerializationFormat::MessagePack => MessagePackSerializer.deserialize(data),
+            #[cfg(not(feature = "msgpack"))]
+            SerializationFormat::MessagePack => Err(msgpack_disabled()),
+            SerializationFormat::Binary => Err(binary_not_typed()),
+        }
+    }
+
+    /// Encode raw bytes as stored in this format
+    ///
+    /// JSON payloads must be UTF-8; the other formats store base64.
+    pub fn encode_bytes(&self, bytes: &[u8]) -> TaskResult<String> {
+        match self {
+            SerializationFormat::Json => String::from_utf8(bytes.to_vec())
+                .map_err(|_| TaskError::config("JSON payloads must be UTF-8; use SerializationFormat::Binary")),
+            #[cfg(feature = "msgpack")]
+            SerializationFormat::MessagePack => Ok(base64_encode(bytes)),
+            #[cfg(not(feature = "msgpack"))]
+            SerializationFormat::MessagePack => Err(msgpack_disabled()),
+            #[cfg(feature = "binary")]
+            SerializationFormat::Binary => Ok(base64_encode(bytes)),
+            #[cfg(not(feature = "binary"))]
+            SerializationFormat::Binary => Err(binary_disabled()),
+        }
+    }
+
+    /// Decode data stored in this format to the raw bytes it carries
+    pub fn decode_bytes(&self, data: &str) -> TaskResult<Vec<u8>> {
+        match self {
+            SerializationFormat::Json => Ok(data.as_bytes().to_vec()),
+            #[cfg(feature = "msgpack")]
+            SerializationFormat::MessagePack => base64_decode(data),
+            #[cfg(not(feature = "msgpack"))]
+            SerializationFormat::MessagePack => Err(msgpack_disabled()),
+            #[cfg(feature = "binary")]
+            SerializationFormat::Binary => base64_decode(data),
+            #[cfg(not(feature = "binary"))]
+            SerializationFormat::Binary => Err(binary_disabled()),
+        }
+    }
+}
+
+#[cfg(not(feature = "msgpack"))]
+fn msgpack_disabled() -> TaskError {
+    TaskError::config("MessagePack serialization requires the `msgpack` feature")
+}
+
+#[cfg(not(feature = "binary"))]
+fn binary_disabled() -> TaskError {
+    TaskError::config("binary payloads require the `binary` feature")
+}
+
+fn binary_not_typed() -> TaskError {
+    TaskError::config("binary payloads are raw bytes; use TaskDefinition::data_bytes and result_bytes")
+}
+
+#[cfg(any(feature = "msgpack", feature = "binary"))]
+fn base64_encode(bytes: &[u8]) -> String {
+    use base64::Engine;
+
+    base64::engine::general_purpose::STANDARD.encode(bytes)
+}
+
+#[cfg(any(feature = "msgpack", feature = "binary"))]
+fn base64_decode(data: &str) -> TaskResult<Vec<u8>> {
+    use base64::Engine;
+
+    base64::engine::general_purpose::STANDARD
+        .decode(data)
+        .map_err(|e| TaskError::Internal(anyhow::anyhow!("payload is not base64: {}", e)))
+}
+
+/// JSON serializer (the default)
+#[derive(Debug, Clone, Copy, Default)]
+pub struct JsonSerializer;
+
+impl Serializer for JsonSerializer {
+    fn format(&self) -> SerializationFormat {
+        SerializationFormat::Json
+    }
+
+    fn serialize<T: Serialize + ?Sized>(&self, value: &T) -> TaskResult<String> {
+        Ok(serde_json::to_string(value)?)
+    }
+
+    fn deserialize<T: DeserializeOwned>(&self, data: &str) -> TaskResult<T> {
+        Ok(serde_json::from_str(data)?)
+    }
+}
+
+/// MessagePack serializer
+///
+/// Values are encoded with field names, then base64-encoded so they fit in
+/// the string fields of a `TaskDefinition`.
+#[cfg(feature = "msgpack")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MessagePackSerializer;
+
+#[cfg(feature = "msgpack")]
+impl Serializer for MessagePackSerializer {
+    fn format(&self) -> SerializationFormat {
+        SerializationFormat::MessagePack
+    }
+
+    fn serialize<T: Serialize + ?Sized>(&self, value: &T) -> TaskResult<String> {
+        use base64::Engine;
+
+        let bytes = rmp_serde::to_vec_named(value)
+            .map_err(|e| TaskError::Internal(anyhow::anyhow!("MessagePack encode failed: {}", e)))?;
+        Ok(base64::engine::general_purpose::STANDARD.encode(bytes))
+    }
+
+    fn deserialize<T: DeserializeOwned>(&self, data: &str) -> TaskResult<T> {
+        use base64::Engine;
+
+        let bytes = base64::engine::general_purpose::STANDARD
+            .decode(data)
+            .map_err(|e| TaskError::Internal(anyhow::anyhow!("MessagePack payload is not base64: {}", e)))?;
+        rmp_serde::from_slice(&bytes)
+            .map_err(|e| TaskError::Internal(anyhow::anyhow!("MessagePack decode failed: {}", e)))
+    }
+}
@@ -2,11 +2,16 @@
 
 use async_trait::async_trait;
 use chrono::{DateTime, Utc};
+use rand::Rng;
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::fmt::Debug;
 use uuid::Uuid;
 
 use crate::error::{TaskError, TaskResult};
+use crate::group::GroupId;
+use crate::serialization::SerializationFormat;
 
 /// Unique identifier for tasks
 pub type TaskId = Uuid;
@@ -30,23 +35,80 @@ pub enum TaskStatus {
     Retrying,
 }
 
+impl TaskStatus {
+    /// Every status, in declaration order
+    pub const ALL: [TaskStatus; 7] = [
+        TaskStatus::Pending,
+        TaskStatus::Running,
+        TaskStatus::Success,
+        TaskStatus::Failed,
+        TaskStatus::Cancelled,
+        TaskStatus::Scheduled,
+        TaskStatus::Retrying,
+    ];
+
+    /// Whether the task has finished and its status will not change again
+    pub fn is_terminal(&self) -> bool {
+        matches!(self, TaskStatus::Success | TaskStatus::Failed | TaskStatus::Cancelled)
+    }
+
+    /// Name of the status, as serialized
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TaskStatus::Pending => "Pending",
+            TaskStatus::Running => "Running",
+            TaskStatus::Success => "Success",
+            TaskStatus::Failed => "Failed",
+            TaskStatus::Cancelled => "Cancelled",
+            TaskStatus::Scheduled => "Scheduled",
+            TaskStatus::Retrying => "Retrying",
+        }
+    }
+}
+
 /// Task priority levels
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+///
+/// Tasks with a higher `value` are dequeued first. Priorities order by value;
+/// a `Custom` level equal in value to a named one sorts just after it.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq, Hash)]
 pub enum TaskPriority {
-    Low = 0,
-    Normal = 5,
-    High = 10,
-    Critical = 15,
+    Low,
+    #[default]
+    Normal,
+    High,
+    Critical,
+    /// Any level, used as the queue score directly
+    Custom(i32),
 }
 
-impl Default for TaskPriority {
-    fn default() -> Self {
-        TaskPriority::Normal
+impl TaskPriority {
+    /// Numeric level of the priority: 0, 5, 10 and 15 for the named levels
+    pub fn value(&self) -> i32 {
+        match self {
+            TaskPriority::Low => 0,
+            TaskPriority::Normal => 5,
+            TaskPriority::High => 10,
+            TaskPriority::Critical => 15,
+            TaskPriority::Custom(value) => *value,
+        }
+    }
+}
+
+impl Ord for TaskPriority {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        let custom = |priority: &Self| matches!(priority, TaskPriority::Custom(_));
+        (self.value(), custom(self)).cmp(&(other.value(), custom(other)))
+    }
+}
+
+impl PartialOrd for TaskPriority {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
     }
 }
 
 /// Configuration for task retry behavior
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct RetryConfig {
     /// Maximum number of retry attempts
     pub max_retries: u32,
@@ -56,6 +118,12 @@ pub struct RetryConfig {
     pub exponential_backoff: bool,
     /// Maximum delay between retries in seconds
     pub max_delay: u64,
+    /// Fraction of the delay to randomize by, in either direction (0.0 to 1.0)
+    ///
+    /// Spreads out retries of tasks that failed together. Defaults to 0.0
+    /// (no jitter) so existing retry timing is unchanged.
+    #[serde(default)]
+    pub jitter: f64,
 }
 
 impl Default for RetryConfig {
@@ -65,7 +133,51 @@ impl Default for RetryConfig {
             retry_delay: 5,
             exponential_backoff: true,
             max_delay: 300, // 5 minutes
+            jitter: 0.0,
+        }
+    }
+}
+
+impl RetryConfig {
+    /// Delay in seconds before the given retry attempt (starting at 1)
+    ///
+    /// Never exceeds `max_delay`; saturates instead of overflowing for
+    /// large delays or attempt counts.
+    ///
+    /// ```
+    /// use distributed_task_queue::task::RetryConfig;
+    ///
+    /// let config = RetryConfig {
+    ///     max_retries: 1000,
+    ///     retry_delay: u64::MAX / 2,
+    ///     exponential_backoff: true,
+    ///     max_delay: 3600,
+    ///     jitter: 0.0,
+    /// };
+    /// assert_eq!(config.delay_for_attempt(200), 3600);
+    /// ```
+    pub fn delay_for_attempt(&self, attempt: u32) -> u64 {
+        let base_delay = self.retry_delay.min(self.max_delay);
+        if !self.exponential_backoff {
+            return base_delay;
         }
+
+        let factor = 2_u64
+            .checked_pow(attempt.saturating_sub(1))
+            .unwrap_or(u64::MAX);
+        base_delay.saturating_mul(factor).min(self.max_delay)
+    }
+
+    /// Randomize a delay by up to `jitter` of its length, within `0..=max_delay`
+    pub fn apply_jitter(&self, delay: u64) -> u64 {
+        let jitter = if self.jitter.is_finite() { self.jitter.clamp(0.0, 1.0) } else { 0.0 };
+        if jitter == 0.0 || delay == 0 {
+            return delay;
+        }
+
+        let spread = delay as f64 * jitter;
+        let offset = rand::thread_rng().gen_range(-spread..=spread);
+        (delay as f64 + offset).round().clamp(0.0, self.max_delay as f64) as u64
     }
 }
 
@@ -80,6 +192,31 @@ pub trait Task: Send + Sync + Debug {
     /// Execute the task and return the result
     async fn execute(&self) -> Result<Self::Output, Self::Error>;
 
+    /// Check that a successful output satisfies the task's invariants
+    ///
+    /// Returning an error makes the task fail (and be retried) instead of
+    /// storing the output as its result.
+    fn validate_output(&self, _output: &Self::Output) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    /// Execute the task and validate its output
+    ///
+    /// Task handlers should call this rather than `execute` directly.
+    async fn execute_validated(&self) -> Result<Self::Output, Self::Error> {
+        let output = self.execute().await?;
+        self.validate_output(&output)?;
+        Ok(output)
+    }
+
+    /// Whether a failure with this error is worth retrying
+    ///
+    /// Handlers should report non-retryable errors as
+    /// `TaskError::permanent` so the worker fails the task immediately.
+    fn is_retryable(&self, _error: &Self::Error) -> bool {
+        true
+    }
+
     /// Get the task name (defaults to the type name)
     fn name(&self) -> &'static str {
         std::any::type_name::<Self>()
@@ -95,10 +232,69 @@ pub trait Task: Send + Sync + Debug {
         TaskPriority::Normal
     }
 
-    /// Estimate task execution time in seconds (for scheduling)
+    /// Estimate task execution time in seconds
+    ///
+    /// Workers use it to pick tasks (see `WorkerConfig::duration_aware`).
     fn estimated_duration(&self) -> Option<u64> {
         None
     }
+
+    /// Execution timeout in seconds, overriding the worker's `task_timeout`
+    fn timeout(&self) -> Option<u64> {
+        None
+    }
+
+    /// External resources this task needs exclusive use of while running
+    ///
+    /// The worker only claims the task once a slot on every listed resource
+    /// is available (see `TaskQueueConfig::resource_capacities`).
+    fn required_resources(&self) -> Vec<String> {
+        Vec::new()
+    }
+
+    /// Key identifying submissions that should only run once at a time
+    ///
+    /// While a task with the same key is pending or running, submitting
+    /// another returns the existing task's id instead of enqueuing a copy.
+    fn idempotency_key(&self) -> Option<String> {
+        None
+    }
+
+    /// Format used to store this task's payload and result
+    fn serialization_format(&self) -> SerializationFormat {
+        SerializationFormat::Json
+    }
+
+    /// Follow-up task to enqueue after this one succeeds
+    ///
+    /// `output` is this task's serialized result, so the follow-up can take
+    /// it as input. The worker records this task as the follow-up's parent.
+    fn on_success(&self, _output: &str) -> Option<TaskDefinition> {
+        None
+    }
+}
+
+/// A task that can be stored in and read back from the queue
+///
+/// Implemented for every `Task` that is also `Serialize` and
+/// `DeserializeOwned`, so it never needs implementing by hand; use it as the
+/// single bound for code that submits tasks.
+pub trait SerializableTask: Task + Serialize + DeserializeOwned {}
+
+impl<T> SerializableTask for T where T: Task + Serialize + DeserializeOwned {}
+
+/// Number of failed attempts kept in `TaskDefinition::attempts`
+pub const MAX_ATTEMPT_HISTORY: usize = 20;
+
+/// One failed execution of a task
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TaskAttempt {
+    /// 1 for the first execution, 2 for the first retry, and so on
+    pub attempt: u32,
+    /// When the attempt failed
+    pub failed_at: DateTime<Utc>,
+    /// Why it failed
+    pub error: String,
 }
 
 /// Complete task definition with metadata
@@ -138,22 +334,235 @@ pub struct TaskDefinition {
     pub worker_id: Option<String>,
     /// Estimated execution duration
     pub estimated_duration: Option<u64>,
+    /// External resources that must be acquired before execution
+    #[serde(default)]
+    pub required_resources: Vec<String>,
+    /// Execution timeout in seconds (falls back to the worker's default)
+    #[serde(default)]
+    pub timeout: Option<u64>,
+    /// Number of times the task was reclaimed from a stalled worker
+    #[serde(default)]
+    pub reclaim_count: u32,
+    /// Deduplication key (see `Task::idempotency_key`)
+    #[serde(default)]
+    pub idempotency_key: Option<String>,
+    /// Task whose success triggered this one (see `Task::on_success`)
+    #[serde(default)]
+    pub parent_id: Option<TaskId>,
+    /// Number of follow-up submissions and retries that led to this run
+    /// (see `TaskQueueConfig::max_chain_depth`)
+    #[serde(default)]
+    pub chain_depth: u32,
+    /// Format of `data` and `result`
+    #[serde(default)]
+    pub format: SerializationFormat,
+    /// URL the worker POSTs the outcome to when the task finishes
+    /// (requires the `webhooks` feature)
+    #[serde(default)]
+    pub callback_url: Option<String>,
+    /// Arbitrary application data, such as a tenant or correlation id
+    #[serde(default)]
+    pub metadata: HashMap<String, String>,
+    /// Group the task belongs to (see `TaskGroup`)
+    #[serde(default)]
+    pub group_id: Option<GroupId>,
+    /// Failed attempts, oldest first (at most `MAX_ATTEMPT_HISTORY`)
+    #[serde(default)]
+    pub attempts: Vec<TaskAttempt>,
+    /// Deadline to start by; a task still waiting after it is cancelled
+    /// instead of run
+    #[serde(default)]
+    pub expires_at: Option<DateTime<Utc>>,
+    /// Whether the output is stored when the task succeeds; without it no
+    /// one can read the result, which saves writes for fire-and-forget tasks
+    #[serde(default = "default_store_result")]
+    pub store_result: bool,
+    /// Seconds to keep the result, overriding `TaskQueueConfig::result_ttl`
+    /// (0 keeps it until evicted)
+    #[serde(default)]
+    pub result_ttl: Option<u64>,
+    /// Whether `result` is stored compressed (see `TaskQueueConfig::compress_results`)
+    #[serde(default)]
+    pub result_compressed: bool,
+    /// Whether `priority` was chosen for this task, so the queue's default
+    /// doesn't replace it (see `set_priority`)
+    #[serde(default)]
+    pub priority_explicit: bool,
+    /// Whether `retry_config` was chosen for this task, so the queue's
+    /// default doesn't replace it (see `set_retry_config`)
+    #[serde(default)]
+    pub retry_config_explicit: bool,
+}
+
+fn default_store_result() -> bool {
+    true
+}
+
+/// Builder for a `TaskDefinition` (see `TaskDefinition::builder`)
+#[derive(Debug, Clone)]
+pub struct TaskDefinitionBuilder {
+    task_def: TaskDefinition,
+}
+
+impl TaskDefinitionBuilder {
+    /// Queue to submit to (the queue's default if not set)
+    pub fn queue<S: Into<String>>(mut self, queue: S) -> Self {
+        self.task_def.queue = queue.into();
+        self
+    }
+
+    /// Override the task's priority
+    pub fn priority(mut self, priority: TaskPriority) -> Self {
+        self.task_def.set_priority(priority);
+        self
+    }
+
+    /// Run the task at a specific time
+    pub fn scheduled_at(mut self, scheduled_at: DateTime<Utc>) -> Self {
+        self.task_def.scheduled_at = Some(scheduled_at);
+        self.task_def.status = TaskStatus::Scheduled;
+        self
+    }
+
+    /// Override the task's retry configuration
+    ///
+    /// The stored configuration is what workers use when the task fails, so
+    /// one task type can be retried differently per submission:
+    ///
+    /// ```rust
+    /// # use distributed_task_queue::{Task, TaskDefinition, TaskError};
+    /// # use distributed_task_queue::task::RetryConfig;
+    /// # #[derive(Debug, serde::Serialize)]
+    /// # struct Charge;
+    /// # #[async_trait::async_trait]
+    /// # impl Task for Charge {
+    /// #     type Output = ();
+    /// #     type Error = TaskError;
+    /// #     async fn execute(&self) -> Result<(), TaskError> { Ok(()) }
+    /// # }
+    /// let mut once = TaskDefinition::builder(&Charge)?
+    ///     .retry_config(RetryConfig { max_retries: 0, ..Default::default() })
+    ///     .build();
+    /// let mut persistent = TaskDefinition::builder(&Charge)?
+    ///     .retry_config(RetryConfig { max_retries: 5, ..Default::default() })
+    ///     .build();
+    ///
+    /// assert!(!once.can_retry());
+    /// assert!(once.mark_retry("declined").is_err());
+    /// for _ in 0..5 {
+    ///     assert!(persistent.mark_retry("declined").is_ok());
+    /// }
+    /// assert!(persistent.mark_retry("declined").is_err());
+    /// assert_eq!(persistent.attempts.len(), 5); // one entry per failed attempt
+    /// # Ok::<(), TaskError>(())
+    /// ```
+    pub fn retry_config(mut self, retry_config: RetryConfig) -> Self {
+        self.task_def.set_retry_config(retry_config);
+        self
+    }
+
+    /// Execution timeout in seconds
+    pub fn timeout(mut self, timeout: u64) -> Self {
+        self.task_def.timeout = Some(timeout);
+        self
+    }
+
+    /// Cancel the task instead of running it if it has not started by then
+    pub fn expires_at(mut self, expires_at: DateTime<Utc>) -> Self {
+        self.task_def.expires_at = Some(expires_at);
+        self
+    }
+
+    /// Don't store the task's output when it succeeds
+    pub fn no_result(mut self) -> Self {
+        self.task_def.store_result = false;
+        self
+    }
+
+    /// Keep the task's result for `seconds` instead of the queue's
+    /// `result_ttl` (0 to keep it until evicted)
+    pub fn result_ttl(mut self, seconds: u64) -> Self {
+        self.task_def.result_ttl = Some(seconds);
+        self
+    }
+
+    /// Attach a metadata entry
+    pub fn metadata<K: Into<String>, V: Into<String>>(mut self, key: K, value: V) -> Self {
+        self.task_def.metadata.insert(key.into(), value.into());
+        self
+    }
+
+    /// Finish building
+    pub fn build(self) -> TaskDefinition {
+        self.task_def
+    }
 }
 
 impl TaskDefinition {
     /// Create a new task definition
+    ///
+    /// A priority or retry configuration the task type overrides with a
+    /// non-default value counts as chosen for the task (see `set_priority`).
     pub fn new<T>(task: &T, queue: String) -> TaskResult<Self>
     where
         T: Task + Serialize,
     {
-        let now = Utc::now();
+        let format = task.serialization_format();
+        let priority = task.priority();
+        let retry_config = task.retry_config();
         Ok(Self {
+            priority_explicit: priority != TaskPriority::default(),
+            retry_config_explicit: retry_config != RetryConfig::default(),
+            priority,
+            retry_config,
+            estimated_duration: task.estimated_duration(),
+            required_resources: task.required_resources(),
+            timeout: task.timeout(),
+            idempotency_key: task.idempotency_key(),
+            ..Self::from_parts(task.name().to_string(), format.serialize(task)?, format, queue)
+        })
+    }
+
+    /// Create a task definition carrying a raw byte payload
+    ///
+    /// The task is stored in `SerializationFormat::Binary` (requires the
+    /// `binary` feature) with default options; its handler receives the
+    /// bytes in `TaskHandler::handle_bytes`.
+    ///
+    /// ```
+    /// # use distributed_task_queue::{TaskDefinition, TaskResult};
+    /// # fn example() -> TaskResult<()> {
+    /// let task_def = TaskDefinition::from_bytes("thumbnail", &[0x89, b'P', b'N', b'G'], "images".to_string())?;
+    /// assert_eq!(task_def.data_bytes()?, [0x89, b'P', b'N', b'G']);
+    /// # Ok(())
+    /// # }
+    /// # #[cfg(feature = "binary")]
+    /// # example().unwrap();
+    /// ```
+    pub fn from_bytes<S: Into<String>>(name: S, data: &[u8], queue: String) -> TaskResult<Self> {
+        let format = SerializationFormat::Binary;
+        Ok(Self::from_parts(name.into(), format.encode_bytes(data)?, format, queue))
+    }
+
+    /// Create a task definition from a payload already serialized as JSON
+    ///
+    /// For forwarding tasks whose type is not linked in; the payload is
+    /// passed to the handler registered for `name` as is. Options are the
+    /// defaults, not those of the task type.
+    pub fn from_raw<S: Into<String>>(name: S, data: String, queue: String) -> Self {
+        Self::from_parts(name.into(), data, SerializationFormat::Json, queue)
+    }
+
+    /// A pending task with default options
+    fn from_parts(name: String, data: String, format: SerializationFormat, queue: String) -> Self {
+        let now = Utc::now();
+        Self {
             id: TaskId::new_v4(),
-            name: task.name().to_string(),
-            data: serde_json::to_string(task)?,
-            priority: task.priority(),
+            name,
+            data,
+            priority: TaskPriority::default(),
             status: TaskStatus::Pending,
-            retry_config: task.retry_config(),
+            retry_config: RetryConfig::default(),
             retry_count: 0,
             created_at: now,
             updated_at: now,
@@ -164,8 +573,52 @@ impl TaskDefinition {
             error: None,
             queue,
             worker_id: None,
-            estimated_duration: task.estimated_duration(),
-        })
+            estimated_duration: None,
+            required_resources: Vec::new(),
+            timeout: None,
+            reclaim_count: 0,
+            idempotency_key: None,
+            parent_id: None,
+            chain_depth: 0,
+            format,
+            callback_url: None,
+            metadata: HashMap::new(),
+            group_id: None,
+            attempts: Vec::new(),
+            expires_at: None,
+            store_result: true,
+            result_ttl: None,
+            result_compressed: false,
+            priority_explicit: false,
+            retry_config_explicit: false,
+        }
+    }
+
+    /// Set the task's priority, taking precedence over the queue's default
+    /// (see `TaskQueueConfig::queue_defaults`)
+    ///
+    /// Assigning `priority` directly leaves a `Normal` priority open to the
+    /// queue's default.
+    pub fn set_priority(&mut self, priority: TaskPriority) {
+        self.priority = priority;
+        self.priority_explicit = true;
+    }
+
+    /// Set the task's retry configuration, taking precedence over the
+    /// queue's default (see `set_priority`)
+    pub fn set_retry_config(&mut self, retry_config: RetryConfig) {
+        self.retry_config = retry_config;
+        self.retry_config_explicit = true;
+    }
+
+    /// The payload as the raw bytes it carries in the task's format
+    pub fn data_bytes(&self) -> TaskResult<Vec<u8>> {
+        self.format.decode_bytes(&self.data)
+    }
+
+    /// The result, if any, as the raw bytes it carries in the task's format
+    pub fn result_bytes(&self) -> TaskResult<Option<Vec<u8>>> {
+        self.result.as_deref().map(|result| self.format.decode_bytes(result)).transpose()
     }
 
     /// Create a scheduled task definition
@@ -183,6 +636,39 @@ impl TaskDefinition {
         Ok(task_def)
     }
 
+    /// Create a new task definition with an application-supplied id
+    pub fn with_id<T>(task: &T, queue: String, id: TaskId) -> TaskResult<Self>
+    where
+        T: Task + Serialize,
+    {
+        let mut task_def = Self::new(task, queue)?;
+        task_def.id = id;
+        Ok(task_def)
+    }
+
+    /// Start building a task definition with non-default options
+    ///
+    /// ```rust,no_run
+    /// # use distributed_task_queue::{Task, TaskDefinition, TaskPriority, TaskResult};
+    /// # fn example<T: Task + serde::Serialize>(task: &T) -> TaskResult<()> {
+    /// let task_def = TaskDefinition::builder(task)?
+    ///     .queue("reports")
+    ///     .priority(TaskPriority::High)
+    ///     .timeout(600)
+    ///     .metadata("tenant", "acme")
+    ///     .build();
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn builder<T>(task: &T) -> TaskResult<TaskDefinitionBuilder>
+    where
+        T: Task + Serialize,
+    {
+        Ok(TaskDefinitionBuilder {
+            task_def: Self::new(task, String::new())?,
+        })
+    }
+
     /// Mark task as started
     pub fn mark_started(&mut self, worker_id: String) {
         self.status = TaskStatus::Running;
@@ -196,23 +682,97 @@ impl TaskDefinition {
     where
         T: Serialize,
     {
+        let output = self.format.serialize(result)?;
+        self.mark_success_serialized(output);
+        Ok(())
+    }
+
+    /// Mark task as completed with output already serialized in the task's format
+    pub fn mark_success_serialized(&mut self, output: String) {
         self.status = TaskStatus::Success;
         self.finished_at = Some(Utc::now());
         self.updated_at = Utc::now();
-        self.result = Some(serde_json::to_string(result)?);
-        Ok(())
+        self.result = Some(output);
     }
 
     /// Mark task as failed
+    ///
+    /// The failure is added to `attempts`, unless `mark_retry` already
+    /// recorded it and the task has not run since.
     pub fn mark_failed(&mut self, error: &str) {
+        if self.status != TaskStatus::Retrying {
+            self.record_attempt(error);
+        }
         self.status = TaskStatus::Failed;
         self.finished_at = Some(Utc::now());
         self.updated_at = Utc::now();
         self.error = Some(error.to_string());
     }
 
-    /// Mark task for retry
-    pub fn mark_retry(&mut self) -> TaskResult<()> {
+    /// Mark task as cancelled
+    pub fn mark_cancelled(&mut self) {
+        self.status = TaskStatus::Cancelled;
+        self.finished_at = Some(Utc::now());
+        self.updated_at = Utc::now();
+    }
+
+    /// Check if the task's start deadline has passed
+    pub fn is_expired(&self) -> bool {
+        self.expires_at.is_some_and(|expires_at| expires_at <= Utc::now())
+    }
+
+    /// Mark task as cancelled because it was not started before `expires_at`
+    pub fn mark_expired(&mut self) {
+        self.mark_cancelled();
+        self.error = Some("Task expired before it could run".to_string());
+    }
+
+    /// Check if the task has not started executing yet
+    pub fn is_cancellable(&self) -> bool {
+        matches!(
+            self.status,
+            TaskStatus::Pending | TaskStatus::Scheduled | TaskStatus::Retrying
+        )
+    }
+
+    /// Reset a task abandoned mid-execution so it can be dequeued again
+    pub fn mark_reclaimed(&mut self) {
+        self.reclaim_count += 1;
+        self.status = TaskStatus::Pending;
+        self.updated_at = Utc::now();
+        self.started_at = None;
+        self.worker_id = None;
+    }
+
+    /// Reset a failed task so it runs again with a fresh retry budget
+    pub fn reset_for_rerun(&mut self) {
+        self.status = TaskStatus::Pending;
+        self.retry_count = 0;
+        self.updated_at = Utc::now();
+        self.scheduled_at = None;
+        self.started_at = None;
+        self.finished_at = None;
+        self.worker_id = None;
+        self.result = None;
+        self.error = None;
+        self.expires_at = None;
+    }
+
+    /// Add a failed attempt to the history, dropping the oldest beyond the cap
+    fn record_attempt(&mut self, error: &str) {
+        self.attempts.push(TaskAttempt {
+            attempt: self.retry_count + 1,
+            failed_at: Utc::now(),
+            error: error.to_string(),
+        });
+        if self.attempts.len() > MAX_ATTEMPT_HISTORY {
+            let excess = self.attempts.len() - MAX_ATTEMPT_HISTORY;
+            self.attempts.drain(..excess);
+        }
+    }
+
+    /// Mark task for retry after a failed attempt
+    pub fn mark_retry(&mut self, error: &str) -> TaskResult<()> {
         if self.retry_count >= self.retry_config.max_retries {
             return Err(TaskError::RetryLimitExceeded {
                 task_id: self.id.to_string(),
@@ -220,7 +780,9 @@ impl TaskDefinition {
             });
         }
 
+        self.record_attempt(error);
         self.retry_count += 1;
+        self.chain_depth += 1;
         self.status = TaskStatus::Retrying;
         self.updated_at = Utc::now();
         self.started_at = None;
@@ -228,14 +790,16 @@ impl TaskDefinition {
         self.worker_id = None;
 
         // Calculate next retry time with exponential backoff
-        let delay = if self.retry_config.exponential_backoff {
-            let exponential_delay = self.retry_config.retry_delay * (2_u64.pow(self.retry_count - 1));
-            exponential_delay.min(self.retry_config.max_delay)
-        } else {
-            self.retry_config.retry_delay
-        };
-
-        self.scheduled_at = Some(Utc::now() + chrono::Duration::seconds(delay as i64));
+        let delay = self.retry_config.delay_for_attempt(self.retry_count);
+        let delay = self.retry_config.apply_jitter(delay);
+        let now = Utc::now();
+        let retry_at = i64::try_from(delay)
+            .ok()
+            .and_then(chrono::Duration::try_seconds)
+            .and_then(|delay| now.checked_add_signed(delay))
+            .unwrap_or(DateTime::<Utc>::MAX_UTC);
+
+        self.scheduled_at = Some(retry_at);
         Ok(())
     }
 
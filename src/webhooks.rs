@@ -0,0 +1,107 @@
+//! HTTP result callbacks for finished tasks
+
+use serde::Serialize;
+use std::sync::OnceLock;
+use std::time::Duration;
+use tracing::{debug, error, warn, Instrument};
+
+use crate::serialization::SerializationFormat;
+use crate::task::{TaskDefinition, TaskId, TaskStatus};
+
+/// Attempts made to deliver a callback before giving up
+const CALLBACK_ATTEMPTS: u32 = 4;
+
+/// Delay before the first retry, doubled for each further retry
+const CALLBACK_RETRY_DELAY: Duration = Duration::from_secs(1);
+
+/// Timeout for a single callback request
+const CALLBACK_REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Body POSTed to a task's callback URL
+#[derive(Debug, Serialize)]
+struct CallbackPayload<'a> {
+    task_id: TaskId,
+    status: &'a TaskStatus,
+    /// The task's output; JSON results are embedded as JSON, others as their
+    /// stored string form
+    result: Option<serde_json::Value>,
+    error: Option<&'a str>,
+}
+
+/// HTTP client shared by all callbacks
+fn http_client() -> &'static reqwest::Client {
+    static CLIENT: OnceLock<reqwest::Client> = OnceLock::new();
+    CLIENT.get_or_init(|| {
+        reqwest::Client::builder()
+            .timeout(CALLBACK_REQUEST_TIMEOUT)
+            .build()
+            .unwrap_or_default()
+    })
+}
+
+/// POST a finished task's outcome to its callback URL in the background
+///
+/// Failed deliveries are retried with exponential backoff and then logged.
+pub(crate) fn spawn_callback(task_def: &TaskDefinition) {
+    let Some(url) = task_def.callback_url.clone() else {
+        return;
+    };
+
+    let result = task_def.result.as_deref().map(|output| match task_def.format {
+        SerializationFormat::Json => serde_json::from_str(output)
+            .unwrap_or_else(|_| serde_json::Value::String(output.to_string())),
+        SerializationFormat::MessagePack | SerializationFormat::Binary => {
+            serde_json::Value::String(output.to_string())
+        }
+    });
+    let body = match serde_json::to_vec(&CallbackPayload {
+        task_id: task_def.id,
+        status: &task_def.status,
+        result,
+        error: task_def.error.as_deref(),
+    }) {
+        Ok(body) => body,
+        Err(e) => {
+            error!("Failed to encode callback for task {}: {}", task_def.id, e);
+            return;
+        }
+    };
+    let task_id = task_def.id;
+
+    let delivery = async move {
+        let mut delay = CALLBACK_RETRY_DELAY;
+
+        for attempt in 1..=CALLBACK_ATTEMPTS {
+            let response = http_client()
+                .post(&url)
+                .header(reqwest::header::CONTENT_TYPE, "application/json")
+                .body(body.clone())
+                .send()
+                .await
+                .and_then(|response| response.error_for_status());
+
+            match response {
+                Ok(_) => {
+                    debug!("Delivered callback for task {} to {}", task_id, url);
+                    return;
+                }
+                Err(e) if attempt < CALLBACK_ATTEMPTS => {
+                    warn!(
+                        "Callback for task {} failed (attempt {}), retrying in {:?}: {}",
+                        task_id, attempt, delay, e
+                    );
+                    tokio::time::sleep(delay).await;
+                    delay *= 2;
+                }
+                Err(e) => {
+                    error!(
+                        "Giving up on callback for task {} to {} after {} attempts: {}",
+                        task_id, url, CALLBACK_ATTEMPTS, e
+                    );
+                }
+            }
+        }
+    };
+    // Keep the task's execution span on delivery logs
+    tokio::spawn(delivery.in_current_span());
+}
@@ -1,22 +1,105 @@
 //! Worker implementation for processing tasks
 
 use std::any::Any;
-use std::collections::HashMap;
-use std::sync::Arc;
-use std::time::Duration;
-use tokio::sync::{RwLock, Mutex};
-use tokio::time::{interval, sleep};
-use tracing::{debug, error, info, warn};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::marker::PhantomData;
+use std::panic::AssertUnwindSafe;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex as StdMutex};
+use std::time::{Duration, Instant};
+use futures_util::FutureExt;
+use tokio::sync::{Mutex, OwnedSemaphorePermit, RwLock, Semaphore, TryAcquireError};
+use tokio::task::JoinSet;
+use tokio::time::{interval, sleep, MissedTickBehavior};
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, error, info, info_span, warn, Instrument, Span};
 use uuid::Uuid;
+use rand::Rng;
 use serde::{Deserialize, Serialize};
 
 use crate::error::{TaskError, TaskResult};
-use crate::queue::TaskQueue;
-use crate::task::{Task, TaskDefinition, TaskStatus};
+use crate::events::{EventListener, TaskEvent};
+use crate::queue::{TaskQueue, WorkerRecord};
+use crate::serialization::SerializationFormat;
+use crate::task::{SerializableTask, TaskDefinition, TaskId, TaskStatus};
 
 /// Unique identifier for workers
 pub type WorkerId = Uuid;
 
+/// Longest a blocking dequeue waits, bounding how long shutdown can be delayed
+const BLOCKING_POLL_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Most scheduled tasks moved to their queues per scheduler tick
+const SCHEDULED_TASKS_PER_TICK: usize = 1000;
+
+/// Task metadata entry holding the submitter's W3C `traceparent` header
+///
+/// When present it is recorded on the task's execution span, so a subscriber
+/// can link the execution to the trace of the service that submitted it.
+pub const TRACEPARENT_METADATA_KEY: &str = "traceparent";
+
+/// Order in which a worker polls its queues
+#[derive(Debug, Clone, Default)]
+pub enum QueueStrategy {
+    /// Always poll queues in the configured order
+    #[default]
+    Ordered,
+    /// Start from the next queue on each tick
+    RoundRobin,
+    /// Shuffle queues each tick, favoring higher weights (missing entries weigh 1)
+    Weighted(HashMap<String, u32>),
+}
+
+impl QueueStrategy {
+    /// Queue polling order for the given tick
+    fn order(&self, queues: &[String], tick: usize) -> Vec<String> {
+        match self {
+            QueueStrategy::Ordered => queues.to_vec(),
+            QueueStrategy::RoundRobin => {
+                let mut order = queues.to_vec();
+                if !order.is_empty() {
+                    order.rotate_left(tick % queues.len());
+                }
+                order
+            }
+            QueueStrategy::Weighted(weights) => {
+                let mut remaining: Vec<(String, u64)> = queues
+                    .iter()
+                    .map(|queue_name| {
+                        let weight = weights.get(queue_name).copied().unwrap_or(1);
+                        (queue_name.clone(), weight as u64)
+                    })
+                    .collect();
+                let mut rng = rand::thread_rng();
+                let mut order = Vec::with_capacity(remaining.len());
+
+                while !remaining.is_empty() {
+                    let total: u64 = remaining.iter().map(|(_, weight)| weight).sum();
+                    let index = if total == 0 {
+                        0
+                    } else {
+                        let mut pick = rng.gen_range(0..total);
+                        remaining
+                            .iter()
+                            .position(|(_, weight)| {
+                                if pick < *weight {
+                                    true
+                                } else {
+                                    pick -= weight;
+                                    false
+                                }
+                            })
+                            .unwrap_or(0)
+                    };
+                    order.push(remaining.remove(index).0);
+                }
+
+                order
+            }
+        }
+    }
+}
+
 /// Worker configuration
 #[derive(Debug, Clone)]
 pub struct WorkerConfig {
@@ -24,18 +107,146 @@ pub struct WorkerConfig {
     pub worker_id: WorkerId,
     /// Queues this worker will process
     pub queues: Vec<String>,
+    /// Order in which `queues` are polled for free task slots
+    pub queue_strategy: QueueStrategy,
     /// Maximum number of concurrent tasks
     pub max_concurrent_tasks: usize,
+    /// Maximum number of concurrent tasks of each listed task name
+    ///
+    /// Keyed by the name tasks are submitted under (`Task::name`). A claimed
+    /// task whose type is at its cap is held by the worker until the type
+    /// frees a slot, while the worker keeps claiming other tasks. Once
+    /// `max_concurrent_tasks` tasks are held this way, further ones are
+    /// returned to the back of their priority level in their queue. Task
+    /// types not listed are only bound by `max_concurrent_tasks`.
+    pub per_type_concurrency: HashMap<String, usize>,
+    /// Tasks to claim ahead of free slots, held locally until a slot frees
+    ///
+    /// 0 (the default) claims a task only when a slot is free, leaving the
+    /// rest of the queue to other workers. Buffered tasks are returned to
+    /// their queues on shutdown.
+    pub prefetch: usize,
     /// Polling interval for new tasks in milliseconds
     pub polling_interval_ms: u64,
+    /// Longest polling interval in milliseconds while the queues stay empty
+    ///
+    /// After `idle_ticks_before_backoff` consecutive polls find no task, the
+    /// interval doubles on each further empty poll up to this bound, and
+    /// drops back to `polling_interval_ms` as soon as a task is found. `None`
+    /// keeps polling at the base interval. Has no effect with `blocking_poll`.
+    pub max_idle_interval_ms: Option<u64>,
+    /// Consecutive empty polls before the polling interval starts growing
+    pub idle_ticks_before_backoff: u32,
+    /// Wait for tasks with `TaskQueue::get_next_task_blocking` instead of polling
+    ///
+    /// Tasks are picked up within about 100ms of being submitted;
+    /// `polling_interval_ms` then only paces retries while the worker is
+    /// saturated or rate limited.
+    pub blocking_poll: bool,
     /// Task execution timeout in seconds
     pub task_timeout: u64,
+    /// Seconds after which a still running task is reported, without
+    /// cancelling it
+    ///
+    /// Logs a warning and emits `TaskEvent::SoftTimeout`. Must be less than
+    /// `task_timeout`; tasks whose own timeout is shorter are not reported.
+    pub soft_timeout: Option<u64>,
     /// Whether to auto-retry failed tasks
     pub auto_retry: bool,
     /// Heartbeat interval in seconds
     pub heartbeat_interval: u64,
     /// Worker shutdown grace period in seconds
     pub shutdown_grace_period: u64,
+    /// Use `TaskDefinition::estimated_duration` when starting tasks
+    ///
+    /// While draining or shutting down, a task estimated to run longer than
+    /// `shutdown_grace_period` goes back to its queue instead of starting.
+    /// The last free slot goes to the prefetched task with the shortest
+    /// estimate, so tasks without one wait for as long as tasks with one
+    /// keep arriving. Tasks without an estimate are otherwise started as
+    /// usual. Off by default.
+    pub duration_aware: bool,
+    /// Maximum tasks per second to dequeue from each listed queue
+    ///
+    /// Limits apply per worker process. Queues not listed are unlimited; a
+    /// limit of 0 pauses the queue.
+    pub rate_limits: HashMap<String, u32>,
+    /// Require handlers to acknowledge each task (see `TaskContext::ack`)
+    ///
+    /// A task that finishes without an ack gets no result and stays in the
+    /// processing set, so the reclaim loop redelivers it once the queue's
+    /// `visibility_timeout` passes (see `TaskQueue::release_unacked`);
+    /// redeliveries do not use up retries, but are capped by
+    /// `TaskQueueConfig::max_redeliveries`. A task that was acked is handled
+    /// as usual, including `auto_retry` when it fails.
+    pub manual_ack: bool,
+    /// Whether `start` shuts the worker down on SIGINT/SIGTERM
+    ///
+    /// Disable this when the embedding application handles signals itself
+    /// and calls `signal_shutdown`.
+    pub handle_os_signals: bool,
+    /// Seconds the main loop may go without a poll before `is_healthy`
+    /// reports the worker as stuck
+    ///
+    /// Must exceed `polling_interval_ms` and `max_idle_interval_ms`.
+    pub stall_threshold: u64,
+}
+
+impl WorkerConfig {
+    /// Check the configuration for inconsistent settings
+    pub fn validate(&self) -> TaskResult<()> {
+        if let Some(soft_timeout) = self.soft_timeout {
+            if soft_timeout >= self.task_timeout {
+                return Err(TaskError::config(format!(
+                    "soft_timeout ({}s) must be less than task_timeout ({}s)",
+                    soft_timeout, self.task_timeout
+                )));
+            }
+        }
+        if let Some(task_name) = self
+            .per_type_concurrency
+            .iter()
+            .find_map(|(task_name, &limit)| (limit == 0).then_some(task_name))
+        {
+            return Err(TaskError::config(format!(
+                "per_type_concurrency for {} must be at least 1",
+                task_name
+            )));
+        }
+        if let Some(max_idle_interval_ms) = self.max_idle_interval_ms {
+            if max_idle_interval_ms < self.polling_interval_ms {
+                return Err(TaskError::config(format!(
+                    "max_idle_interval_ms ({}) must be at least polling_interval_ms ({})",
+                    max_idle_interval_ms, self.polling_interval_ms
+                )));
+            }
+        }
+        let longest_poll_gap_ms = self.max_idle_interval_ms.unwrap_or(0).max(self.polling_interval_ms);
+        if self.stall_threshold.saturating_mul(1000) <= longest_poll_gap_ms {
+            return Err(TaskError::config(format!(
+                "stall_threshold ({}s) must exceed the longest polling interval ({}ms)",
+                self.stall_threshold, longest_poll_gap_ms
+            )));
+        }
+        Ok(())
+    }
+
+    /// Extra wait before the next poll after `idle_ticks` consecutive empty polls
+    fn idle_backoff(&self, idle_ticks: u32) -> Duration {
+        let Some(max_idle_interval_ms) = self.max_idle_interval_ms else {
+            return Duration::ZERO;
+        };
+        if idle_ticks < self.idle_ticks_before_backoff {
+            return Duration::ZERO;
+        }
+
+        let doublings = (idle_ticks - self.idle_ticks_before_backoff + 1).min(16);
+        let idle_interval_ms = self
+            .polling_interval_ms
+            .saturating_mul(1 << doublings)
+            .min(max_idle_interval_ms);
+        Duration::from_millis(idle_interval_ms.saturating_sub(self.polling_interval_ms))
+    }
 }
 
 impl Default for WorkerConfig {
@@ -43,12 +254,24 @@ impl Default for WorkerConfig {
         Self {
             worker_id: WorkerId::new_v4(),
             queues: vec!["default".to_string()],
+            queue_strategy: QueueStrategy::Ordered,
             max_concurrent_tasks: 4,
+            per_type_concurrency: HashMap::new(),
+            prefetch: 0,
             polling_interval_ms: 1000,
+            max_idle_interval_ms: None,
+            idle_ticks_before_backoff: 10,
+            blocking_poll: false,
             task_timeout: 300, // 5 minutes
+            soft_timeout: None,
             auto_retry: true,
             heartbeat_interval: 30,
             shutdown_grace_period: 30,
+            duration_aware: false,
+            rate_limits: HashMap::new(),
+            manual_ack: false,
+            handle_os_signals: true,
+            stall_threshold: 60,
         }
     }
 }
@@ -65,17 +288,465 @@ pub struct WorkerStats {
     pub started_at: chrono::DateTime<chrono::Utc>,
 }
 
+/// Token bucket limiting how fast tasks are taken from a queue
+#[derive(Debug)]
+struct TokenBucket {
+    tokens_per_second: f64,
+    tokens: f64,
+    last_refill: tokio::time::Instant,
+}
+
+impl TokenBucket {
+    /// Create a full bucket allowing `tasks_per_second` with bursts of the same size
+    fn new(tasks_per_second: u32) -> Self {
+        let tokens_per_second = tasks_per_second as f64;
+        Self {
+            tokens_per_second,
+            tokens: tokens_per_second,
+            last_refill: tokio::time::Instant::now(),
+        }
+    }
+
+    /// Whether a token is available right now
+    fn has_token(&mut self) -> bool {
+        let now = tokio::time::Instant::now();
+        let elapsed = now.duration_since(self.last_refill).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.tokens_per_second).min(self.tokens_per_second);
+        self.last_refill = now;
+        self.tokens >= 1.0
+    }
+
+    /// Consume a token
+    fn take(&mut self) {
+        self.tokens -= 1.0;
+    }
+}
+
+/// Acknowledges a task in manual-ack mode (see `WorkerConfig::manual_ack`)
+///
+/// Clones share the same acknowledgment, so a handle can be moved into
+/// other tasks spawned by the handler.
+#[derive(Debug, Clone, Default)]
+pub struct AckHandle {
+    acked: Arc<AtomicBool>,
+}
+
+impl AckHandle {
+    /// Acknowledge the task, letting the worker record its outcome
+    pub fn ack(&self) {
+        self.acked.store(true, Ordering::SeqCst);
+    }
+
+    /// Whether the task has been acknowledged
+    pub fn is_acked(&self) -> bool {
+        self.acked.load(Ordering::SeqCst)
+    }
+}
+
+/// Worker slots held by a running task
+struct SlotPermits {
+    /// Slot under `max_concurrent_tasks`
+    _worker: OwnedSemaphorePermit,
+    /// Slot under the task's `per_type_concurrency` cap, if its type has one
+    _task_type: Option<OwnedSemaphorePermit>,
+}
+
+/// What the worker loop needs to start the tasks it claims
+struct Dispatcher {
+    config: WorkerConfig,
+    queue: Arc<TaskQueue>,
+    handlers: Arc<TaskHandlerRegistry>,
+    stats: Arc<Mutex<WorkerStats>>,
+    shutdown_signal: Arc<RwLock<bool>>,
+    draining: Arc<RwLock<bool>>,
+    active_tasks: Arc<Mutex<JoinSet<()>>>,
+    running_task_ids: Arc<StdMutex<HashSet<TaskId>>>,
+    cancellation: CancellationToken,
+}
+
+impl Dispatcher {
+    /// Start executing a dequeued task, or settle it if it cannot run
+    ///
+    /// The task holds `permit` (its worker slots) until it finishes.
+    async fn dispatch(&self, mut task_def: TaskDefinition, permit: SlotPermits) {
+        // Tasks started while the worker drains or shuts down
+        let winding_down = *self.draining.read().await || *self.shutdown_signal.read().await;
+
+        // Don't start what would be cut off by the shutdown grace period
+        let outlasts_shutdown = task_def
+            .estimated_duration
+            .is_some_and(|estimate| estimate > self.config.shutdown_grace_period);
+        if winding_down && self.config.duration_aware && outlasts_shutdown {
+            info!(
+                "Returning task {} to its queue: estimated to outlast the {}s shutdown grace period",
+                task_def.id, self.config.shutdown_grace_period
+            );
+            if let Err(e) = self.queue.release_resources(&task_def).await {
+                error!("Failed to release resources for task {}: {}", task_def.id, e);
+            }
+            if let Err(e) = self.queue.requeue_task(&task_def).await {
+                error!("Failed to return task {} to its queue: {}", task_def.id, e);
+            }
+            return;
+        }
+
+        // Honor a cancellation that raced with the dequeue
+        if let Ok(Some(stored)) = self.queue.get_task(task_def.id).await {
+            if stored.status == TaskStatus::Cancelled {
+                info!("Skipping cancelled task {}", task_def.id);
+                if let Err(e) = self.queue.release_resources(&task_def).await {
+                    error!("Failed to release resources for task {}: {}", task_def.id, e);
+                }
+                if let Err(e) = self.queue.remove_from_processing(&task_def).await {
+                    error!("Failed to remove cancelled task from processing: {}", e);
+                }
+                return;
+            }
+        }
+
+        // Mark task as started
+        task_def.mark_started(self.config.worker_id.to_string());
+        if let Err(e) = self.queue.mark_task_started(&task_def).await {
+            error!("Failed to record start of task {}: {}", task_def.id, e);
+        }
+        self.queue
+            .emit_event(TaskEvent::Started {
+                task_id: task_def.id,
+                queue: task_def.queue.clone(),
+                worker_id: self.config.worker_id.to_string(),
+            })
+            .await;
+
+        // Find handler for this task
+        if let Some(handler) = self.handlers.find_handler(&task_def.name).await {
+            let task_id = task_def.id;
+            let span = Worker::task_span(&task_def);
+            let execution = Worker::execute_task(
+                task_def,
+                handler,
+                self.handlers.middleware().await,
+                self.queue.clone(),
+                self.stats.clone(),
+                self.config.clone(),
+                self.cancellation.child_token(),
+            );
+
+            // Spawn task execution, freeing the slot when it finishes
+            let running = RunningTask::start(self.running_task_ids.clone(), task_id);
+            self.active_tasks.lock().await.spawn(
+                async move {
+                    execution.await;
+                    drop(running);
+                    drop(permit);
+                }
+                .instrument(span),
+            );
+        } else {
+            error!("No handler found for task type: {}", task_def.name);
+            task_def.mark_failed(&format!("No handler found for task type: {}", task_def.name));
+            if let Err(e) = self.queue.release_resources(&task_def).await {
+                error!("Failed to release resources for task {}: {}", task_def.id, e);
+            }
+            if let Err(e) = self.queue.mark_task_failed(&task_def).await {
+                error!("Failed to mark task as failed: {}", e);
+            }
+            Worker::emit_failed(&self.queue, &task_def).await;
+            Worker::send_callback(&task_def);
+        }
+    }
+}
+
+/// Lock a worker's set of running task ids
+///
+/// The lock is only held to update or copy the set, so a panic while holding
+/// it cannot leave the set half-updated.
+fn lock_running(ids: &StdMutex<HashSet<TaskId>>) -> std::sync::MutexGuard<'_, HashSet<TaskId>> {
+    ids.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// Keeps a task in its worker's running set until dropped, even if the task
+/// panics
+struct RunningTask {
+    ids: Arc<StdMutex<HashSet<TaskId>>>,
+    task_id: TaskId,
+}
+
+impl RunningTask {
+    fn start(ids: Arc<StdMutex<HashSet<TaskId>>>, task_id: TaskId) -> Self {
+        lock_running(&ids).insert(task_id);
+        Self { ids, task_id }
+    }
+}
+
+impl Drop for RunningTask {
+    fn drop(&mut self) {
+        lock_running(&self.ids).remove(&self.task_id);
+    }
+}
+
+/// Execution context passed to task handlers
+#[derive(Debug, Clone)]
+pub struct TaskContext {
+    task_id: TaskId,
+    queue: Arc<TaskQueue>,
+    cancellation: CancellationToken,
+    metadata: HashMap<String, String>,
+    ack: AckHandle,
+}
+
+impl TaskContext {
+    /// Create a context for the given task
+    pub fn new(task_id: TaskId, queue: Arc<TaskQueue>) -> Self {
+        Self {
+            task_id,
+            queue,
+            cancellation: CancellationToken::new(),
+            metadata: HashMap::new(),
+            ack: AckHandle::default(),
+        }
+    }
+
+    /// Attach the task's metadata
+    pub fn with_metadata(mut self, metadata: HashMap<String, String>) -> Self {
+        self.metadata = metadata;
+        self
+    }
+
+    /// Use `cancellation` as the task's cancellation token
+    pub fn with_cancellation(mut self, cancellation: CancellationToken) -> Self {
+        self.cancellation = cancellation;
+        self
+    }
+
+    /// Id of the task being executed
+    pub fn task_id(&self) -> TaskId {
+        self.task_id
+    }
+
+    /// Metadata attached to the task at submission
+    pub fn metadata(&self) -> &HashMap<String, String> {
+        &self.metadata
+    }
+
+    /// Whether the worker has asked the task to stop
+    ///
+    /// Set when the worker starts shutting down. Long-running handlers should
+    /// check it between steps, clean up and return an error, before the
+    /// grace period ends and the task is aborted.
+    pub fn is_cancelled(&self) -> bool {
+        self.cancellation.is_cancelled()
+    }
+
+    /// Completes when the worker asks the task to stop
+    pub async fn cancelled(&self) {
+        self.cancellation.cancelled().await
+    }
+
+    /// The task's cancellation token, e.g. to hand to other libraries
+    pub fn cancellation_token(&self) -> &CancellationToken {
+        &self.cancellation
+    }
+
+    /// Acknowledge the task
+    ///
+    /// Only needed with `WorkerConfig::manual_ack`; call it once the task's
+    /// work is safely done (or has failed for good), before returning.
+    pub fn ack(&self) {
+        self.ack.ack();
+    }
+
+    /// Handle for acknowledging the task from elsewhere
+    pub fn ack_handle(&self) -> AckHandle {
+        self.ack.clone()
+    }
+
+    /// Report progress (0 to 100) with an optional status message
+    ///
+    /// Clients can read it with `TaskClient::get_task_progress`.
+    pub async fn set_progress(&self, percent: u8, message: Option<&str>) -> TaskResult<()> {
+        self.queue.set_task_progress(self.task_id, percent, message).await
+    }
+}
+
+/// The message a panic was raised with, if it was a string
+fn panic_message(panic: &(dyn Any + Send)) -> &str {
+    if let Some(message) = panic.downcast_ref::<&str>() {
+        message
+    } else if let Some(message) = panic.downcast_ref::<String>() {
+        message
+    } else {
+        "non-string panic payload"
+    }
+}
+
 /// Task handler trait for executing different types of tasks
+///
+/// A handler that panics fails its task with a "handler panicked" error,
+/// which is retried like any other error.
 #[async_trait::async_trait]
 pub trait TaskHandler: Send + Sync {
     fn can_handle(&self, task_name: &str) -> bool;
     async fn handle(&self, task_data: &str) -> TaskResult<String>;
+
+    /// Whether a failed task should be retried
+    ///
+    /// Defaults to `TaskError::is_recoverable`, so `TaskError::permanent`
+    /// and serialization errors fail the task without retrying.
+    fn is_retryable(&self, error: &crate::error::TaskError) -> bool {
+        error.is_recoverable()
+    }
+
+    /// Execute a task with access to its execution context
+    ///
+    /// Override this instead of relying on `handle` to report progress or to
+    /// stop cooperatively when the worker shuts down.
+    async fn handle_with_context(&self, task_data: &str, _ctx: &TaskContext) -> TaskResult<String> {
+        self.handle(task_data).await
+    }
+
+    /// Execute a task with a raw byte payload (`SerializationFormat::Binary`)
+    ///
+    /// Returns the raw output, stored as the task's result. Handlers for
+    /// binary tasks must override this; the default rejects the task.
+    async fn handle_bytes(&self, _task_data: &[u8], _ctx: &TaskContext) -> TaskResult<Vec<u8>> {
+        Err(TaskError::permanent("handler does not accept binary payloads"))
+    }
+
+    /// Execute a task, returning its output serialized in the task's format
+    ///
+    /// The default passes binary payloads to `handle_bytes` and everything
+    /// else as JSON to `handle_with_context`, transcoding payloads and outputs
+    /// of tasks stored in other formats. Handlers that work with
+    /// `task_def.format` directly can override this to skip the conversion.
+    async fn handle_task(&self, task_def: &TaskDefinition, ctx: &TaskContext) -> TaskResult<String> {
+        if task_def.format == SerializationFormat::Binary {
+            let output = self.handle_bytes(&task_def.data_bytes()?, ctx).await?;
+            return task_def.format.encode_bytes(&output);
+        }
+        if task_def.format == SerializationFormat::Json {
+            return self.handle_with_context(&task_def.data, ctx).await;
+        }
+
+        let data: serde_json::Value = task_def.format.deserialize(&task_def.data)?;
+        let output = self.handle_with_context(&serde_json::to_string(&data)?, ctx).await?;
+        let output: serde_json::Value = serde_json::from_str(&output)?;
+        task_def.format.serialize(&output)
+    }
+
+    /// Follow-up task to enqueue after a successful `handle`
+    ///
+    /// `output` is in the task's serialization format, `task_def.format`.
+    /// Handlers for chained tasks deserialize `task_def.data` with that format
+    /// and delegate to `Task::on_success`.
+    fn on_success(&self, _task_def: &TaskDefinition, _output: &str) -> TaskResult<Option<TaskDefinition>> {
+        Ok(None)
+    }
+}
+
+/// Handler for a task type that runs the task's own `Task` impl
+///
+/// Deserializes the payload into `T`, calls `execute_validated` and
+/// serializes the output, so a task type needs no hand-written handler.
+/// Errors `Task::is_retryable` rejects fail the task without retrying, and
+/// `Task::on_success` follow-ups are enqueued. `Worker::register_task`
+/// registers one under the task's default name.
+///
+/// ```
+/// use distributed_task_queue::worker::{TaskHandler, TypedTaskHandler};
+/// use distributed_task_queue::{Task, TaskError};
+///
+/// #[derive(Debug, serde::Serialize, serde::Deserialize)]
+/// struct Add { a: i64, b: i64 }
+///
+/// #[async_trait::async_trait]
+/// impl Task for Add {
+///     type Output = i64;
+///     type Error = TaskError;
+///     async fn execute(&self) -> Result<i64, TaskError> { Ok(self.a + self.b) }
+///     fn name(&self) -> &'static str { "add" }
+/// }
+///
+/// # tokio_test::block_on(async {
+/// let handler = TypedTaskHandler::<Add>::new("add");
+/// assert!(handler.can_handle("add"));
+/// assert_eq!(handler.handle(r#"{"a":2,"b":3}"#).await?, "5");
+/// # Ok::<(), TaskError>(())
+/// # })?;
+/// # Ok::<(), TaskError>(())
+/// ```
+pub struct TypedTaskHandler<T> {
+    task_name: String,
+    _task: PhantomData<fn() -> T>,
+}
+
+impl<T: SerializableTask + 'static> TypedTaskHandler<T> {
+    /// Handle tasks named `task_name` as `T`
+    pub fn new<S: Into<String>>(task_name: S) -> Self {
+        Self {
+            task_name: task_name.into(),
+            _task: PhantomData,
+        }
+    }
+
+    /// Run a deserialized task, returning its output unserialized
+    async fn run(task: T) -> TaskResult<T::Output> {
+        match task.execute_validated().await {
+            Ok(output) => Ok(output),
+            Err(error) if !task.is_retryable(&error) => Err(TaskError::permanent(error.to_string())),
+            // Keep a `TaskError` as it is, so e.g. connection errors are recognized
+            Err(error) => {
+                let message = error.to_string();
+                match (Box::new(error) as Box<dyn Any>).downcast::<TaskError>() {
+                    Ok(error) => Err(*error),
+                    Err(_) => Err(TaskError::task_execution(message)),
+                }
+            }
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl<T: SerializableTask + 'static> TaskHandler for TypedTaskHandler<T> {
+    fn can_handle(&self, task_name: &str) -> bool {
+        task_name == self.task_name
+    }
+
+    async fn handle(&self, task_data: &str) -> TaskResult<String> {
+        let output = Self::run(serde_json::from_str(task_data)?).await?;
+        Ok(serde_json::to_string(&output)?)
+    }
+
+    async fn handle_task(&self, task_def: &TaskDefinition, _ctx: &TaskContext) -> TaskResult<String> {
+        let output = Self::run(task_def.format.deserialize(&task_def.data)?).await?;
+        task_def.format.serialize(&output)
+    }
+
+    fn on_success(&self, task_def: &TaskDefinition, output: &str) -> TaskResult<Option<TaskDefinition>> {
+        let task: T = task_def.format.deserialize(&task_def.data)?;
+        Ok(task.on_success(output))
+    }
+}
+
+/// Hooks that run around every task a worker executes
+///
+/// Useful for cross-cutting concerns such as tracing spans, metrics or
+/// setting up an auth context.
+#[async_trait::async_trait]
+pub trait Middleware: Send + Sync {
+    /// Called before the task's handler runs
+    async fn before(&self, _task: &TaskDefinition) {}
+
+    /// Called with the handler's outcome, including a timeout error
+    async fn after(&self, _task: &TaskDefinition, _result: &TaskResult<String>) {}
 }
 
 /// Registry for task handlers
 #[derive(Default)]
 pub struct TaskHandlerRegistry {
     handlers: RwLock<HashMap<String, Arc<dyn TaskHandler>>>,
+    /// Used for tasks no registered handler accepts
+    fallback: RwLock<Option<Arc<dyn TaskHandler>>>,
+    middleware: RwLock<Vec<Arc<dyn Middleware>>>,
 }
 
 impl TaskHandlerRegistry {
@@ -88,6 +759,35 @@ impl TaskHandlerRegistry {
         handlers.insert(task_name, Arc::new(handler));
     }
 
+    /// Set the handler for tasks no registered handler accepts, replacing
+    /// any previous one
+    pub async fn set_fallback<H>(&self, handler: H)
+    where
+        H: TaskHandler + 'static,
+    {
+        *self.fallback.write().await = Some(Arc::new(handler));
+    }
+
+    /// Add middleware to run around every task
+    pub async fn add_middleware<M>(&self, middleware: M)
+    where
+        M: Middleware + 'static,
+    {
+        self.middleware.write().await.push(Arc::new(middleware));
+    }
+
+    /// Registered middleware, in registration order
+    async fn middleware(&self) -> Vec<Arc<dyn Middleware>> {
+        self.middleware.read().await.clone()
+    }
+
+    /// Names handlers were registered under, sorted
+    async fn task_names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.handlers.read().await.keys().cloned().collect();
+        names.sort();
+        names
+    }
+
     /// Find a handler for a task
     async fn find_handler(&self, task_name: &str) -> Option<Arc<dyn TaskHandler>> {
         let handlers = self.handlers.read().await;
@@ -104,7 +804,8 @@ impl TaskHandlerRegistry {
             }
         }
 
-        None
+        // Finally the catch-all, if any
+        self.fallback.read().await.clone()
     }
 }
 
@@ -115,22 +816,53 @@ pub struct Worker {
     handlers: Arc<TaskHandlerRegistry>,
     stats: Arc<Mutex<WorkerStats>>,
     shutdown_signal: Arc<RwLock<bool>>,
-    active_tasks: Arc<RwLock<HashMap<Uuid, tokio::task::JoinHandle<()>>>>,
+    /// Set while draining: no new tasks are taken, but the worker keeps running
+    draining: Arc<RwLock<bool>>,
+    /// One permit per free task slot; each running task holds a permit
+    task_slots: Arc<Semaphore>,
+    /// Free slots of each task type with a `per_type_concurrency` cap
+    type_slots: Arc<HashMap<String, Arc<Semaphore>>>,
+    active_tasks: Arc<Mutex<JoinSet<()>>>,
+    /// Ids of the tasks being executed, tracked apart from `active_tasks` so
+    /// reading them never waits on the worker loop
+    running_task_ids: Arc<StdMutex<HashSet<TaskId>>>,
+    /// Cancelled when shutdown starts, asking running tasks to stop
+    cancellation: CancellationToken,
+    /// Cancelled with the shutdown signal, waking the background loops
+    stop_loops: CancellationToken,
+    /// When the main loop last polled the queues without an error, or had
+    /// no room to poll, `None` before it first does
+    last_poll: Arc<StdMutex<Option<Instant>>>,
 }
 
 impl Worker {
     /// Create a new worker with the given configuration
     pub fn new(config: WorkerConfig, queue: Arc<TaskQueue>) -> Self {
-        let mut stats = WorkerStats::default();
-        stats.started_at = chrono::Utc::now();
+        let stats = WorkerStats {
+            started_at: chrono::Utc::now(),
+            ..Default::default()
+        };
 
         Self {
+            task_slots: Arc::new(Semaphore::new(config.max_concurrent_tasks)),
+            type_slots: Arc::new(
+                config
+                    .per_type_concurrency
+                    .iter()
+                    .map(|(task_name, &limit)| (task_name.clone(), Arc::new(Semaphore::new(limit))))
+                    .collect(),
+            ),
             config,
             queue,
             handlers: Arc::new(TaskHandlerRegistry::default()),
             stats: Arc::new(Mutex::new(stats)),
             shutdown_signal: Arc::new(RwLock::new(false)),
-            active_tasks: Arc::new(RwLock::new(HashMap::new())),
+            draining: Arc::new(RwLock::new(false)),
+            active_tasks: Arc::new(Mutex::new(JoinSet::new())),
+            running_task_ids: Arc::new(StdMutex::new(HashSet::new())),
+            cancellation: CancellationToken::new(),
+            stop_loops: CancellationToken::new(),
+            last_poll: Arc::new(StdMutex::new(None)),
         }
     }
 
@@ -142,40 +874,114 @@ impl Worker {
         self.handlers.register(task_name, handler).await;
     }
 
+    /// Register the generated handler for a task type (see `TypedTaskHandler`)
+    ///
+    /// Uses the default task name, the type name; for a task that overrides
+    /// `Task::name`, register `TypedTaskHandler::new(name)` with
+    /// `register_handler` instead.
+    pub async fn register_task<T>(&self)
+    where
+        T: SerializableTask + 'static,
+    {
+        let task_name = std::any::type_name::<T>();
+        self.handlers
+            .register(task_name.to_string(), TypedTaskHandler::<T>::new(task_name))
+            .await;
+    }
+
+    /// Register a catch-all handler for tasks no other handler accepts
+    ///
+    /// Its `can_handle` is not consulted. Without one, such tasks fail
+    /// immediately. Override `handle_task` to see the task's name, for
+    /// example to forward unknown tasks to another system.
+    pub async fn register_fallback_handler<H>(&self, handler: H)
+    where
+        H: TaskHandler + 'static,
+    {
+        self.handlers.set_fallback(handler).await;
+    }
+
+    /// Names the worker's handlers were registered under
+    ///
+    /// Handlers that accept further names through `can_handle` only
+    /// contribute their registered name. Useful to fill a client's
+    /// `KnownTasks`.
+    pub async fn task_names(&self) -> Vec<String> {
+        self.handlers.task_names().await
+    }
+
+    /// Register middleware to run around every task
+    ///
+    /// `before` hooks run in registration order and `after` hooks in
+    /// reverse order.
+    pub async fn register_middleware<M>(&self, middleware: M)
+    where
+        M: Middleware + 'static,
+    {
+        self.handlers.add_middleware(middleware).await;
+    }
+
+    /// Register a listener for task lifecycle events
+    ///
+    /// Listeners belong to the worker's queue, so they also see `Queued`
+    /// events for tasks submitted through it.
+    pub async fn add_event_listener<L>(&self, listener: L)
+    where
+        L: EventListener + 'static,
+    {
+        self.queue.add_event_listener(listener).await;
+    }
+
     /// Start the worker
+    ///
+    /// Runs until `signal_shutdown` is called, a background loop stops or,
+    /// with `handle_os_signals`, SIGINT or SIGTERM arrives. Returns only once
+    /// every background loop has exited and running tasks have finished or
+    /// been stopped, so nothing keeps polling the queue afterwards.
     pub async fn start(&self) -> TaskResult<()> {
+        self.config.validate()?;
         info!("Starting worker {} for queues: {:?}", self.config.worker_id, self.config.queues);
 
         // Start heartbeat task
-        let heartbeat_task = self.start_heartbeat_task().await;
+        let mut heartbeat_task = self.start_heartbeat_task().await;
 
         // Start scheduled task processor
-        let scheduler_task = self.start_scheduler_task().await;
+        let mut scheduler_task = self.start_scheduler_task().await;
 
         // Start cleanup task
-        let cleanup_task = self.start_cleanup_task().await;
+        let mut cleanup_task = self.start_cleanup_task().await;
+
+        // Start stalled task reclaimer
+        let mut reclaim_task = self.start_reclaim_task().await;
 
         // Main worker loop
-        let worker_task = self.start_worker_loop().await;
+        let mut worker_task = self.start_worker_loop().await;
 
         // Wait for shutdown signal or task completion
         tokio::select! {
-            _ = heartbeat_task => {
+            _ = &mut heartbeat_task => {
                 warn!("Heartbeat task completed unexpectedly");
             }
-            _ = scheduler_task => {
+            _ = &mut scheduler_task => {
                 warn!("Scheduler task completed unexpectedly");
             }
-            _ = cleanup_task => {
+            _ = &mut cleanup_task => {
                 warn!("Cleanup task completed unexpectedly");
             }
-            _ = worker_task => {
+            _ = &mut reclaim_task => {
+                warn!("Reclaim task completed unexpectedly");
+            }
+            _ = &mut worker_task => {
                 info!("Worker loop completed");
             }
+            _ = Self::wait_for_os_signal(), if self.config.handle_os_signals => {
+                info!("Received shutdown signal, draining worker {}", self.config.worker_id);
+            }
         }
 
         // Graceful shutdown
-        self.shutdown().await?;
+        self.shutdown(vec![worker_task, heartbeat_task, scheduler_task, cleanup_task, reclaim_task])
+            .await?;
 
         Ok(())
     }
@@ -184,205 +990,642 @@ impl Worker {
     async fn start_worker_loop(&self) -> tokio::task::JoinHandle<()> {
         let config = self.config.clone();
         let queue = self.queue.clone();
-        let handlers = self.handlers.clone();
-        let stats = self.stats.clone();
         let shutdown_signal = self.shutdown_signal.clone();
+        let draining = self.draining.clone();
+        let task_slots = self.task_slots.clone();
+        let type_slots = self.type_slots.clone();
         let active_tasks = self.active_tasks.clone();
+        let dispatcher = Dispatcher {
+            config: self.config.clone(),
+            queue: self.queue.clone(),
+            handlers: self.handlers.clone(),
+            stats: self.stats.clone(),
+            shutdown_signal: self.shutdown_signal.clone(),
+            draining: self.draining.clone(),
+            active_tasks: self.active_tasks.clone(),
+            running_task_ids: self.running_task_ids.clone(),
+            cancellation: self.cancellation.clone(),
+        };
+        let last_poll = self.last_poll.clone();
+        let stop_loops = self.stop_loops.clone();
 
         tokio::spawn(async move {
             let mut interval = interval(Duration::from_millis(config.polling_interval_ms));
+            // Don't catch up on ticks missed while backing off
+            interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+            let mut rate_limiters: HashMap<String, TokenBucket> = config
+                .rate_limits
+                .iter()
+                .map(|(queue_name, &limit)| (queue_name.clone(), TokenBucket::new(limit)))
+                .collect();
+
+            let mut tick: usize = 0;
+            // Consecutive polls that found every queue empty
+            let mut idle_ticks: u32 = 0;
+            // Claimed tasks waiting for a free slot (see `WorkerConfig::prefetch`)
+            let mut prefetched: VecDeque<TaskDefinition> = VecDeque::new();
+            // Claimed tasks whose type is at its cap (see `WorkerConfig::per_type_concurrency`)
+            let mut deferred: VecDeque<TaskDefinition> = VecDeque::new();
 
             loop {
-                interval.tick().await;
+                if !config.blocking_poll {
+                    let wait = async {
+                        interval.tick().await;
+                        let backoff = config.idle_backoff(idle_ticks);
+                        if !backoff.is_zero() {
+                            sleep(backoff).await;
+                        }
+                    };
+                    // Shutdown is checked below
+                    tokio::select! {
+                        _ = wait => {}
+                        _ = stop_loops.cancelled() => {}
+                    }
+                }
+                tick = tick.wrapping_add(1);
 
                 // Check shutdown signal
                 if *shutdown_signal.read().await {
                     break;
                 }
+                let mark_polled = || {
+                    *last_poll.lock().unwrap_or_else(|poisoned| poisoned.into_inner()) = Some(Instant::now());
+                };
+
+                Self::reap_finished_tasks(&active_tasks).await;
+
+                // Wait out a Redis outage rather than polling into it
+                if let Some(delay) = queue.reconnect_delay() {
+                    debug!("Redis unavailable, pausing polling for {:?}", delay);
+                    sleep(delay).await;
+                    continue;
+                }
+
+                // Start deferred tasks whose type has freed a slot
+                while !deferred.is_empty() {
+                    let permit = match task_slots.clone().try_acquire_owned() {
+                        Ok(permit) => permit,
+                        Err(_) => break,
+                    };
+                    let Some((task_def, type_permit)) = Self::take_deferred(&mut deferred, &type_slots) else {
+                        break;
+                    };
+                    let permits = SlotPermits { _worker: permit, _task_type: type_permit };
+                    dispatcher.dispatch(task_def, permits).await;
+                }
 
-                // Check if we can process more tasks
-                let active_count = active_tasks.read().await.len();
-                if active_count >= config.max_concurrent_tasks {
+                // Start prefetched tasks before claiming new ones
+                while !prefetched.is_empty() {
+                    let permit = match task_slots.clone().try_acquire_owned() {
+                        Ok(permit) => permit,
+                        Err(_) => break,
+                    };
+                    // With no slot left after this one, start the quickest task
+                    let last_slot = task_slots.available_permits() == 0;
+                    if let Some(task_def) = Self::take_prefetched(&mut prefetched, config.duration_aware && last_slot) {
+                        match Self::claim_type_slot(&type_slots, &task_def) {
+                            Ok(type_permit) => {
+                                let permits = SlotPermits { _worker: permit, _task_type: type_permit };
+                                dispatcher.dispatch(task_def, permits).await;
+                            }
+                            Err(_) => deferred.push_back(task_def),
+                        }
+                    }
+                }
+
+                // A draining worker only finishes the tasks it already holds
+                if *draining.read().await {
+                    mark_polled();
+                    if config.blocking_poll {
+                        sleep(Duration::from_millis(config.polling_interval_ms)).await;
+                    }
                     continue;
                 }
 
-                // Try to get a task from each queue
-                for queue_name in &config.queues {
-                    if let Ok(Some(mut task_def)) = queue.get_next_task(queue_name).await {
-                        debug!("Got task {} from queue {}", task_def.id, queue_name);
-
-                        // Mark task as started
-                        task_def.mark_started(config.worker_id.to_string());
-
-                                                 // Find handler for this task
-                         if let Some(handler) = handlers.find_handler(&task_def.name).await {
-                             let task_id = task_def.id;
-                             
-                             // Spawn task execution
-                             let task_handle = Self::spawn_task_execution(
-                                 task_def,
-                                 handler,
-                                 queue.clone(),
-                                 stats.clone(),
-                                 config.clone(),
-                             ).await;
-
-                             // Track active task
-                             active_tasks.write().await.insert(task_id, task_handle);
-                         } else {
-                             error!("No handler found for task type: {}", task_def.name);
-                             task_def.mark_failed(&format!("No handler found for task type: {}", task_def.name));
-                             if let Err(e) = queue.mark_task_failed(&task_def).await {
-                                 error!("Failed to mark task as failed: {}", e);
-                             }
-                         }
+                // Check if we can hold more tasks
+                if task_slots.available_permits() == 0 && prefetched.len() >= config.prefetch {
+                    mark_polled();
+                    if config.blocking_poll {
+                        sleep(Duration::from_millis(config.polling_interval_ms)).await;
                     }
+                    continue;
                 }
 
-                // Clean up completed tasks
-                Self::cleanup_completed_tasks(&active_tasks).await;
+                let paused = match queue.paused_queues(&config.queues).await {
+                    Ok(paused) => paused,
+                    // The queue already logs an outage once
+                    Err(e) if e.is_connection_error() => continue,
+                    Err(e) => {
+                        error!("Failed to check for paused queues: {}", e);
+                        HashSet::new()
+                    }
+                };
+
+                // Skip paused and rate-limited queues without blocking the others
+                let ready_queues: Vec<String> = config
+                    .queue_strategy
+                    .order(&config.queues, tick)
+                    .into_iter()
+                    .filter(|queue_name| {
+                        if paused.contains(queue_name) {
+                            debug!("Queue {} is paused, skipping this tick", queue_name);
+                            return false;
+                        }
+                        let limited = rate_limiters
+                            .get_mut(queue_name)
+                            .is_some_and(|bucket| !bucket.has_token());
+                        if limited {
+                            debug!("Queue {} is rate limited, skipping this tick", queue_name);
+                        }
+                        !limited
+                    })
+                    .collect();
+
+                if config.blocking_poll {
+                    if ready_queues.is_empty() {
+                        mark_polled();
+                        sleep(Duration::from_millis(config.polling_interval_ms)).await;
+                        continue;
+                    }
+
+                    // Without a free slot the task is prefetched
+                    let permit = task_slots.clone().try_acquire_owned().ok();
+
+                    let dequeued = queue.get_next_task_blocking(&ready_queues, BLOCKING_POLL_TIMEOUT).await;
+                    if dequeued.is_ok() {
+                        mark_polled();
+                    }
+                    match dequeued {
+                        Ok(Some(task_def)) => {
+                            if let Some(bucket) = rate_limiters.get_mut(&task_def.queue) {
+                                bucket.take();
+                            }
+                            match permit {
+                                Some(permit) => match Self::claim_type_slot(&type_slots, &task_def) {
+                                    Ok(type_permit) => {
+                                        let permits = SlotPermits { _worker: permit, _task_type: type_permit };
+                                        dispatcher.dispatch(task_def, permits).await;
+                                    }
+                                    Err(_) if deferred.len() < config.max_concurrent_tasks => {
+                                        debug!("Task type {} is at its concurrency cap, holding task {}", task_def.name, task_def.id);
+                                        deferred.push_back(task_def);
+                                    }
+                                    Err(_) => {
+                                        Self::defer_task(&queue, &task_def).await;
+                                        // Don't pop the same task straight back
+                                        sleep(Duration::from_millis(config.polling_interval_ms)).await;
+                                    }
+                                },
+                                None => prefetched.push_back(task_def),
+                            }
+                        }
+                        Ok(None) => {}
+                        Err(e) if e.is_connection_error() => {}
+                        Err(e) => {
+                            error!("Blocking dequeue failed: {}", e);
+                            sleep(Duration::from_millis(config.polling_interval_ms)).await;
+                        }
+                    }
+                } else {
+                    // Try to get a task from each queue while slots (or the
+                    // prefetch buffer) have room
+                    let mut found_task = false;
+                    let mut dequeue_failed = false;
+                    for queue_name in &ready_queues {
+                        // A task held at its type's cap doesn't use up the
+                        // queue's turn, so the tasks behind it aren't starved
+                        loop {
+                            let permit = task_slots.clone().try_acquire_owned().ok();
+                            if permit.is_none() && prefetched.len() >= config.prefetch {
+                                break;
+                            }
+
+                            let task_def = match queue.get_next_task(queue_name).await {
+                                Ok(Some(task_def)) => task_def,
+                                Ok(None) => break,
+                                Err(e) => {
+                                    // The queue already logs an outage once
+                                    if !e.is_connection_error() {
+                                        error!("Failed to dequeue from queue {}: {}", queue_name, e);
+                                    }
+                                    dequeue_failed = true;
+                                    break;
+                                }
+                            };
+                            debug!("Got task {} from queue {}", task_def.id, queue_name);
+                            found_task = true;
+
+                            if let Some(bucket) = rate_limiters.get_mut(queue_name) {
+                                bucket.take();
+                            }
+                            match permit {
+                                Some(permit) => match Self::claim_type_slot(&type_slots, &task_def) {
+                                    Ok(type_permit) => {
+                                        let permits = SlotPermits { _worker: permit, _task_type: type_permit };
+                                        dispatcher.dispatch(task_def, permits).await;
+                                    }
+                                    Err(_) if deferred.len() < config.max_concurrent_tasks => {
+                                        debug!("Task type {} is at its concurrency cap, holding task {}", task_def.name, task_def.id);
+                                        deferred.push_back(task_def);
+                                        let limited = rate_limiters
+                                            .get_mut(queue_name)
+                                            .is_some_and(|bucket| !bucket.has_token());
+                                        if !limited {
+                                            continue;
+                                        }
+                                    }
+                                    Err(_) => Self::defer_task(&queue, &task_def).await,
+                                },
+                                None => prefetched.push_back(task_def),
+                            }
+                            break;
+                        }
+                    }
+
+                    if !dequeue_failed {
+                        mark_polled();
+                    }
+                    if found_task {
+                        idle_ticks = 0;
+                    } else {
+                        idle_ticks = idle_ticks.saturating_add(1);
+                    }
+                }
+            }
+
+            // Hand back tasks that were claimed but never started
+            for task_def in prefetched.into_iter().chain(deferred) {
+                if let Err(e) = queue.release_resources(&task_def).await {
+                    error!("Failed to release resources for task {}: {}", task_def.id, e);
+                }
+                if let Err(e) = queue.requeue_task(&task_def).await {
+                    error!("Failed to return held task {} to its queue: {}", task_def.id, e);
+                }
             }
 
             info!("Worker loop shutting down");
         })
     }
 
-    /// Spawn task execution in a separate task
-    async fn spawn_task_execution(
+    /// Take a slot under the task type's `per_type_concurrency` cap
+    ///
+    /// Returns `Ok(None)` if the type has no cap, and an error if it is at
+    /// its cap.
+    fn claim_type_slot(
+        type_slots: &HashMap<String, Arc<Semaphore>>,
+        task_def: &TaskDefinition,
+    ) -> Result<Option<OwnedSemaphorePermit>, TryAcquireError> {
+        match type_slots.get(&task_def.name) {
+            Some(slots) => slots.clone().try_acquire_owned().map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// Return a claimed task whose type is at its concurrency cap to its queue
+    async fn defer_task(queue: &TaskQueue, task_def: &TaskDefinition) {
+        debug!("Task type {} is at its concurrency cap, deferring task {}", task_def.name, task_def.id);
+        if let Err(e) = queue.release_resources(task_def).await {
+            error!("Failed to release resources for task {}: {}", task_def.id, e);
+        }
+        if let Err(e) = queue.requeue_task(task_def).await {
+            error!("Failed to defer task {}: {}", task_def.id, e);
+        }
+    }
+
+    /// Take the oldest deferred task whose type has a free slot, along with
+    /// that slot
+    fn take_deferred(
+        deferred: &mut VecDeque<TaskDefinition>,
+        type_slots: &HashMap<String, Arc<Semaphore>>,
+    ) -> Option<(TaskDefinition, Option<OwnedSemaphorePermit>)> {
+        let (index, type_permit) = deferred
+            .iter()
+            .enumerate()
+            .find_map(|(index, task_def)| Self::claim_type_slot(type_slots, task_def).ok().map(|permit| (index, permit)))?;
+        deferred.remove(index).map(|task_def| (task_def, type_permit))
+    }
+
+    /// Take the next prefetched task: the one with the shortest
+    /// `estimated_duration` if `shortest` is set, otherwise the oldest
+    ///
+    /// Tasks without an estimate count as the longest.
+    fn take_prefetched(prefetched: &mut VecDeque<TaskDefinition>, shortest: bool) -> Option<TaskDefinition> {
+        if !shortest {
+            return prefetched.pop_front();
+        }
+        let index = prefetched
+            .iter()
+            .enumerate()
+            .min_by_key(|(_, task_def)| task_def.estimated_duration.unwrap_or(u64::MAX))
+            .map(|(index, _)| index)?;
+        prefetched.remove(index)
+    }
+
+    /// Span covering one execution of a task
+    ///
+    /// Everything logged while the task runs, including by its handler,
+    /// carries these fields.
+    fn task_span(task_def: &TaskDefinition) -> Span {
+        let span = info_span!(
+            "task",
+            task_id = %task_def.id,
+            task_name = %task_def.name,
+            queue = %task_def.queue,
+            attempt = task_def.retry_count + 1,
+            traceparent = tracing::field::Empty,
+        );
+        if let Some(traceparent) = task_def.metadata.get(TRACEPARENT_METADATA_KEY) {
+            span.record("traceparent", traceparent.as_str());
+        }
+        span
+    }
+
+    /// Execute a task and record its outcome
+    async fn execute_task(
         mut task_def: TaskDefinition,
         handler: Arc<dyn TaskHandler>,
+        middleware: Vec<Arc<dyn Middleware>>,
         queue: Arc<TaskQueue>,
         stats: Arc<Mutex<WorkerStats>>,
         config: WorkerConfig,
-    ) -> tokio::task::JoinHandle<()> {
-        tokio::spawn(async move {
-            let start_time = std::time::Instant::now();
-            
-            // Execute task with timeout
-            let execution_result = tokio::time::timeout(
-                Duration::from_secs(config.task_timeout),
-                handler.handle(&task_def.data)
-            ).await;
-
-            let execution_duration = start_time.elapsed();
-
-            // Update statistics
-            {
-                let mut stats = stats.lock().await;
-                stats.tasks_processed += 1;
-                
-                // Update average execution time
-                let new_avg = if stats.tasks_processed == 1 {
-                    execution_duration.as_millis() as f64
-                } else {
-                    (stats.average_execution_time_ms * (stats.tasks_processed - 1) as f64 
-                        + execution_duration.as_millis() as f64) / stats.tasks_processed as f64
-                };
-                stats.average_execution_time_ms = new_avg;
+        cancellation: CancellationToken,
+    ) {
+        let start_time = std::time::Instant::now();
+        let task_timeout = task_def.timeout.unwrap_or(config.task_timeout);
+        let ctx = TaskContext::new(task_def.id, queue.clone())
+            .with_cancellation(cancellation)
+            .with_metadata(task_def.metadata.clone());
+        let ack = ctx.ack_handle();
+
+        for middleware in &middleware {
+            middleware.before(&task_def).await;
+        }
+        
+        // Execute task with timeout, reporting it once past the soft timeout
+        let execution = async {
+            // A panicking handler fails the task like an error would
+            let handle = AssertUnwindSafe(handler.handle_task(&task_def, &ctx))
+                .catch_unwind()
+                .map(|outcome| {
+                    outcome.unwrap_or_else(|panic| {
+                        Err(TaskError::task_execution(format!("handler panicked: {}", panic_message(&*panic))))
+                    })
+                })
+                .instrument(info_span!("handler"));
+            tokio::pin!(handle);
+
+            if let Some(soft_timeout) = config.soft_timeout.filter(|&secs| secs < task_timeout) {
+                tokio::select! {
+                    result = &mut handle => return result,
+                    _ = sleep(Duration::from_secs(soft_timeout)) => {
+                        warn!(
+                            "Task {} still running after soft timeout of {} seconds (hard timeout {} seconds)",
+                            task_def.id, soft_timeout, task_timeout
+                        );
+                        queue
+                            .emit_event(TaskEvent::SoftTimeout {
+                                task_id: task_def.id,
+                                queue: task_def.queue.clone(),
+                                elapsed_secs: soft_timeout,
+                            })
+                            .await;
+                    }
+                }
             }
 
-            // Handle execution result
-            match execution_result {
-                Ok(Ok(result)) => {
-                    // Task succeeded
-                    if let Err(e) = task_def.mark_success(&result) {
-                        error!("Failed to serialize task result: {}", e);
-                        task_def.mark_failed(&format!("Failed to serialize result: {}", e));
-                    }
+            handle.await
+        };
+        let execution_result =
+            tokio::time::timeout(Duration::from_secs(task_timeout), execution).await;
 
-                    let mut stats = stats.lock().await;
-                    stats.tasks_successful += 1;
+        let execution_duration = start_time.elapsed();
+
+        if !middleware.is_empty() {
+            let timed_out;
+            let outcome = match &execution_result {
+                Ok(result) => result,
+                Err(_) => {
+                    timed_out = Err(TaskError::timeout(format!("task {}", task_def.id)));
+                    &timed_out
+                }
+            };
+            for middleware in middleware.iter().rev() {
+                middleware.after(&task_def, outcome).await;
+            }
+        }
+
+        // Free any external resources held for the task
+        if let Err(e) = queue.release_resources(&task_def).await {
+            error!("Failed to release resources for task {}: {}", task_def.id, e);
+        }
 
-                    if let Err(e) = queue.mark_task_completed(&task_def).await {
-                        error!("Failed to mark task as completed: {}", e);
+        // Leave unacknowledged tasks in the processing set for redelivery
+        if config.manual_ack && !ack.is_acked() {
+            warn!(
+                "Task {} finished without an ack, leaving it to be redelivered",
+                task_def.id
+            );
+            if let Err(e) = queue.release_unacked(&task_def).await {
+                error!("Failed to release unacked task {}: {}", task_def.id, e);
+            }
+            return;
+        }
+
+        // Update statistics
+        {
+            let mut stats = stats.lock().await;
+            stats.tasks_processed += 1;
+            
+            // Update average execution time
+            let new_avg = if stats.tasks_processed == 1 {
+                execution_duration.as_millis() as f64
+            } else {
+                (stats.average_execution_time_ms * (stats.tasks_processed - 1) as f64 
+                    + execution_duration.as_millis() as f64) / stats.tasks_processed as f64
+            };
+            stats.average_execution_time_ms = new_avg;
+        }
+
+        // Handle execution result
+        match execution_result {
+            Ok(Ok(result)) => {
+                // Task succeeded; the handler already serialized its output
+                task_def.mark_success_serialized(result.clone());
+
+                stats.lock().await.tasks_successful += 1;
+
+                if let Err(e) = queue.mark_task_completed(&task_def).await {
+                    error!("Failed to mark task as completed: {}", e);
+                }
+                queue
+                    .emit_event(TaskEvent::Succeeded {
+                        task_id: task_def.id,
+                        queue: task_def.queue.clone(),
+                    })
+                    .await;
+                Self::send_callback(&task_def);
+
+                // Enqueue the next task in the chain, if any
+                match handler.on_success(&task_def, &result) {
+                    Ok(Some(next_task)) => {
+                        match queue.submit_follow_up(&task_def, next_task).await {
+                            Ok(next_id) => info!("Task {} triggered follow-up task {}", task_def.id, next_id),
+                            Err(e) => error!("Failed to submit follow-up of task {}: {}", task_def.id, e),
+                        }
                     }
+                    Ok(None) => {}
+                    Err(e) => error!("Failed to build follow-up of task {}: {}", task_def.id, e),
+                }
 
-                    info!("Task {} completed successfully in {:?}", task_def.id, execution_duration);
-                }
-                Ok(Err(e)) => {
-                    // Task failed
-                    let error_msg = e.to_string();
-                    error!("Task {} failed: {}", task_def.id, error_msg);
-
-                    // Try to retry if configured and possible
-                    if config.auto_retry && task_def.can_retry() {
-                        if let Ok(()) = task_def.mark_retry() {
-                            if let Err(e) = queue.requeue_task(&task_def).await {
-                                error!("Failed to requeue task for retry: {}", e);
-                                task_def.mark_failed(&error_msg);
-                                if let Err(e) = queue.mark_task_failed(&task_def).await {
-                                    error!("Failed to mark task as failed: {}", e);
-                                }
-                            } else {
-                                let mut stats = stats.lock().await;
-                                stats.tasks_retried += 1;
-                                info!("Task {} queued for retry (attempt {})", task_def.id, task_def.retry_count);
-                                return;
+                info!("Task {} completed successfully in {:?}", task_def.id, execution_duration);
+            }
+            // The queue lost Redis under the task; it is not the task's fault.
+            // Connection errors from the task's own services fail it as usual.
+            Ok(Err(e)) if e.is_connection_error() && queue.reconnect_delay().is_some() => {
+                warn!(
+                    "Task {} lost its Redis connection, leaving it to be redelivered: {}",
+                    task_def.id, e
+                );
+            }
+            Ok(Err(e)) => {
+                // Task failed
+                let mut error_msg = e.to_string();
+                error!("Task {} failed: {}", task_def.id, error_msg);
+
+                // Try to retry if configured and possible
+                let retryable = handler.is_retryable(&e);
+                if !retryable {
+                    info!("Task {} failed with a non-retryable error", task_def.id);
+                }
+                if config.auto_retry && retryable && task_def.can_retry() {
+                    if let Ok(()) = task_def.mark_retry(&error_msg) {
+                        if let Err(e) = queue.check_chain_depth(&task_def) {
+                            error!("Not retrying task {}: {}", task_def.id, e);
+                            error_msg = e.to_string();
+                        } else if let Err(e) = queue.requeue_task(&task_def).await {
+                            error!("Failed to requeue task for retry: {}", e);
+                            task_def.mark_failed(&error_msg);
+                            if let Err(e) = queue.mark_task_failed(&task_def).await {
+                                error!("Failed to mark task as failed: {}", e);
                             }
+                        } else {
+                            stats.lock().await.tasks_retried += 1;
+                            info!("Task {} queued for retry (attempt {})", task_def.id, task_def.retry_count);
+                            queue
+                                .emit_event(TaskEvent::Retried {
+                                    task_id: task_def.id,
+                                    queue: task_def.queue.clone(),
+                                    attempt: task_def.retry_count,
+                                })
+                                .await;
+                            return;
                         }
                     }
+                }
 
-                    task_def.mark_failed(&error_msg);
-                    let mut stats = stats.lock().await;
-                    stats.tasks_failed += 1;
+                task_def.mark_failed(&error_msg);
+                stats.lock().await.tasks_failed += 1;
 
-                    if let Err(e) = queue.mark_task_failed(&task_def).await {
-                        error!("Failed to mark task as failed: {}", e);
-                    }
+                if let Err(e) = queue.mark_task_failed(&task_def).await {
+                    error!("Failed to mark task as failed: {}", e);
                 }
-                Err(_) => {
-                    // Task timed out
-                    let error_msg = format!("Task execution timed out after {} seconds", config.task_timeout);
-                    error!("Task {} timed out", task_def.id);
+                Self::emit_failed(&queue, &task_def).await;
+                Self::send_callback(&task_def);
+            }
+            Err(_) => {
+                // Task timed out
+                let error_msg = format!("Task execution timed out after {} seconds", task_timeout);
+                error!("Task {} timed out", task_def.id);
 
-                    task_def.mark_failed(&error_msg);
-                    let mut stats = stats.lock().await;
-                    stats.tasks_failed += 1;
+                task_def.mark_failed(&error_msg);
+                stats.lock().await.tasks_failed += 1;
 
-                    if let Err(e) = queue.mark_task_failed(&task_def).await {
-                        error!("Failed to mark task as failed: {}", e);
-                    }
+                if let Err(e) = queue.mark_task_failed(&task_def).await {
+                    error!("Failed to mark task as failed: {}", e);
                 }
+                Self::emit_failed(&queue, &task_def).await;
+                Self::send_callback(&task_def);
             }
-        })
+        }
     }
 
-    /// Clean up completed task handles
-    async fn cleanup_completed_tasks(active_tasks: &Arc<RwLock<HashMap<Uuid, tokio::task::JoinHandle<()>>>>) {
-        let mut tasks = active_tasks.write().await;
-        let mut completed_ids = Vec::new();
+    /// Emit a `Failed` event for a task marked as failed
+    async fn emit_failed(queue: &TaskQueue, task_def: &TaskDefinition) {
+        queue
+            .emit_event(TaskEvent::Failed {
+                task_id: task_def.id,
+                queue: task_def.queue.clone(),
+                error: task_def.error.clone().unwrap_or_default(),
+            })
+            .await;
+    }
 
-        for (task_id, handle) in tasks.iter() {
-            if handle.is_finished() {
-                completed_ids.push(*task_id);
-            }
+    /// POST a finished task's outcome to its callback URL, if it has one
+    fn send_callback(task_def: &TaskDefinition) {
+        #[cfg(feature = "webhooks")]
+        crate::webhooks::spawn_callback(task_def);
+
+        #[cfg(not(feature = "webhooks"))]
+        if task_def.callback_url.is_some() {
+            warn!("Task {} has a callback URL, but the `webhooks` feature is disabled", task_def.id);
         }
+    }
+
+    /// Collect the results of finished task executions
+    async fn reap_finished_tasks(active_tasks: &Mutex<JoinSet<()>>) {
+        let mut tasks = active_tasks.lock().await;
 
-        for task_id in completed_ids {
-            tasks.remove(&task_id);
+        while let Some(result) = tasks.try_join_next() {
+            if let Err(e) = result {
+                if e.is_panic() {
+                    error!("Task execution panicked: {}", e);
+                }
+            }
         }
     }
 
     /// Start heartbeat task
     async fn start_heartbeat_task(&self) -> tokio::task::JoinHandle<()> {
         let config = self.config.clone();
+        let queue = self.queue.clone();
         let stats = self.stats.clone();
         let shutdown_signal = self.shutdown_signal.clone();
+        let running_task_ids = self.running_task_ids.clone();
+        let stop_loops = self.stop_loops.clone();
 
         tokio::spawn(async move {
             let mut interval = interval(Duration::from_secs(config.heartbeat_interval));
+            // Outlive the interval by half again so one late beat doesn't expire the record
+            let record_ttl = config.heartbeat_interval + config.heartbeat_interval / 2 + 1;
 
             loop {
-                interval.tick().await;
+                tokio::select! {
+                    _ = interval.tick() => {}
+                    _ = stop_loops.cancelled() => break,
+                }
 
                 if *shutdown_signal.read().await {
                     break;
                 }
 
                 // Update heartbeat timestamp
+                let now = chrono::Utc::now();
                 {
                     let mut stats = stats.lock().await;
-                    stats.last_heartbeat = Some(chrono::Utc::now());
+                    stats.last_heartbeat = Some(now);
+                }
+
+                let record = WorkerRecord {
+                    worker_id: config.worker_id.to_string(),
+                    queues: config.queues.clone(),
+                    active_tasks: lock_running(&running_task_ids).len(),
+                    last_heartbeat: now,
+                };
+                if !queue.uses_redis() {
+                    continue;
+                }
+                if let Err(e) = queue.record_worker_heartbeat(&record, record_ttl).await {
+                    warn!("Failed to persist heartbeat for worker {}: {}", config.worker_id, e);
                 }
 
                 debug!("Worker {} heartbeat", config.worker_id);
@@ -394,34 +1637,49 @@ impl Worker {
     async fn start_scheduler_task(&self) -> tokio::task::JoinHandle<()> {
         let queue = self.queue.clone();
         let shutdown_signal = self.shutdown_signal.clone();
+        let stop_loops = self.stop_loops.clone();
 
         tokio::spawn(async move {
             let mut interval = interval(Duration::from_secs(10)); // Check every 10 seconds
 
             loop {
-                interval.tick().await;
+                tokio::select! {
+                    _ = interval.tick() => {}
+                    _ = stop_loops.cancelled() => break,
+                }
 
                 if *shutdown_signal.read().await {
                     break;
                 }
 
-                if let Err(e) = queue.process_scheduled_tasks().await {
+                if let Err(e) = queue.process_scheduled_tasks(SCHEDULED_TASKS_PER_TICK).await {
                     error!("Failed to process scheduled tasks: {}", e);
                 }
             }
         })
     }
 
-    /// Start cleanup task
+    /// Start cleanup task (gives up on tasks abandoned past `processing_max_age`)
+    ///
+    /// Only Redis queues have abandoned tasks to clean up; on other backends
+    /// the loop just waits for shutdown.
     async fn start_cleanup_task(&self) -> tokio::task::JoinHandle<()> {
         let queue = self.queue.clone();
         let shutdown_signal = self.shutdown_signal.clone();
+        let stop_loops = self.stop_loops.clone();
 
         tokio::spawn(async move {
-            let mut interval = interval(Duration::from_secs(3600)); // Check every hour
+            if !queue.uses_redis() {
+                stop_loops.cancelled().await;
+                return;
+            }
+            let mut interval = interval(Duration::from_secs(queue.config().cleanup_interval.max(1)));
 
             loop {
-                interval.tick().await;
+                tokio::select! {
+                    _ = interval.tick() => {}
+                    _ = stop_loops.cancelled() => break,
+                }
 
                 if *shutdown_signal.read().await {
                     break;
@@ -434,46 +1692,248 @@ impl Worker {
         })
     }
 
+    /// Start reclaim task (re-queues tasks abandoned by crashed workers)
+    ///
+    /// Like the cleanup loop, this only runs on Redis queues.
+    async fn start_reclaim_task(&self) -> tokio::task::JoinHandle<()> {
+        let queue = self.queue.clone();
+        let shutdown_signal = self.shutdown_signal.clone();
+        let stop_loops = self.stop_loops.clone();
+
+        tokio::spawn(async move {
+            if !queue.uses_redis() {
+                stop_loops.cancelled().await;
+                return;
+            }
+            let mut interval = interval(Duration::from_secs(60)); // Check every minute
+            let visibility_timeout = queue.config().visibility_timeout;
+
+            loop {
+                tokio::select! {
+                    _ = interval.tick() => {}
+                    _ = stop_loops.cancelled() => break,
+                }
+
+                if *shutdown_signal.read().await {
+                    break;
+                }
+
+                if let Err(e) = queue.reclaim_dead_worker_tasks().await {
+                    error!("Failed to reclaim tasks of dead workers: {}", e);
+                }
+                if let Err(e) = queue.reclaim_stalled_tasks(visibility_timeout).await {
+                    error!("Failed to reclaim stalled tasks: {}", e);
+                }
+            }
+        })
+    }
+
+    /// Wait for SIGINT (or SIGTERM on Unix)
+    ///
+    /// Never resolves if the signal handlers cannot be installed.
+    async fn wait_for_os_signal() {
+        #[cfg(unix)]
+        {
+            use tokio::signal::unix::{signal, SignalKind};
+
+            match signal(SignalKind::terminate()) {
+                Ok(mut sigterm) => {
+                    tokio::select! {
+                        _ = tokio::signal::ctrl_c() => {}
+                        _ = sigterm.recv() => {}
+                    }
+                    return;
+                }
+                Err(e) => warn!("Failed to install SIGTERM handler: {}", e),
+            }
+        }
+
+        if let Err(e) = tokio::signal::ctrl_c().await {
+            warn!("Failed to install SIGINT handler: {}", e);
+            std::future::pending::<()>().await;
+        }
+    }
+
     /// Get worker statistics
     pub async fn get_stats(&self) -> WorkerStats {
         self.stats.lock().await.clone()
     }
 
+    /// Whether the worker is running normally, for liveness probes
+    ///
+    /// Unhealthy if the main loop has not polled the queues successfully
+    /// within `stall_threshold` seconds (including before `start`), or if
+    /// Redis is unreachable. A worker that is full or draining counts as
+    /// polling.
+    pub async fn is_healthy(&self) -> bool {
+        let last_poll = *self.last_poll.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        let stall_threshold = Duration::from_secs(self.config.stall_threshold);
+        match last_poll {
+            Some(last_poll) if last_poll.elapsed() <= stall_threshold => {}
+            Some(last_poll) => {
+                warn!(
+                    "Worker {} has not polled for {:?}",
+                    self.config.worker_id,
+                    last_poll.elapsed()
+                );
+                return false;
+            }
+            None => return false,
+        }
+
+        match self.queue.health_check().await {
+            Ok(status) => status.healthy,
+            Err(_) => false,
+        }
+    }
+
+    /// Number of tasks the worker is executing
+    pub fn active_task_count(&self) -> usize {
+        lock_running(&self.running_task_ids).len()
+    }
+
+    /// Ids of the tasks the worker is executing
+    pub fn active_task_ids(&self) -> Vec<TaskId> {
+        lock_running(&self.running_task_ids).iter().copied().collect()
+    }
+
+    /// Stop taking new tasks without shutting down
+    ///
+    /// Running and prefetched tasks still finish, and heartbeats, scheduled
+    /// task processing and cleanup keep going. Call `resume` to take tasks
+    /// again, or `signal_shutdown` to stop the worker.
+    pub async fn drain(&self) {
+        *self.draining.write().await = true;
+        info!("Worker {} is draining", self.config.worker_id);
+    }
+
+    /// Take new tasks again after `drain`
+    pub async fn resume(&self) {
+        *self.draining.write().await = false;
+        info!("Worker {} resumed taking tasks", self.config.worker_id);
+    }
+
+    /// Whether the worker is draining (see `drain`)
+    pub async fn is_draining(&self) -> bool {
+        *self.draining.read().await
+    }
+
     /// Signal worker to shutdown
+    ///
+    /// `start` returns once the worker has shut down.
     pub async fn signal_shutdown(&self) {
         let mut shutdown = self.shutdown_signal.write().await;
         *shutdown = true;
+        self.stop_loops.cancel();
     }
 
-    /// Graceful shutdown
-    async fn shutdown(&self) -> TaskResult<()> {
+    /// Graceful shutdown, given the handles of the worker's background loops
+    async fn shutdown(&self, loops: Vec<tokio::task::JoinHandle<()>>) -> TaskResult<()> {
         info!("Shutting down worker {}", self.config.worker_id);
 
         // Signal shutdown
         self.signal_shutdown().await;
 
-        // Wait for active tasks to complete or timeout
-        let start = std::time::Instant::now();
-        let grace_period = Duration::from_secs(self.config.shutdown_grace_period);
+        // Wait for the loops to exit, so none dequeues or spawns anything
+        // past this point. The one that ended `start` was already awaited.
+        for handle in loops.into_iter().filter(|handle| !handle.is_finished()) {
+            if let Err(e) = handle.await {
+                if e.is_panic() {
+                    error!("Worker loop panicked: {}", e);
+                }
+            }
+        }
 
-        while start.elapsed() < grace_period {
-            let active_count = self.active_tasks.read().await.len();
-            if active_count == 0 {
-                break;
+        // Ask running tasks to stop, then wait for them to complete or timeout
+        self.cancellation.cancel();
+        let grace_period = Duration::from_secs(self.config.shutdown_grace_period);
+        let mut active_tasks = self.active_tasks.lock().await;
+        debug!("Waiting for {} active tasks to complete", active_tasks.len());
+
+        let drain = async {
+            while let Some(result) = active_tasks.join_next().await {
+                if let Err(e) = result {
+                    if e.is_panic() {
+                        error!("Task execution panicked: {}", e);
+                    }
+                }
             }
+        };
 
-            debug!("Waiting for {} active tasks to complete", active_count);
-            sleep(Duration::from_millis(500)).await;
+        // Abort tasks that did not stop within the grace period
+        if tokio::time::timeout(grace_period, drain).await.is_err() {
+            warn!("Force stopping {} tasks", active_tasks.len());
+            active_tasks.shutdown().await;
         }
 
-        // Force shutdown remaining tasks
-        let active_tasks = self.active_tasks.read().await;
-        for (task_id, handle) in active_tasks.iter() {
-            warn!("Force stopping task {}", task_id);
-            handle.abort();
+        if self.queue.uses_redis() {
+            if let Err(e) = self.queue.remove_worker(&self.config.worker_id.to_string()).await {
+                warn!("Failed to remove worker record: {}", e);
+            }
         }
 
         info!("Worker {} shut down complete", self.config.worker_id);
         Ok(())
     }
-} 
\ No newline at end of file
+} 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test(start_paused = true)]
+    async fn token_bucket_limits_sustained_rate() {
+        let mut bucket = TokenBucket::new(5);
+        let started = tokio::time::Instant::now();
+        let mut taken = 0;
+        while taken < 20 {
+            if bucket.has_token() {
+                bucket.take();
+                taken += 1;
+            } else {
+                sleep(Duration::from_millis(5)).await;
+            }
+        }
+
+        // A burst of 5, then the other 15 at 5 per second
+        let elapsed = started.elapsed();
+        assert!(elapsed >= Duration::from_millis(2900), "took only {:?}", elapsed);
+        assert!(elapsed < Duration::from_millis(3100), "took {:?}", elapsed);
+    }
+
+    #[test]
+    fn prefetched_tasks_start_oldest_first_by_default() {
+        let estimated = |seconds| {
+            let mut task_def = TaskDefinition::from_raw("job", "{}".to_string(), "default".to_string());
+            task_def.estimated_duration = seconds;
+            task_def
+        };
+        let mut prefetched: VecDeque<TaskDefinition> = [estimated(None), estimated(Some(60)), estimated(Some(1))].into();
+        let ids: Vec<TaskId> = prefetched.iter().map(|task_def| task_def.id).collect();
+
+        let shortest = WorkerConfig::default().duration_aware;
+        let started: Vec<TaskId> = std::iter::from_fn(|| Worker::take_prefetched(&mut prefetched, shortest))
+            .map(|task_def| task_def.id)
+            .collect();
+        assert_eq!(started, ids);
+    }
+
+    #[test]
+    fn round_robin_spreads_first_pick_evenly() {
+        let queues: Vec<String> = ["high", "default", "low"].iter().map(|q| q.to_string()).collect();
+        let mut first_picks: HashMap<String, usize> = HashMap::new();
+
+        for tick in 0..300 {
+            let order = QueueStrategy::RoundRobin.order(&queues, tick);
+            let mut sorted = order.clone();
+            sorted.sort();
+            let mut expected = queues.clone();
+            expected.sort();
+            assert_eq!(sorted, expected, "tick {} dropped or repeated a queue", tick);
+            *first_picks.entry(order[0].clone()).or_default() += 1;
+        }
+
+        for queue_name in &queues {
+            assert_eq!(first_picks[queue_name], 100, "{} first {:?}", queue_name, first_picks);
+        }
+    }
+}
@@ -0,0 +1,716 @@
+//! End-to-end tests against the in-memory backend
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use distributed_task_queue::task::RetryConfig;
+use distributed_task_queue::worker::TaskHandler;
+use distributed_task_queue::{Task, TaskDefinition, TaskError, TaskId, TaskPriority, TaskQueue, TaskResult, TaskStatus, Worker, WorkerConfig};
+use serde::{Deserialize, Serialize};
+use tokio::task::JoinHandle;
+
+fn worker_config(queue: &str) -> WorkerConfig {
+    WorkerConfig {
+        queues: vec![queue.to_string()],
+        polling_interval_ms: 10,
+        handle_os_signals: false,
+        ..Default::default()
+    }
+}
+
+fn start(worker: &Arc<Worker>) -> JoinHandle<()> {
+    let worker = worker.clone();
+    tokio::spawn(async move { worker.start().await.unwrap() })
+}
+
+async fn stop(worker: &Worker, running: JoinHandle<()>) {
+    worker.signal_shutdown().await;
+    running.await.unwrap();
+}
+
+/// Poll the task until it has one of `statuses`, panicking after five seconds
+async fn wait_for_status(queue: &TaskQueue, task_id: TaskId, statuses: &[TaskStatus]) -> TaskDefinition {
+    tokio::time::timeout(Duration::from_secs(5), async {
+        loop {
+            if let Some(task_def) = queue.get_task(task_id).await.unwrap() {
+                if statuses.contains(&task_def.status) {
+                    return task_def;
+                }
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    })
+    .await
+    .unwrap_or_else(|_| panic!("task {} never reached {:?}", task_id, statuses))
+}
+
+/// Halves even numbers; odd ones produce an output that fails validation
+#[derive(Debug, Serialize, Deserialize)]
+struct Halve {
+    n: u32,
+}
+
+#[async_trait::async_trait]
+impl Task for Halve {
+    type Output = f64;
+    type Error = TaskError;
+
+    async fn execute(&self) -> Result<Self::Output, Self::Error> {
+        Ok(self.n as f64 / 2.0)
+    }
+
+    fn validate_output(&self, output: &Self::Output) -> Result<(), Self::Error> {
+        if output.fract() == 0.0 {
+            Ok(())
+        } else {
+            Err(TaskError::task_execution(format!("{} is not a whole number", output)))
+        }
+    }
+}
+
+#[tokio::test]
+async fn invalid_output_fails_the_task_without_storing_a_result() {
+    let queue = Arc::new(TaskQueue::new_in_memory(Default::default()).unwrap());
+    let worker = Arc::new(Worker::new(worker_config("halve"), queue.clone()));
+    worker.register_task::<Halve>().await;
+
+    let valid = TaskDefinition::new(&Halve { n: 4 }, "halve".to_string()).unwrap();
+    let retried = TaskDefinition::new(&Halve { n: 3 }, "halve".to_string()).unwrap();
+    let failed = TaskDefinition::builder(&Halve { n: 5 })
+        .unwrap()
+        .queue("halve")
+        .retry_config(RetryConfig {
+            max_retries: 0,
+            ..Default::default()
+        })
+        .build();
+    for task_def in [&valid, &retried, &failed] {
+        queue.submit_task(task_def.clone()).await.unwrap();
+    }
+
+    let running = start(&worker);
+    let valid_done = wait_for_status(&queue, valid.id, &[TaskStatus::Success]).await;
+    let retried_done = wait_for_status(&queue, retried.id, &[TaskStatus::Retrying]).await;
+    let failed_done = wait_for_status(&queue, failed.id, &[TaskStatus::Failed]).await;
+    stop(&worker, running).await;
+
+    assert_eq!(valid_done.result.as_deref(), Some("2.0"));
+    assert!(queue.get_result(valid.id).await.unwrap().is_some());
+    for task_def in [&retried_done, &failed_done] {
+        assert!(task_def.result.is_none());
+        assert!(task_def.attempts[0].error.contains("is not a whole number"));
+    }
+    // Only the final failure is recorded as an outcome, with no output
+    assert!(queue.get_result(retried.id).await.unwrap().is_none());
+    let outcome = queue.get_result(failed.id).await.unwrap().unwrap();
+    assert_eq!(outcome.status, TaskStatus::Failed);
+    assert!(outcome.result.is_none());
+}
+
+#[tokio::test]
+async fn cancel_only_succeeds_while_the_task_is_waiting() {
+    let queue = TaskQueue::new_in_memory(Default::default()).unwrap();
+    let cancelled = TaskDefinition::new(&Halve { n: 2 }, "halve".to_string()).unwrap();
+    let claimed = TaskDefinition::new(&Halve { n: 4 }, "halve".to_string()).unwrap();
+    queue.submit_task(cancelled.clone()).await.unwrap();
+    queue.submit_task(claimed.clone()).await.unwrap();
+
+    assert!(queue.cancel_task(cancelled.id).await.unwrap());
+    assert!(!queue.cancel_task(cancelled.id).await.unwrap());
+    let next = queue.get_next_task("halve").await.unwrap().unwrap();
+    assert_eq!(next.id, claimed.id);
+    assert!(!queue.cancel_task(claimed.id).await.unwrap());
+    assert!(queue.get_next_task("halve").await.unwrap().is_none());
+    let stored = queue.get_task(cancelled.id).await.unwrap().unwrap();
+    assert_eq!(stored.status, TaskStatus::Cancelled);
+}
+
+/// First step of a chain: counts words and hands the count to `Double`
+#[derive(Debug, Serialize, Deserialize)]
+struct CountWords {
+    text: String,
+    next_id: TaskId,
+}
+
+#[async_trait::async_trait]
+impl Task for CountWords {
+    type Output = usize;
+    type Error = TaskError;
+
+    async fn execute(&self) -> Result<Self::Output, Self::Error> {
+        Ok(self.text.split_whitespace().count())
+    }
+
+    fn on_success(&self, output: &str) -> Option<TaskDefinition> {
+        let next = Double {
+            n: output.parse().ok()?,
+        };
+        TaskDefinition::with_id(&next, "chain".to_string(), self.next_id).ok()
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Double {
+    n: usize,
+}
+
+#[async_trait::async_trait]
+impl Task for Double {
+    type Output = usize;
+    type Error = TaskError;
+
+    async fn execute(&self) -> Result<Self::Output, Self::Error> {
+        Ok(self.n * 2)
+    }
+}
+
+#[tokio::test]
+async fn chain_runs_the_follow_up_with_the_first_output() {
+    let queue = Arc::new(TaskQueue::new_in_memory(Default::default()).unwrap());
+    let worker = Arc::new(Worker::new(worker_config("chain"), queue.clone()));
+    worker.register_task::<CountWords>().await;
+    worker.register_task::<Double>().await;
+
+    let first = CountWords {
+        text: "one two three".to_string(),
+        next_id: TaskId::new_v4(),
+    };
+    let first_def = TaskDefinition::new(&first, "chain".to_string()).unwrap();
+    queue.submit_task(first_def.clone()).await.unwrap();
+
+    let running = start(&worker);
+    let second = wait_for_status(&queue, first.next_id, &[TaskStatus::Success]).await;
+    stop(&worker, running).await;
+
+    assert_eq!(second.result.as_deref(), Some("6"));
+    assert_eq!(second.parent_id, Some(first_def.id));
+    assert_eq!(second.chain_depth, 1);
+    let first_done = queue.get_task(first_def.id).await.unwrap().unwrap();
+    assert_eq!(first_done.status, TaskStatus::Success);
+    assert_eq!(first_done.result.as_deref(), Some("3"));
+}
+
+#[tokio::test]
+async fn self_chaining_task_stops_at_max_chain_depth() {
+    let config = distributed_task_queue::TaskQueueConfig {
+        max_chain_depth: Some(3),
+        ..Default::default()
+    };
+    let queue = TaskQueue::new_in_memory(config).unwrap();
+    let mut parent = TaskDefinition::new(&Double { n: 1 }, "chain".to_string()).unwrap();
+    let stopped = loop {
+        let next = TaskDefinition::new(&Double { n: 1 }, String::new()).unwrap();
+        let next_id = next.id;
+        match queue.submit_follow_up(&parent, next).await {
+            Ok(_) => parent = queue.get_next_task("chain").await.unwrap().unwrap(),
+            Err(_) => break queue.get_task(next_id).await.unwrap().unwrap(),
+        }
+    };
+
+    assert_eq!(stopped.chain_depth, 4);
+    assert_eq!(stopped.status, TaskStatus::Failed);
+    assert_eq!(queue.get_stats("chain").await.unwrap().pending_tasks, 0);
+}
+
+#[tokio::test]
+async fn retries_count_towards_max_chain_depth() {
+    let config = distributed_task_queue::TaskQueueConfig {
+        max_chain_depth: Some(2),
+        ..Default::default()
+    };
+    let queue = Arc::new(TaskQueue::new_in_memory(config).unwrap());
+    let worker = Arc::new(Worker::new(worker_config("chain-retries"), queue.clone()));
+    worker.register_task::<Halve>().await;
+    // Always fails validation, with retries to spare
+    let task_def = TaskDefinition::builder(&Halve { n: 1 })
+        .unwrap()
+        .queue("chain-retries")
+        .retry_config(RetryConfig {
+            max_retries: 10,
+            retry_delay: 0,
+            exponential_backoff: false,
+            ..Default::default()
+        })
+        .build();
+    queue.submit_task(task_def.clone()).await.unwrap();
+
+    let running = start(&worker);
+    let failed = wait_for_status(&queue, task_def.id, &[TaskStatus::Failed]).await;
+    stop(&worker, running).await;
+
+    assert_eq!(failed.chain_depth, 3);
+    assert_eq!(failed.attempts.len(), 3);
+    assert!(failed.error.unwrap().contains("max_chain_depth"));
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn concurrent_dequeues_never_hand_out_a_task_twice() {
+    let queue = Arc::new(TaskQueue::new_in_memory(Default::default()).unwrap());
+    let mut submitted = Vec::new();
+    for n in 0..200 {
+        let task_def = TaskDefinition::new(&Double { n }, "race".to_string()).unwrap();
+        submitted.push(task_def.id);
+        queue.submit_task(task_def).await.unwrap();
+    }
+
+    let consumers: Vec<_> = (0..8)
+        .map(|_| {
+            let queue = queue.clone();
+            tokio::spawn(async move {
+                let mut taken = Vec::new();
+                while let Some(task_def) = queue.get_next_task("race").await.unwrap() {
+                    taken.push(task_def.id);
+                    tokio::task::yield_now().await;
+                }
+                taken
+            })
+        })
+        .collect();
+    let mut taken = Vec::new();
+    for consumer in consumers {
+        taken.extend(consumer.await.unwrap());
+    }
+
+    assert_eq!(taken.len(), submitted.len());
+    taken.sort();
+    submitted.sort();
+    assert_eq!(taken, submitted);
+}
+
+#[tokio::test]
+async fn retried_task_stays_hidden_until_its_backoff_elapses() {
+    let queue = TaskQueue::new_in_memory(Default::default()).unwrap();
+    let task_def = TaskDefinition::builder(&Double { n: 1 })
+        .unwrap()
+        .queue("retry")
+        .retry_config(RetryConfig {
+            retry_delay: 1,
+            exponential_backoff: false,
+            ..Default::default()
+        })
+        .build();
+    queue.submit_task(task_def.clone()).await.unwrap();
+
+    let mut claimed = queue.get_next_task("retry").await.unwrap().unwrap();
+    claimed.mark_retry("flaky").unwrap();
+    queue.requeue_task(&claimed).await.unwrap();
+
+    for _ in 0..3 {
+        queue.process_scheduled_tasks(100).await.unwrap();
+        assert!(queue.get_next_task("retry").await.unwrap().is_none());
+        tokio::time::sleep(Duration::from_millis(250)).await;
+    }
+
+    tokio::time::sleep(Duration::from_millis(500)).await;
+    queue.process_scheduled_tasks(100).await.unwrap();
+    let retried = queue.get_next_task("retry").await.unwrap().unwrap();
+    assert_eq!(retried.id, task_def.id);
+    assert_eq!(retried.retry_count, 1);
+}
+
+static FLAKY_RUNS: AtomicUsize = AtomicUsize::new(0);
+
+/// Fails its first run and succeeds after that
+#[derive(Debug, Serialize, Deserialize)]
+struct Flaky;
+
+#[async_trait::async_trait]
+impl Task for Flaky {
+    type Output = ();
+    type Error = TaskError;
+
+    async fn execute(&self) -> Result<Self::Output, Self::Error> {
+        if FLAKY_RUNS.fetch_add(1, Ordering::SeqCst) == 0 {
+            return Err(TaskError::task_execution("flaky"));
+        }
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn worker_holds_no_slot_while_a_retry_backs_off() {
+    let queue = Arc::new(TaskQueue::new_in_memory(Default::default()).unwrap());
+    let worker = Arc::new(Worker::new(worker_config("backoff"), queue.clone()));
+    worker.register_task::<Flaky>().await;
+    let task_def = TaskDefinition::builder(&Flaky)
+        .unwrap()
+        .queue("backoff")
+        .retry_config(RetryConfig {
+            retry_delay: 1,
+            exponential_backoff: false,
+            ..Default::default()
+        })
+        .build();
+    queue.submit_task(task_def.clone()).await.unwrap();
+
+    let running = start(&worker);
+    wait_for_status(&queue, task_def.id, &[TaskStatus::Retrying]).await;
+    let failed_at = std::time::Instant::now();
+
+    // The failed run frees its slot rather than sleeping out the delay
+    tokio::time::timeout(Duration::from_millis(500), async {
+        while worker.active_task_count() > 0 {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    })
+    .await
+    .expect("worker kept the failed task running");
+    while failed_at.elapsed() < Duration::from_millis(700) {
+        queue.process_scheduled_tasks(100).await.unwrap();
+        assert_eq!(worker.active_task_count(), 0);
+        assert_eq!(FLAKY_RUNS.load(Ordering::SeqCst), 1);
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+
+    tokio::time::sleep(Duration::from_millis(1100).saturating_sub(failed_at.elapsed())).await;
+    queue.process_scheduled_tasks(100).await.unwrap();
+    let done = wait_for_status(&queue, task_def.id, &[TaskStatus::Success]).await;
+    stop(&worker, running).await;
+
+    assert_eq!(done.retry_count, 1);
+    assert_eq!(FLAKY_RUNS.load(Ordering::SeqCst), 2);
+}
+
+#[tokio::test]
+async fn submit_new_task_rejects_the_id_of_a_failed_task() {
+    let queue = TaskQueue::new_in_memory(Default::default()).unwrap();
+    let task_id = TaskId::new_v4();
+    let task_def = TaskDefinition::with_id(&Double { n: 1 }, "ids".to_string(), task_id).unwrap();
+    queue.submit_new_task(task_def.clone()).await.unwrap();
+
+    let mut claimed = queue.get_next_task("ids").await.unwrap().unwrap();
+    claimed.mark_failed("boom");
+    queue.mark_task_failed(&claimed).await.unwrap();
+
+    let resubmitted = queue.submit_new_task(task_def).await;
+    assert!(matches!(resubmitted, Err(TaskError::TaskExists { .. })), "{:?}", resubmitted);
+}
+
+#[tokio::test]
+async fn result_that_looks_compressed_is_returned_verbatim() {
+    let queue = TaskQueue::new_in_memory(Default::default()).unwrap();
+    let task_def = TaskDefinition::new(&Double { n: 1 }, "results".to_string()).unwrap();
+    queue.submit_task(task_def.clone()).await.unwrap();
+
+    let output = "\u{1}gz:not compressed at all".to_string();
+    let mut claimed = queue.get_next_task("results").await.unwrap().unwrap();
+    claimed.mark_success_serialized(output.clone());
+    queue.mark_task_completed(&claimed).await.unwrap();
+
+    let stored = queue.get_result(task_def.id).await.unwrap().unwrap();
+    assert_eq!(stored.result, Some(output.clone()));
+    let task = queue.get_task(task_def.id).await.unwrap().unwrap();
+    assert_eq!(task.result, Some(output));
+}
+
+#[cfg(feature = "compression")]
+#[tokio::test]
+async fn compressed_results_round_trip() {
+    let config = distributed_task_queue::TaskQueueConfig {
+        compress_results: true,
+        compression_threshold: 64,
+        ..Default::default()
+    };
+    let queue = TaskQueue::new_in_memory(config).unwrap();
+    for output in ["short".to_string(), "long enough to compress ".repeat(20)] {
+        let task_def = TaskDefinition::new(&Double { n: 1 }, "results".to_string()).unwrap();
+        queue.submit_task(task_def.clone()).await.unwrap();
+        let mut claimed = queue.get_next_task("results").await.unwrap().unwrap();
+        claimed.mark_success_serialized(output.clone());
+        queue.mark_task_completed(&claimed).await.unwrap();
+
+        let stored = queue.get_result(task_def.id).await.unwrap().unwrap();
+        assert_eq!(stored.result, Some(output));
+        assert!(!stored.result_compressed);
+    }
+}
+
+/// Never acknowledges, so in manual-ack mode it is redelivered until the cap
+#[derive(Debug, Serialize, Deserialize)]
+struct NeverAcked;
+
+#[async_trait::async_trait]
+impl Task for NeverAcked {
+    type Output = ();
+    type Error = TaskError;
+
+    async fn execute(&self) -> Result<Self::Output, Self::Error> {
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn unacked_task_is_failed_after_max_redeliveries() {
+    let config = distributed_task_queue::TaskQueueConfig {
+        max_redeliveries: Some(3),
+        ..Default::default()
+    };
+    let queue = Arc::new(TaskQueue::new_in_memory(config).unwrap());
+    let worker = Arc::new(Worker::new(
+        WorkerConfig {
+            manual_ack: true,
+            ..worker_config("acks")
+        },
+        queue.clone(),
+    ));
+    worker.register_task::<NeverAcked>().await;
+    let task_def = TaskDefinition::new(&NeverAcked, "acks".to_string()).unwrap();
+    queue.submit_task(task_def.clone()).await.unwrap();
+
+    let running = start(&worker);
+    let failed = wait_for_status(&queue, task_def.id, &[TaskStatus::Failed]).await;
+    stop(&worker, running).await;
+
+    assert_eq!(failed.reclaim_count, 3);
+    assert_eq!(failed.retry_count, 0);
+    assert!(failed.error.unwrap().contains("3 redeliveries"));
+}
+
+#[tokio::test]
+async fn equal_priority_tasks_are_dequeued_in_submission_order() {
+    let queue = TaskQueue::new_in_memory(Default::default()).unwrap();
+    let submitted: Vec<_> = (0..3)
+        .map(|n| TaskDefinition::new(&Double { n }, "fifo".to_string()).unwrap())
+        .collect();
+    for task_def in &submitted {
+        queue.submit_task(task_def.clone()).await.unwrap();
+    }
+
+    for expected in &submitted {
+        let next = queue.get_next_task("fifo").await.unwrap().unwrap();
+        assert_eq!(next.id, expected.id);
+    }
+}
+
+static THROTTLED_RUNNING: AtomicUsize = AtomicUsize::new(0);
+static THROTTLED_PEAK: AtomicUsize = AtomicUsize::new(0);
+
+/// Sleeps briefly, recording how many copies of itself run at once
+#[derive(Debug, Serialize, Deserialize)]
+struct Throttled;
+
+#[async_trait::async_trait]
+impl Task for Throttled {
+    type Output = ();
+    type Error = TaskError;
+
+    async fn execute(&self) -> Result<Self::Output, Self::Error> {
+        let running = THROTTLED_RUNNING.fetch_add(1, Ordering::SeqCst) + 1;
+        THROTTLED_PEAK.fetch_max(running, Ordering::SeqCst);
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        THROTTLED_RUNNING.fetch_sub(1, Ordering::SeqCst);
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn capped_task_type_does_not_starve_the_tasks_behind_it() {
+    let queue = Arc::new(TaskQueue::new_in_memory(Default::default()).unwrap());
+    // Each capped task is back at the head of the queue when it's deferred
+    let throttled: Vec<_> = (0..5)
+        .map(|_| {
+            TaskDefinition::builder(&Throttled)
+                .unwrap()
+                .queue("capped")
+                .priority(TaskPriority::High)
+                .build()
+        })
+        .collect();
+    let halved: Vec<_> = (0..5)
+        .map(|n| TaskDefinition::new(&Halve { n: n * 2 }, "capped".to_string()).unwrap())
+        .collect();
+    for task_def in throttled.iter().chain(&halved) {
+        queue.submit_task(task_def.clone()).await.unwrap();
+    }
+
+    let worker = Arc::new(Worker::new(
+        WorkerConfig {
+            max_concurrent_tasks: 4,
+            per_type_concurrency: HashMap::from([(throttled[0].name.clone(), 1)]),
+            ..worker_config("capped")
+        },
+        queue.clone(),
+    ));
+    worker.register_task::<Throttled>().await;
+    worker.register_task::<Halve>().await;
+
+    let running = start(&worker);
+    for task_def in &halved {
+        wait_for_status(&queue, task_def.id, &[TaskStatus::Success]).await;
+    }
+    // The capped tasks run one at a time, so most are still held
+    let mut throttled_done = 0;
+    for task_def in &throttled {
+        if queue.get_task(task_def.id).await.unwrap().unwrap().status == TaskStatus::Success {
+            throttled_done += 1;
+        }
+    }
+    assert!(throttled_done < 3, "{} capped tasks finished first", throttled_done);
+    for task_def in &throttled {
+        wait_for_status(&queue, task_def.id, &[TaskStatus::Success]).await;
+    }
+    stop(&worker, running).await;
+
+    assert_eq!(THROTTLED_PEAK.load(Ordering::SeqCst), 1);
+}
+
+#[tokio::test]
+async fn redis_only_operations_fail_instead_of_doing_nothing() {
+    let queue = TaskQueue::new_in_memory(Default::default()).unwrap();
+    assert!(!queue.uses_redis());
+
+    let record = distributed_task_queue::queue::WorkerRecord {
+        worker_id: "w1".to_string(),
+        queues: vec!["default".to_string()],
+        active_tasks: 0,
+        last_heartbeat: chrono::Utc::now(),
+    };
+    assert!(matches!(queue.record_worker_heartbeat(&record, 30).await, Err(TaskError::Config { .. })));
+    assert!(matches!(queue.remove_worker("w1").await, Err(TaskError::Config { .. })));
+    assert!(matches!(queue.reclaim_stalled_tasks(60).await, Err(TaskError::Config { .. })));
+    assert!(matches!(queue.reclaim_dead_worker_tasks().await, Err(TaskError::Config { .. })));
+    assert!(matches!(queue.cleanup_expired_tasks().await, Err(TaskError::Config { .. })));
+    assert!(matches!(queue.pause_queue("default").await, Err(TaskError::Config { .. })));
+}
+
+#[tokio::test]
+async fn worker_is_healthy_once_it_has_polled() {
+    let queue = Arc::new(TaskQueue::new_in_memory(Default::default()).unwrap());
+    let worker = Arc::new(Worker::new(worker_config("health"), queue));
+    assert!(!worker.is_healthy().await);
+
+    let running = start(&worker);
+    tokio::time::timeout(Duration::from_secs(5), async {
+        while !worker.is_healthy().await {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    })
+    .await
+    .expect("worker never became healthy");
+    stop(&worker, running).await;
+}
+
+#[tokio::test]
+async fn nothing_polls_the_queue_once_start_returns() {
+    let queue = Arc::new(TaskQueue::new_in_memory(Default::default()).unwrap());
+    let worker = Arc::new(Worker::new(worker_config("stopped"), queue.clone()));
+    worker.register_task::<Double>().await;
+
+    let running = start(&worker);
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    stop(&worker, running).await;
+
+    // No loop is left to pick this up
+    let task_def = TaskDefinition::new(&Double { n: 1 }, "stopped".to_string()).unwrap();
+    queue.submit_task(task_def.clone()).await.unwrap();
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    assert_eq!(queue.get_stats("stopped").await.unwrap().pending_tasks, 1);
+    assert_eq!(queue.get_task(task_def.id).await.unwrap().unwrap().status, TaskStatus::Pending);
+}
+
+/// Handler that panics on every task it runs
+struct Panicking;
+
+#[async_trait::async_trait]
+impl TaskHandler for Panicking {
+    fn can_handle(&self, _task_name: &str) -> bool {
+        true
+    }
+
+    async fn handle(&self, _task_data: &str) -> TaskResult<String> {
+        panic!("boom")
+    }
+}
+
+#[tokio::test]
+async fn panicking_handler_fails_the_attempt_and_frees_the_task() {
+    let queue = Arc::new(TaskQueue::new_in_memory(Default::default()).unwrap());
+    let worker = Arc::new(Worker::new(worker_config("panics"), queue.clone()));
+    let task_def = TaskDefinition::new(&Double { n: 1 }, "panics".to_string()).unwrap();
+    worker.register_handler(task_def.name.clone(), Panicking).await;
+    queue.submit_task(task_def.clone()).await.unwrap();
+
+    let running = start(&worker);
+    let retrying = wait_for_status(&queue, task_def.id, &[TaskStatus::Retrying]).await;
+    stop(&worker, running).await;
+
+    // Waiting out its retry backoff, not stuck in processing
+    assert!(retrying.attempts[0].error.contains("handler panicked: boom"));
+    assert_eq!(queue.get_stats("panics").await.unwrap().processing_tasks, 0);
+    let stats = worker.get_stats().await;
+    assert_eq!((stats.tasks_retried, stats.tasks_failed), (1, 0));
+}
+
+/// Fails the way a handler does when its own Redis server is unreachable
+#[derive(Debug, Serialize, Deserialize)]
+struct LosesConnection;
+
+#[async_trait::async_trait]
+impl Task for LosesConnection {
+    type Output = ();
+    type Error = TaskError;
+
+    async fn execute(&self) -> Result<Self::Output, Self::Error> {
+        Err(TaskError::Redis(redis::RedisError::from(std::io::Error::new(
+            std::io::ErrorKind::ConnectionRefused,
+            "connection refused",
+        ))))
+    }
+}
+
+#[tokio::test]
+async fn task_connection_error_fails_the_task_while_the_queue_is_up() {
+    let queue = Arc::new(TaskQueue::new_in_memory(Default::default()).unwrap());
+    let worker = Arc::new(Worker::new(worker_config("connections"), queue.clone()));
+    worker.register_task::<LosesConnection>().await;
+    let task_def = TaskDefinition::builder(&LosesConnection)
+        .unwrap()
+        .queue("connections")
+        .retry_config(RetryConfig {
+            max_retries: 0,
+            ..Default::default()
+        })
+        .build();
+    queue.submit_task(task_def.clone()).await.unwrap();
+
+    let running = start(&worker);
+    let failed = wait_for_status(&queue, task_def.id, &[TaskStatus::Failed]).await;
+    stop(&worker, running).await;
+
+    assert!(failed.error.unwrap().contains("connection refused"));
+}
+
+#[tokio::test]
+async fn queue_defaults_leave_explicit_settings_alone() {
+    let mut config = distributed_task_queue::TaskQueueConfig::default();
+    config.queue_defaults.insert(
+        "defaults".to_string(),
+        distributed_task_queue::QueueDefaults {
+            priority: Some(TaskPriority::High),
+            retry_config: Some(RetryConfig {
+                max_retries: 7,
+                ..Default::default()
+            }),
+        },
+    );
+    let queue = TaskQueue::new_in_memory(config).unwrap();
+    let unset = TaskDefinition::new(&Double { n: 1 }, "defaults".to_string()).unwrap();
+    let explicit = TaskDefinition::builder(&Double { n: 2 })
+        .unwrap()
+        .queue("defaults")
+        .priority(TaskPriority::Normal)
+        .retry_config(RetryConfig::default())
+        .build();
+    for task_def in [&unset, &explicit] {
+        queue.submit_task(task_def.clone()).await.unwrap();
+    }
+
+    let unset = queue.get_task(unset.id).await.unwrap().unwrap();
+    assert_eq!(unset.priority, TaskPriority::High);
+    assert_eq!(unset.retry_config.max_retries, 7);
+    let explicit = queue.get_task(explicit.id).await.unwrap().unwrap();
+    assert_eq!(explicit.priority, TaskPriority::Normal);
+    assert_eq!(explicit.retry_config, RetryConfig::default());
+}
@@ -0,0 +1,374 @@
+//! Queue tests against a live Redis server
+//!
+//! Run with `REDIS_URL=redis://127.0.0.1:6379 cargo test -- --ignored`. Every
+//! test uses its own key prefix, so they can share a database.
+
+use distributed_task_queue::{Task, TaskDefinition, TaskError, TaskPriority, TaskQueue, TaskQueueConfig, TaskStatus};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Job {
+    n: u32,
+    resources: Vec<String>,
+}
+
+#[async_trait::async_trait]
+impl Task for Job {
+    type Output = u32;
+    type Error = TaskError;
+
+    async fn execute(&self) -> Result<Self::Output, Self::Error> {
+        Ok(self.n)
+    }
+
+    fn required_resources(&self) -> Vec<String> {
+        self.resources.clone()
+    }
+}
+
+fn job(n: u32, resources: &[&str]) -> TaskDefinition {
+    let job = Job {
+        n,
+        resources: resources.iter().map(|r| r.to_string()).collect(),
+    };
+    TaskDefinition::new(&job, "default".to_string()).unwrap()
+}
+
+fn redis_url() -> String {
+    std::env::var("REDIS_URL").unwrap_or_else(|_| "redis://127.0.0.1:6379".to_string())
+}
+
+fn unique_prefix() -> String {
+    format!("dtq-test-{}", uuid::Uuid::new_v4())
+}
+
+async fn queue_with_prefix(key_prefix: &str) -> TaskQueue {
+    let config = TaskQueueConfig {
+        redis_url: redis_url(),
+        key_prefix: key_prefix.to_string(),
+        ..Default::default()
+    };
+    TaskQueue::new(config).await.unwrap()
+}
+
+async fn redis_queue() -> TaskQueue {
+    queue_with_prefix(&unique_prefix()).await
+}
+
+async fn complete_next(queue: &TaskQueue) -> TaskDefinition {
+    let mut task_def = queue.get_next_task("default").await.unwrap().unwrap();
+    task_def.mark_started("worker-1".to_string());
+    queue.mark_task_started(&task_def).await.unwrap();
+    task_def.mark_success_serialized("1".to_string());
+    queue.mark_task_completed(&task_def).await.unwrap();
+    task_def
+}
+
+#[tokio::test]
+#[ignore = "needs Redis"]
+async fn busy_resources_do_not_block_independent_tasks() {
+    let queue = redis_queue().await;
+    let dependent: Vec<_> = (0..3).map(|n| job(n, &["db"])).collect();
+    let independent: Vec<_> = (10..12).map(|n| job(n, &[])).collect();
+    for task_def in dependent.iter().chain(&independent) {
+        queue.submit_task(task_def.clone()).await.unwrap();
+    }
+
+    // "db" has capacity 1, so only the first dependent task is claimed and
+    // the independent ones behind the rest still run alongside it
+    let first = queue.get_next_task("default").await.unwrap().unwrap();
+    assert_eq!(first.id, dependent[0].id);
+    let mut alongside = Vec::new();
+    while let Some(task_def) = queue.get_next_task("default").await.unwrap() {
+        alongside.push(task_def.id);
+    }
+    assert_eq!(alongside, vec![independent[0].id, independent[1].id]);
+
+    // Each release lets exactly one more dependent task through
+    let mut current = first;
+    for expected in &dependent[1..] {
+        queue.release_resources(&current).await.unwrap();
+        let next = queue.get_next_task("default").await.unwrap().unwrap();
+        assert_eq!(next.id, expected.id);
+        assert!(queue.get_next_task("default").await.unwrap().is_none());
+        current = next;
+    }
+}
+
+#[tokio::test]
+#[ignore = "needs Redis"]
+async fn migrate_namespace_moves_every_key() {
+    let (from, to) = (unique_prefix(), unique_prefix());
+    let old = queue_with_prefix(&from).await;
+    let queued = job(1, &[]);
+    old.submit_task(job(2, &[])).await.unwrap();
+    old.submit_task(queued.clone()).await.unwrap();
+    let mut running = old.get_next_task("default").await.unwrap().unwrap();
+    running.mark_started("worker-1".to_string());
+    old.mark_task_started(&running).await.unwrap();
+
+    let migration = old.migrate_namespace(&from, &to).await.unwrap();
+    assert!(!migration.dry_run);
+    assert!(migration.conflicts.is_empty());
+    assert!(!migration.moved.is_empty());
+    for (old_key, new_key) in &migration.moved {
+        assert!(old_key.starts_with(&format!("{}:", from)));
+        assert_eq!(new_key[to.len()..], old_key[from.len()..]);
+    }
+
+    let new = queue_with_prefix(&to).await;
+    assert_eq!(new.get_stats("default").await.unwrap().pending_tasks, 1);
+    assert!(new.get_task(queued.id).await.unwrap().is_some());
+    let processing = new.list_processing_tasks(10).await.unwrap();
+    assert!(processing.iter().any(|(task_def, _)| task_def.id == running.id));
+    assert_eq!(old.get_stats("default").await.unwrap().pending_tasks, 0);
+    assert!(old.get_task(queued.id).await.unwrap().is_none());
+    assert!(old.migrate_namespace_dry_run(&from, &to).await.unwrap().moved.is_empty());
+}
+
+#[tokio::test]
+#[ignore = "needs Redis"]
+async fn submit_new_task_rejects_the_id_of_a_failed_task() {
+    let queue = redis_queue().await;
+    let task_id = distributed_task_queue::TaskId::new_v4();
+    let task_def = TaskDefinition::with_id(&Job { n: 1, resources: Vec::new() }, "default".to_string(), task_id).unwrap();
+    queue.submit_new_task(task_def.clone()).await.unwrap();
+
+    let mut claimed = queue.get_next_task("default").await.unwrap().unwrap();
+    claimed.mark_failed("boom");
+    queue.mark_task_failed(&claimed).await.unwrap();
+
+    let resubmitted = queue.submit_new_task(task_def).await;
+    assert!(matches!(resubmitted, Err(TaskError::TaskExists { .. })), "{:?}", resubmitted);
+}
+
+#[tokio::test]
+#[ignore = "needs Redis"]
+async fn status_index_drops_finished_tasks_after_their_ttl() {
+    let config = TaskQueueConfig {
+        redis_url: redis_url(),
+        key_prefix: unique_prefix(),
+        result_ttl: 1,
+        ..Default::default()
+    };
+    let queue = TaskQueue::new(config).await.unwrap();
+    queue.submit_task(job(1, &[])).await.unwrap();
+    queue.submit_task(job(2, &[])).await.unwrap();
+
+    let first = complete_next(&queue).await;
+    let running = queue.list_tasks_by_status("default", TaskStatus::Running, 10).await.unwrap();
+    assert!(running.is_empty());
+    let listed = queue.list_tasks_by_status("default", TaskStatus::Success, 10).await.unwrap();
+    assert_eq!(listed.iter().map(|t| t.id).collect::<Vec<_>>(), vec![first.id]);
+
+    tokio::time::sleep(std::time::Duration::from_millis(2100)).await;
+    let second = complete_next(&queue).await;
+    let listed = queue.list_tasks_by_status("default", TaskStatus::Success, 10).await.unwrap();
+    assert_eq!(listed.iter().map(|t| t.id).collect::<Vec<_>>(), vec![second.id]);
+}
+
+#[tokio::test]
+#[ignore = "needs Redis"]
+async fn group_finalizer_is_enqueued_before_the_group_is_deleted() {
+    let prefix = unique_prefix();
+    let queue = std::sync::Arc::new(queue_with_prefix(&prefix).await);
+    let client = distributed_task_queue::TaskClient::from_queue(queue.clone());
+    let members = [Job { n: 1, resources: Vec::new() }, Job { n: 2, resources: Vec::new() }];
+    let group = distributed_task_queue::TaskGroup::new(&members)
+        .unwrap()
+        .with_finalizer(&Job { n: 3, resources: Vec::new() })
+        .unwrap();
+    let group_id = client.submit_group(group, "default").await.unwrap();
+
+    let mut redis = redis::Client::open(redis_url()).unwrap().get_async_connection().await.unwrap();
+    let group_key = format!("{}:group:{}", prefix, group_id);
+    let ttl: i64 = redis::cmd("TTL").arg(&group_key).query_async(&mut redis).await.unwrap();
+    assert!(ttl > 0, "group hash has no TTL ({})", ttl);
+
+    complete_next(&queue).await;
+    let results_ttl: i64 = redis::cmd("TTL")
+        .arg(format!("{}:results", group_key))
+        .query_async(&mut redis)
+        .await
+        .unwrap();
+    assert!(results_ttl > 0, "group results have no TTL ({})", results_ttl);
+    complete_next(&queue).await;
+
+    let finalizer = queue.get_next_task("default").await.unwrap().unwrap();
+    assert!(finalizer.data.contains("group_results"));
+    let exists: u32 = redis::cmd("EXISTS")
+        .arg(&group_key)
+        .arg(format!("{}:results", group_key))
+        .query_async(&mut redis)
+        .await
+        .unwrap();
+    assert_eq!(exists, 0);
+}
+
+#[tokio::test]
+#[ignore = "needs Redis"]
+async fn equal_priority_tasks_are_dequeued_in_submission_order() {
+    let queue = redis_queue().await;
+    let submitted: Vec<_> = (0..3).map(|n| job(n, &[])).collect();
+    for task_def in &submitted {
+        queue.submit_task(task_def.clone()).await.unwrap();
+    }
+
+    for expected in &submitted {
+        let next = queue.get_next_task("default").await.unwrap().unwrap();
+        assert_eq!(next.id, expected.id);
+    }
+}
+
+#[tokio::test]
+#[ignore = "needs Redis"]
+async fn due_scheduled_tasks_keep_their_priority() {
+    let queue = redis_queue().await;
+    let normal = job(1, &[]);
+    queue.submit_task(normal.clone()).await.unwrap();
+    let critical = TaskDefinition::builder(&Job { n: 2, resources: Vec::new() })
+        .unwrap()
+        .queue("default")
+        .priority(TaskPriority::Critical)
+        .scheduled_at(chrono::Utc::now() - chrono::Duration::seconds(1))
+        .build();
+    queue.submit_scheduled_task(critical.clone()).await.unwrap();
+
+    assert_eq!(queue.process_scheduled_tasks(10).await.unwrap(), 1);
+    assert_eq!(queue.get_next_task("default").await.unwrap().unwrap().id, critical.id);
+    assert_eq!(queue.get_next_task("default").await.unwrap().unwrap().id, normal.id);
+}
+
+#[tokio::test]
+#[ignore = "needs Redis"]
+async fn results_indexed_before_the_expiry_set_are_still_counted() {
+    let prefix = unique_prefix();
+    let queue = queue_with_prefix(&prefix).await;
+    queue.submit_task(job(1, &[])).await.unwrap();
+    queue.submit_task(job(2, &[])).await.unwrap();
+    let first = complete_next(&queue).await;
+
+    // Older versions kept only the store-time index
+    let expiry_key = format!("{}:results:expiry:default", prefix);
+    let mut conn = redis::Client::open(redis_url()).unwrap().get_async_connection().await.unwrap();
+    redis::cmd("DEL").arg(&expiry_key).query_async::<_, ()>(&mut conn).await.unwrap();
+
+    complete_next(&queue).await;
+    let expires_at: Option<f64> = redis::cmd("ZSCORE")
+        .arg(&expiry_key)
+        .arg(first.id.to_string())
+        .query_async(&mut conn)
+        .await
+        .unwrap();
+    assert!(expires_at.unwrap() > chrono::Utc::now().timestamp() as f64);
+    assert_eq!(queue.get_stats("default").await.unwrap().stored_results, 2);
+}
+
+#[tokio::test]
+#[ignore = "needs Redis"]
+async fn result_cap_keeps_only_the_newest_results() {
+    let config = TaskQueueConfig {
+        redis_url: redis_url(),
+        key_prefix: unique_prefix(),
+        max_results_per_queue: Some(3),
+        ..Default::default()
+    };
+    let queue = TaskQueue::new(config).await.unwrap();
+    for n in 0..5 {
+        queue.submit_task(job(n, &[])).await.unwrap();
+    }
+
+    let mut completed = Vec::new();
+    for _ in 0..5 {
+        completed.push(complete_next(&queue).await);
+        // Store times have millisecond resolution
+        tokio::time::sleep(std::time::Duration::from_millis(2)).await;
+    }
+    for evicted in &completed[..2] {
+        assert!(queue.get_result(evicted.id).await.unwrap().is_none());
+        assert!(queue.get_task(evicted.id).await.unwrap().is_none());
+    }
+    for kept in &completed[2..] {
+        assert!(queue.get_result(kept.id).await.unwrap().is_some());
+    }
+    assert_eq!(queue.get_stats("default").await.unwrap().stored_results, 3);
+}
+
+#[tokio::test]
+#[ignore = "needs Redis"]
+async fn result_cap_evicts_the_oldest_whatever_their_ttl() {
+    let config = TaskQueueConfig {
+        redis_url: redis_url(),
+        key_prefix: unique_prefix(),
+        max_results_per_queue: Some(2),
+        ..Default::default()
+    };
+    let queue = TaskQueue::new(config).await.unwrap();
+    let with_ttl = |n, ttl| {
+        TaskDefinition::builder(&Job { n, resources: Vec::new() })
+            .unwrap()
+            .queue("default")
+            .result_ttl(ttl)
+            .build()
+    };
+    // Oldest first: a week-long result, one kept until evicted, a short one
+    for task_def in [with_ttl(1, 7 * 86400), with_ttl(2, 0), with_ttl(3, 60)] {
+        queue.submit_task(task_def).await.unwrap();
+    }
+
+    let mut completed = Vec::new();
+    for _ in 0..3 {
+        completed.push(complete_next(&queue).await);
+        tokio::time::sleep(std::time::Duration::from_millis(2)).await;
+    }
+    let [oldest, kept, newest] = &completed[..] else { unreachable!() };
+    assert!(queue.get_result(oldest.id).await.unwrap().is_none());
+    assert!(queue.get_result(kept.id).await.unwrap().is_some());
+    assert!(queue.get_result(newest.id).await.unwrap().is_some());
+    assert_eq!(queue.get_stats("default").await.unwrap().stored_results, 2);
+}
+
+#[tokio::test]
+#[ignore = "needs Redis"]
+async fn migrate_namespace_treats_the_prefix_literally() {
+    let base = unique_prefix();
+    let (glob, neighbour, to) = (format!("{}-*", base), format!("{}-x", base), unique_prefix());
+    let globbed = queue_with_prefix(&glob).await;
+    let bystander = queue_with_prefix(&neighbour).await;
+    globbed.submit_task(job(1, &[])).await.unwrap();
+    let untouched = job(2, &[]);
+    bystander.submit_task(untouched.clone()).await.unwrap();
+
+    let migration = globbed.migrate_namespace(&glob, &to).await.unwrap();
+    assert!(!migration.moved.is_empty());
+    assert!(migration.moved.iter().all(|(old_key, _)| old_key.starts_with(&format!("{}:", glob))));
+    assert!(bystander.get_task(untouched.id).await.unwrap().is_some());
+    assert_eq!(bystander.get_stats("default").await.unwrap().pending_tasks, 1);
+}
+
+/// Timing comparison rather than a correctness check; run it with
+/// `--ignored --nocapture` to see the numbers
+#[tokio::test]
+#[ignore = "needs Redis"]
+async fn submit_tasks_beats_one_submit_per_task_for_10k_tasks() {
+    const TASKS: u32 = 10_000;
+
+    let looped = redis_queue().await;
+    let started = std::time::Instant::now();
+    for n in 0..TASKS {
+        looped.submit_task(job(n, &[])).await.unwrap();
+    }
+    let per_task = started.elapsed();
+
+    let batched = redis_queue().await;
+    let task_defs: Vec<_> = (0..TASKS).map(|n| job(n, &[])).collect();
+    let started = std::time::Instant::now();
+    let ids = batched.submit_tasks(task_defs).await.unwrap();
+    let batch = started.elapsed();
+
+    eprintln!("{} tasks: submit_task loop {:?}, submit_tasks {:?}", TASKS, per_task, batch);
+    assert_eq!(ids.len(), TASKS as usize);
+    assert_eq!(batched.get_stats("default").await.unwrap().pending_tasks, TASKS as u64);
+    assert_eq!(looped.get_stats("default").await.unwrap().pending_tasks, TASKS as u64);
+    assert!(batch < per_task, "batch {:?} vs loop {:?}", batch, per_task);
+}